@@ -1,8 +1,10 @@
 
 use serenity::all::UserId;
 use nonempty::{NonEmpty, nonempty};
+use chrono::Utc;
 
 use crate::commands::command::*;
+use crate::commands::CommandStatsCommand;
 use crate::utility::*;
 use crate::databases::*;
 
@@ -25,24 +27,46 @@ impl Command for AboutCommand {
                 let message = &params.message;
 
                 // get uptime and bot_id from database
-                let query = ConfigDB::get_instance()
-                    .get_multiple(vec!["uptime", "bot_id", "command_prefix", "executed_commands"]).await.unwrap();
-                let uptime = query[0].to_string().parse::<i64>().unwrap();
-                let bot_id: UserId = query[1].clone().into();
+                let query = match ConfigDB::get_instance().scope(message)
+                    .get_multiple(vec!["uptime", "bot_id", "command_prefix", "executed_commands"]).await {
+                    Ok(query) => query,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let uptime = query[0].to_string().parse::<i64>().unwrap_or(0);
+                let bot_id: UserId = match query[1].clone().try_into() {
+                    Ok(bot_id) => bot_id,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let bot = &message.get_resolver().resolve_user(bot_id).await.unwrap();
                 let bot_name = &message.get_resolver().resolve_name(bot);
 
+                // the most used commands of the last week
+                let week = Query::new()
+                    .after(Utc::now().timestamp() - 7 * 24 * 60 * 60);
+                let uses = match CommandUsesDB::get_instance().scope(message).query(week).await {
+                    Ok(uses) => uses,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let most_used = CommandStatsCommand::top(uses.iter().map(|command_use| command_use.key.clone()), 3)
+                    .iter()
+                    .map(|(trigger, count)| format!("`{}` ({})", trigger, count))
+                    .collect::<Vec<String>>();
+                let most_used = match most_used.is_empty() {
+                    true  => message.text("about.none").await,
+                    false => most_used.join(", "),
+                };
+
                 // create embed
                 let embed = message.get_log_builder()
+                    .for_author()
                     .target(bot)
-                    .title(&format!("About {}", bot_name))
-                    .description(
-                        format!(
-                            "{} is a powerful Discord bot which runs moderation, tickets, and other miscellaneous tasks for the Kalopsia Discord server.", 
-                            bot_name))
-                    .labeled_timestamp("Online Since", uptime)
-                    .arbitrary("Prefix", format!("`{}`", query[2].to_string()))
-                    .arbitrary("Executed Commands", format!("`{}`", query[3].to_string()))
+                    .title(text!("about.title", bot = bot_name))
+                    .description(text!("about.description", bot = bot_name))
+                    .labeled_timestamp("about.online-since", uptime)
+                    .arbitrary("about.prefix", format!("`{}`", query[2]))
+                    .arbitrary("about.executed-commands", format!("`{}`", query[3]))
+                    .arbitrary("about.commands-this-week", format!("`{}`", uses.len()))
+                    .arbitrary("about.most-used", most_used)
                     .build().await;
 
                 let _ = message.reply(embed).await;
@@ -11,20 +11,16 @@ pub struct AddEmojiCommand;
 
 impl Command for AddEmojiCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Casual,
             nonempty!["add-emoji".to_string(), "addemoji".to_string(),
         ])
-            .add_required("emoji-id")
+            .level(PermissionLevel::HeadMod)
+            .add_required("emoji", ArgumentType::Text)
             .new_usage()
-            .add_required(vec!["name", "img-url"])
+            .add_required("name", ArgumentType::Text)
+            .add_required("img-url", ArgumentType::Text)
             .example("<:pandauwu:1259245515309060238>")
     }
 
@@ -54,12 +50,12 @@ impl Command for AddEmojiCommand {
                         Some(emoji) => {
 
                             if emoji.animated && emojis_animated >= 250 {
-                                message.reply_failure("Cannot add more animated emojis.").await;
+                                message.reply_failure("add-emoji.animated-full").await;
                                 return;
                             }
 
                             if !emoji.animated && emojis_regular >= 250 {
-                                message.reply_failure("Cannot add more regular emojis.").await;
+                                message.reply_failure("add-emoji.regular-full").await;
                                 return;
                             }
 
@@ -76,14 +72,14 @@ impl Command for AddEmojiCommand {
                                 let add = guild.create_emoji(&message, &emoji.name, &attachment.to_base64()).await;
                                 match add {
                                     Ok(_)  => message.reply_success().await,
-                                    Err(_) => message.reply_failure("Failed to add emoji.").await,
+                                    Err(_) => message.reply_failure("add-emoji.failed").await,
                                 };
                             }
                         },
                         None => {
 
                             if emojis_regular >= 250 {
-                                message.reply_failure("Cannot add more regular emojis.").await;
+                                message.reply_failure("add-emoji.regular-full").await;
                                 return;
                             }
 
@@ -117,7 +113,7 @@ impl Command for AddEmojiCommand {
                                 let add = guild.create_emoji(&message, &name, &attachment.to_base64()).await;
                                 match add {
                                     Ok(_)  => message.reply_success().await,
-                                    Err(_) => message.reply_failure("Failed to add emoji.").await,
+                                    Err(_) => message.reply_failure("add-emoji.failed").await,
                                 };
                             }
                         }
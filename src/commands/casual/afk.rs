@@ -15,7 +15,7 @@ impl Command for AfkCommand {
             CommandType::Casual,
             nonempty!["afk".to_string()]
         )
-            .add_optional("message (max 154 characters)")
+            .add_optional("message (max 154 characters)", ArgumentType::Text)
             .example("I am going afk now :)")
     }
 
@@ -24,15 +24,17 @@ impl Command for AfkCommand {
             async move {
 
                 let message = &params.message;
-                let content = &message.payload(None, None);
+                let content = params.arguments.text("message").unwrap_or("");
 
                 if content.len() >= 154 {
                     self.invalid_usage(params).await;
                     return;
                 }
 
-                AfkDB::get_instance()
-                    .set(&message.get_author().id.to_string(), content).await;
+                if let Err(err) = AfkDB::get_instance().scope(message)
+                    .set(&message.get_author().id.to_string(), content).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 message.reply_success().await;
             }
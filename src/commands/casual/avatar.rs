@@ -14,7 +14,7 @@ impl Command for AvatarCommand {
             CommandType::Casual,
             nonempty!["av".to_string(), "avatar".to_string()]
         )
-            .add_required("user")
+            .add_required("user", ArgumentType::User)
             .example("@Poggy")
     }
 
@@ -25,7 +25,8 @@ impl Command for AvatarCommand {
                 let target = params.target.unwrap();
 
                 let embed = message.get_log_builder()
-                    .title(&format!("{}'s avatar", message.get_resolver().resolve_name(&target)))
+                    .for_author()
+                    .title(text!("avatar.title", user = message.get_resolver().resolve_name(&target)))
                     .image(target.face())
                     .target(&target)
                     .no_thumbnail()
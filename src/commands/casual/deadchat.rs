@@ -7,6 +7,7 @@ use chrono::Utc;
 use std::str::FromStr;
 
 use crate::commands::command::{CommandType, Command, CommandParams};
+use crate::commands::middleware::{Middleware, Cooldown, CooldownBucket};
 use crate::utility::*;
 use crate::databases::*;
 
@@ -15,19 +16,18 @@ pub struct DeadchatCommand;
 
 impl Command for DeadchatCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            let top_kalie = &message.get_resolver().resolve_role("Top Kalie").await.unwrap()[0];
-            message.is_trial().await || message.has_role(top_kalie).await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Casual,
             nonempty!["dcp".to_string(), "deadchat".to_string()]
         )
-            .add_optional("message")
+            .level(PermissionLevel::Trusted)
+            .add_optional("message", ArgumentType::Text)
+    }
+
+    // dead chat pings ping a role, so every user may only ping every 10 minutes
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        vec![Box::new(Cooldown::new(CooldownBucket::User, 10 * 60))]
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -36,30 +36,17 @@ impl Command for DeadchatCommand {
 
                 let message = &params.message;
                 let author = &message.get_author().id.to_string();
-                let content = message.payload(None, None);
-                let content = match content.is_empty() {
-                    true => "".to_string(),
-                    false => format!(" `>` {}", content)
+                let content = match params.arguments.text("message") {
+                    Some(content) => format!(" `>` {}", content),
+                    None => "".to_string(),
                 };
 
-                let last_dcp = DeadchatDB::get_instance()
-                    .get_last(author, 1).await.unwrap();
-
-                // You can only dcp every 10 minutes to rate limit pings
-                if let Some(dcp) = last_dcp.first() {
-                    let now = chrono::Utc::now().timestamp();
-                    let next_dcp = dcp.timestamp + 10 * 60;
-                    if now < next_dcp {
-                        let _ = message.reply_failure(
-                            &format!("You can ping deadchat again <t:{}:R>", next_dcp)).await;
-                        return;
-                    }
-                }
-
                 // resolve role and channel
                 let role_dcp = message.get_resolver().resolve_role("Dead Chat").await.unwrap()[0].id;
-                let channel_welcome: ChannelId = ConfigDB::get_instance()
-                    .get("channel_welcome").await.unwrap().into();
+                let channel_welcome: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_welcome,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // create dcp message
                 let allowed_mentions = CreateAllowedMentions::new()
@@ -68,10 +55,6 @@ impl Command for DeadchatCommand {
                     .content(format!("<@&{}> {} - ***<@{}>***", role_dcp.to_string(), content, author))
                     .allowed_mentions(allowed_mentions);
 
-                // log last dcp
-                DeadchatDB::get_instance()
-                    .set(author, &content).await;
-
                 // send to general channel
                 let _ = channel_welcome.send_message(&message, dcp).await;
 
@@ -14,7 +14,7 @@ impl Command for InfoCommand {
             CommandType::Casual,
             nonempty!["info".to_string()]
         )
-            .add_required("user")
+            .add_required("user", ArgumentType::User)
             .example("@Poggy")
     }
 
@@ -26,16 +26,17 @@ impl Command for InfoCommand {
                 let target  = &params.target.unwrap();
 
                 let mut embed = message.get_log_builder()
+                    .for_author()
                     .target(target)
-                    .title(&format!("{}'s Information", message.get_resolver().resolve_name(target)))
-                    .arbitrary("Handle", &format!("<@{}>", target.id.to_string()))
-                    .labeled_timestamp("Created At", target.created_at().unix_timestamp());
+                    .title(text!("info.title", user = message.get_resolver().resolve_name(target)))
+                    .arbitrary("info.handle", format!("<@{}>", target.id))
+                    .labeled_timestamp("info.created-at", target.created_at().unix_timestamp());
 
                 let member = message.get_resolver().resolve_member(target).await;
                 if let Some(member) = member {
 
                     if let Some(timestamp) = member.joined_at {
-                        embed = embed.labeled_timestamp("Joined At", timestamp.unix_timestamp());
+                        embed = embed.labeled_timestamp("info.joined-at", timestamp.unix_timestamp());
                     }
 
                     let roles = member.roles.iter()
@@ -44,8 +45,8 @@ impl Command for InfoCommand {
                         .join(", ");
 
                     embed = match roles.is_empty() {
-                        true  => embed.arbitrary("Roles", "None"),
-                        false => embed.arbitrary("Roles", roles)
+                        true  => embed.arbitrary("info.roles", message.text("info.no-roles").await),
+                        false => embed.arbitrary("info.roles", roles)
                     };
                 }
 
@@ -0,0 +1,78 @@
+
+use nonempty::{NonEmpty, nonempty};
+use strum::IntoEnumIterator;
+
+use std::str::FromStr;
+
+use crate::commands::command::*;
+use crate::utility::*;
+use crate::databases::*;
+
+
+pub struct LanguageCommand;
+
+impl Command for LanguageCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Casual,
+            nonempty!["language".to_string(), "lang".to_string()]
+        )
+            .new_usage()
+            .add_optional("language", ArgumentType::Text)
+            .new_usage()
+            .add_constant("-reset", false)
+            .example("de")
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+                let user_id = message.get_author().id.to_string();
+                let languages = Locale::iter()
+                    .map(|locale| format!("`{}` ({})", locale, locale.name()))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                // follow the language of the guild again
+                if params.arguments.has("reset") {
+                    if let Err(err) = LocalesDB::get_instance().global().delete(&user_id).await {
+                        return message.reply_db_error(err).await;
+                    }
+                    let guild_locale = Locale::of_guild(message).await;
+                    let embed = message.create_text_embed("language.title",
+                        text!("language.reset", language = guild_locale.name())).await;
+                    let _ = message.reply(embed).await;
+                    return;
+                }
+
+                // display the current language
+                let Some(language) = params.arguments.text("language") else {
+                    let embed = message.create_text_embed("language.title", text!("language.current",
+                        language = message.locale().await.name(),
+                        languages = languages)).await;
+                    let _ = message.reply(embed).await;
+                    return;
+                };
+
+                let Ok(locale) = Locale::from_str(language) else {
+                    return message.reply_failure(text!("language.unknown",
+                        language = language,
+                        languages = languages)).await;
+                };
+
+                if let Err(err) = LocalesDB::get_instance().global().set(&user_id, locale.to_string()).await {
+                    return message.reply_db_error(err).await;
+                }
+
+                // already answered in the new language
+                let embed = message.create_text_embed("language.title",
+                    text!("language.changed", language = locale.name())).await;
+                let _ = message.reply(embed).await;
+            }
+        )
+    }
+
+}
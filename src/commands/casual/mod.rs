@@ -14,3 +14,4 @@ pub mod remind;
 pub mod remove_reminder;
 pub mod tweet;
 pub mod deadchat;
+pub mod language;
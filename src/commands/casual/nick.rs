@@ -10,19 +10,14 @@ pub struct NicknameCommand;
 
 impl Command for NicknameCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            let role = message.resolve_role("Level 10+").await.unwrap();
-            message.has_role(role).await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Casual,
             nonempty!["nick".to_string(), "nickname".to_string()]
         )
-            .add_required(vec!["user", "nickname"])
+            .level(PermissionLevel::Member)
+            .add_required("user", ArgumentType::User)
+            .add_required("nickname", ArgumentType::Text)
             .example("@Poggy Poggor")
     }
 
@@ -37,30 +32,30 @@ impl Command for NicknameCommand {
                 if !target_is_self {
 
                     // only staff can change others nicknames
-                    if !message.is_trial().await {
-                        message.reply_failure("You cannot change the nicknames of others.").await;
+                    if !message.has_level(PermissionLevel::Trial).await {
+                        message.reply_failure("nick.others").await;
                         return;
                     }
 
                     // staff changing other staffs nickname
-                    if message.get_resolver().is_trial(&target).await {
-                        message.reply_failure("You cannot change the nicknames of other staff.").await;
+                    if message.get_resolver().has_level(target, PermissionLevel::Trial).await {
+                        message.reply_failure("nick.staff").await;
                         return;
                     }
 
                 }
 
-                let nickname = message.payload_without_mentions(None, None);
+                let nickname = params.arguments.text("nickname").unwrap_or_default();
 
                 // cannot change nickname to empty
                 if nickname.is_empty() {
-                    message.reply_failure("No nickname given.").await;
+                    message.reply_failure("nick.empty").await;
                     return;
                 }
 
                 // nickname too long
                 if nickname.len() > 32 {
-                    message.reply_failure("Nickname can be at most 32 characters long.").await;
+                    message.reply_failure("nick.too-long").await;
                     return;
                 }
 
@@ -20,9 +20,7 @@ impl Command for PollCommand {
             nonempty!["poll".to_string()]
         )
             .add_constant("-title", true)
-            .add_constant("-opts", false)
-            .add_required("+option1")
-            .add_optional("+option2 .. +option9")
+            .add_required("-opts +option1 .. +option9", ArgumentType::Text)
             .example("-title What is the best color? -opts +Reddish Blue +Blue +Green")
     }
 
@@ -32,19 +30,8 @@ impl Command for PollCommand {
 
                 let message = &params.message;
 
-                if !message.has_parameter("title") {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-
-                if !message.has_parameter("opts") {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-
-                let title = message.get_parameter("title");
-                let payload = message.get_parameter("opts");
-                let options = payload
+                let title = params.arguments.text("title").unwrap_or_default().to_string();
+                let options = params.arguments.text("opts").unwrap_or_default()
                     .split("+")
                     .map(|option| option.trim())
                     .filter(|option| !option.is_empty())
@@ -73,8 +60,8 @@ impl Command for PollCommand {
 
                 let embed = message.get_log_builder()
                     .no_thumbnail()
-                    .title(title)
-                    .description(description)
+                    .title(Text::plain(title))
+                    .description(Text::plain(description))
                     .timestamp()
                     .build().await;
 
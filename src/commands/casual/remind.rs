@@ -15,7 +15,8 @@ impl Command for RemindCommand {
             CommandType::Casual,
             nonempty!["remind".to_string(), "remind-me".to_string(), "reminder".to_string()]
         )
-            .add_required(vec!["time", "message"])
+            .add_required("time", ArgumentType::Duration)
+            .add_required("message", ArgumentType::Text)
             .new_usage()
             .add_constant("-list", false)
             .example("1m30s Ninty later!")
@@ -26,22 +27,25 @@ impl Command for RemindCommand {
             async move {
 
                 let message = &params.message;
-                let list = message.has_parameter("list");
+                let list = params.arguments.has("list");
 
                 // list all reminders
                 if list {
 
-                    let reminders = RemindersDB::get_instance()
-                        .get_all(&message.get_author().id.to_string()).await.unwrap();
+                    let reminders = match RemindersDB::get_instance().scope(message).get_all(&message.get_author().id.to_string()).await {
+                        Ok(reminders) => reminders,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let description = match reminders.len() {
-                        0 => "You have no reminders.".to_string(),
-                        _ => format!("You have **{}** reminders.", reminders.len())
+                        0 => Text::new("remind.none"),
+                        _ => text!("remind.count", count = reminders.len()),
                     };
 
                     let name = message.resolve_name();
                     let mut builder = message.get_log_builder()
-                        .title(&format!("{}' Reminders", name))
-                        .description(&description)
+                        .for_author()
+                        .title(text!("remind.title", user = name))
+                        .description(description)
                         .no_thumbnail();
 
                     for reminder in reminders.iter() {
@@ -54,23 +58,14 @@ impl Command for RemindCommand {
                 // create reminder
                 else {
 
-                    let time = TimeDecorator::get_time(message).await;
-                    if time.is_none() {
-                        self.invalid_usage(params).await;
-                        return;
-                    }
-                    let time = time.unwrap();
+                    let time = params.arguments.duration("time").unwrap_or_default();
 
                     if time > 604_800 {
-                        message.reply_failure("Time can at most be 1 week.").await;
+                        message.reply_failure("remind.too-long").await;
                         return;
                     }
 
-                    let content = message.payload(None, Some(vec![time.to_string()]));
-                    if content.is_empty() {
-                        self.invalid_usage(params).await;
-                        return;
-                    }
+                    let content = params.arguments.text("message").unwrap_or_default().to_string();
 
                     // create log
                     let log = ScheduleLog::new(
@@ -80,8 +75,10 @@ impl Command for RemindCommand {
                     );
 
                     // append log
-                    RemindersDB::get_instance()
-                        .append(&message.get_author().id.to_string(), &log.into()).await;
+                    if let Err(err) = RemindersDB::get_instance().scope(message)
+                        .append(&message.get_author().id.to_string(), log).await {
+                        return message.reply_db_error(err).await;
+                    }
                     message.reply_success().await;
                 }
             }
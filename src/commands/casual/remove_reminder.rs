@@ -19,7 +19,7 @@ impl Command for RemoveReminderCommand {
             CommandType::Casual,
             nonempty!["remove-reminder".to_string()]
         )
-            .add_required("database ID")
+            .add_required("database ID", ArgumentType::Integer)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -27,21 +27,11 @@ impl Command for RemoveReminderCommand {
             async move {
 
                 let message = &params.message;
-                let id = message.payload(None, None);
+                let id = params.number.unwrap();
 
-                if id.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
+                if let Err(err) = RemindersDB::get_instance().scope(message).delete_by_id(id).await {
+                    return message.reply_db_error(err).await;
                 }
-                let id = id.parse::<i64>();
-
-                if id.is_err() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-                let id = id.unwrap();
-
-                RemindersDB::get_instance().delete_by_id(id).await;
 
                 message.reply_success().await;
             }
@@ -15,7 +15,8 @@ impl Command for ScheduleCommand {
             CommandType::Casual,
             nonempty!["schedule".to_string(),"later".to_string()]
         )
-            .add_required(vec!["time", "message"])
+            .add_required("time", ArgumentType::Duration)
+            .add_required("message", ArgumentType::Text)
             .example("1m30s Ninty seconds later!")
     }
 
@@ -27,15 +28,11 @@ impl Command for ScheduleCommand {
                 let time = params.time.unwrap();
 
                 if time > 86_400 {
-                    message.reply_failure("Time can at most be 1 day.").await;
+                    message.reply_failure("schedule.too-long").await;
                     return;
                 }
 
-                let content = message.payload(None, Some(vec![time.to_string()]));
-                if content.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
+                let content = params.arguments.text("message").unwrap_or_default().to_string();
 
                 // create log
                 let log = ScheduleLog::new(
@@ -45,8 +42,10 @@ impl Command for ScheduleCommand {
                 );
 
                 // append log
-                ScheduleDB::get_instance()
-                    .append(&message.get_author().id.to_string(), &log.into()).await;
+                if let Err(err) = ScheduleDB::get_instance().scope(message)
+                    .append(&message.get_author().id.to_string(), log).await {
+                    return message.reply_db_error(err).await;
+                }
                 message.reply_success().await;
             }
         )
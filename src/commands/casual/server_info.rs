@@ -35,26 +35,30 @@ impl Command for ServerInfoCommand {
                         .filter(|emoji| !emoji.animated).count();
 
                     // obtain the bot's user
-                    let bot_id: UserId = ConfigDB::get_instance()
-                        .get("bot_id").await.unwrap().into();
+                    let bot_id: UserId = match BotConfig::get(message).await {
+                        Ok(config) => config.bot_id,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
 
                     // obtain the owner
                     let owner = message.get_resolver().resolve_user(guild.owner_id).await.unwrap();
                     let owner_name = message.get_resolver().resolve_name(&owner);
 
+                    let emoji_counts = message.text(text!("server-info.emoji-counts",
+                        animated = emojis_animated, regular = emojis_regular, total = emojis.len())).await;
+
                     // create the embed
                     let embed = message.get_log_builder()
+                        .for_author()
                         .target(&bot)
-                        .title(guild.name)
-                        .labeled_timestamp("Server Creation", guild.id.created_at().unix_timestamp())
-                        .arbitrary("Owner", owner_name)
-                        .arbitrary("Roles", guild.roles.len().to_string())
-                        .arbitrary("Members", guild.member_count.to_string())
-                        .arbitrary("Channels", guild.channels.len().to_string())
-                        .arbitrary("Emojis", format!(
-                                "Animated: {} / 250\nRegular: {} / 250\nTotal: {} / 500",
-                                emojis_animated, emojis_regular, emojis.len()))
+                        .title(Text::plain(guild.name))
+                        .labeled_timestamp("server-info.creation", guild.id.created_at().unix_timestamp())
+                        .arbitrary("server-info.owner", owner_name)
+                        .arbitrary("server-info.roles", guild.roles.len().to_string())
+                        .arbitrary("server-info.members", guild.member_count.to_string())
+                        .arbitrary("server-info.channels", guild.channels.len().to_string())
+                        .arbitrary("server-info.emojis", emoji_counts)
                         .build().await;
 
                     let _ = message.reply(embed).await;
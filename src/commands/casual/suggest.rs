@@ -19,10 +19,8 @@ impl Command for SuggestCommand {
             CommandType::Casual,
             nonempty!["suggest".to_string()]
         )
-            .add_required("message")
-            .new_usage()
-            .add_required("message")
-            .add_constant("-event", false)
+            .add_required("message", ArgumentType::Text)
+            .add_optional("-event", ArgumentType::Flag)
             .example("Add unicorns to planet earth!")
     }
 
@@ -31,33 +29,27 @@ impl Command for SuggestCommand {
             async move {
 
                 let message = &params.message;
-                let content = message.payload(None, Some(vec!["event".to_string()]));
-
-                if content.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
+                let content = params.arguments.text("message").unwrap_or_default().to_string();
 
                 // create embed
+                let title = message.guild_text("suggest.title").await;
+                let footer = message.guild_text(text!("suggest.footer", user = message.resolve_name())).await;
                 let embed = MessageManager::create_embed(|embed| {
                     embed
-                        .title("Pending Suggestion")
+                        .title(title)
                         .description(&content)
-                        .footer(CreateEmbedFooter::new(
-                            format!("Suggested by {}", message.resolve_name())
-                        ))
+                        .footer(CreateEmbedFooter::new(footer))
                 }).await;
                 let reactions = vec![ReactionType::Unicode("✅".to_string()),
                                      ReactionType::Unicode("❌".to_string())];
                 let suggestion = embed.to_message().reactions(reactions);
 
                 // determine channel
-                let channel_name = match message.has_parameter("event") {
-                    true  => "channel_event_suggestions",
-                    false => "channel_suggestions"
+                let channel = match BotConfig::get(message).await {
+                    Ok(config) if params.arguments.has("event") => config.channel_event_suggestions,
+                    Ok(config) => config.channel_suggestions,
+                    Err(err) => return message.reply_db_error(err).await,
                 };
-                let channel: ChannelId = ConfigDB::get_instance()
-                    .get(channel_name).await.unwrap().into();
 
                 let _ = channel.send_message(&message, suggestion).await;
                 let _ = message.reply_success().await;
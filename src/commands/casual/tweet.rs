@@ -7,6 +7,7 @@ use chrono::Utc;
 use std::str::FromStr;
 
 use crate::commands::command::{CommandType, Command, CommandParams};
+use crate::commands::middleware::{Middleware, Cooldown, CooldownBucket};
 use crate::utility::*;
 use crate::databases::*;
 
@@ -20,60 +21,47 @@ impl Command for TweetCommand {
             CommandType::Casual,
             nonempty!["tweet".to_string()]
         )
-            .add_required("message (max 280 characters)")
+            .add_required("message (max 280 characters)", ArgumentType::Text)
             .example("Twitter is now X!")
     }
 
+    // tweets ping a role, so every user may only tweet every 10 minutes
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        vec![Box::new(Cooldown::new(CooldownBucket::User, 10 * 60))]
+    }
+
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
         Box::pin(
             async move {
 
                 let message = &params.message;
                 let author = &message.get_author().id.to_string();
-                let content = message.payload(None, None);
-
-                if content.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
+                let content = params.arguments.text("message").unwrap_or_default().to_string();
 
                 if content.len() > 280 {
                     self.invalid_usage(params).await;
                     return;
                 }
 
-                let last_tweet = TweetsDB::get_instance()
-                    .get_last(author, 1).await.unwrap();
-
-                // You can only tweet every 10 minutes to rate limit pings
-                if let Some(tweet) = last_tweet.first() {
-                    let now = chrono::Utc::now().timestamp();
-                    let next_tweet = tweet.timestamp + 10 * 60;
-                    if now < next_tweet {
-                        let _ = message.reply_failure(
-                            &format!("You can tweet again <t:{}:R>", next_tweet)).await;
-                        return;
-                    }
-                }
-
                 // resolve role and channel
                 let role_tweets = message.get_resolver().resolve_role("Tweets").await.unwrap()[0].id;
-                let channel_tweets: ChannelId = ConfigDB::get_instance()
-                    .get("channel_tweets").await.unwrap().into();
+                let channel_tweets: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_tweets,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // create tweet message
                 let allowed_mentions = CreateAllowedMentions::new()
                     .roles(vec![role_tweets]);
                 let reactions = vec![ReactionType::Unicode("👍".to_string()),
                                      ReactionType::Unicode("👎".to_string())];
+                let announcement = message.guild_text(text!("tweet.announcement",
+                    user = format!("<@{}>", author), role = format!("<@&{}>", role_tweets.to_string()))).await;
                 let tweet = CreateMessage::new()
-                    .content(format!("<@{}> **tweeted!** <@&{}>\n\n{}", author, role_tweets.to_string(), content))
+                    .content(format!("{}\n\n{}", announcement, content))
                     .reactions(reactions)
                     .allowed_mentions(allowed_mentions);
 
-                // log last tweet
-                TweetsDB::get_instance().set(author, &content).await;
-
                 // send to tweets channel
                 let _ = channel_tweets.send_message(&message, tweet).await;
 
@@ -14,12 +14,13 @@ pub struct VerifyCommand;
 
 impl VerifyCommand {
 
+    // messages of the catalog and gifs that are shown as they are
     pub const WELCOME_MESSAGES: [&'static str; 11] = [
-        "Please help them with roles if they need help.",
-        "Welcome to Kalopsia! You can ask for help by pinging a staff member.",
-        "Thank you for joining Kalopsia! If you'd like help then you can always ask anyone chat!",
-        "Welcome to Kalopsia! We're thrilled that you chose to join!",
-        "Please help them with roles if they want! \nWe hope you enjoy the server!",
+        "verify.welcome-1",
+        "verify.welcome-2",
+        "verify.welcome-3",
+        "verify.welcome-4",
+        "verify.welcome-5",
         "https://tenor.com/view/the-god-father-marlon-brando-vito-corleone-talk-to-me-gif-20107028",
         "https://tenor.com/view/star-wars-baby-yoda-the-mandalorian-welcome-wave-gif-16179355",
         "https://tenor.com/view/welcome-captain-gif-18905364",
@@ -28,8 +29,12 @@ impl VerifyCommand {
         "https://giphy.com/gifs/welcome-austin-powers-dr-evil-l0MYC0LajbaPoEADu"
     ];
 
-    fn random_welcome_message() -> &'static str {
-        VerifyCommand::WELCOME_MESSAGES.choose(&mut rand::rng()).unwrap()
+    fn random_welcome_message() -> Text {
+        let message = *VerifyCommand::WELCOME_MESSAGES.choose(&mut rand::rng()).unwrap();
+        match message.starts_with("https://") {
+            true  => Text::plain(message),
+            false => Text::new(message),
+        }
     }
 
 }
@@ -48,15 +53,17 @@ impl Command for VerifyCommand {
             async move {
 
                 let message = &params.message;
-                let channel_verify = ConfigDB::get_instance()
-                    .get("channel_verify").await.unwrap().to_string();
+                let channel_verify = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_verify.to_string(),
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 if message.get_channel().to_string() == channel_verify {
 
                     let roles = &message.resolve_role(vec!["User Restriction", "Kalopsian"]).await.unwrap();
 
                     if !message.has_role(&roles[0]).await || message.has_role(&roles[1]).await {
-                        message.reply_failure("You are already verified!").await;
+                        message.reply_failure("verify.already-verified").await;
                         return;
                     }
 
@@ -70,15 +77,15 @@ impl Command for VerifyCommand {
                         message.reply_success().await;
 
                         // send welcome message
-                        let channel: ChannelId = ConfigDB::get_instance()
-                            .get("channel_welcome").await.unwrap().into();
-                        let welcome_message = VerifyCommand::random_welcome_message();
+                        let channel: ChannelId = match BotConfig::get(message).await {
+                            Ok(config) => config.channel_welcome,
+                            Err(err) => return message.reply_db_error(err).await,
+                        };
+                        let joined = message.guild_text(
+                            text!("verify.joined", user = format!("<@{}>", message.get_author().id))).await;
+                        let welcome_message = message.guild_text(VerifyCommand::random_welcome_message()).await;
                         let _ = channel.send_message(message,
-                            format!(
-                                "<@{}> has joined Kalopsia!\n{}",
-                                message.get_author().id,
-                                welcome_message).to_message()
-                            ).await;
+                            format!("{}\n{}", joined, welcome_message).to_message()).await;
                     }
                 }
             }
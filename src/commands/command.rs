@@ -9,9 +9,11 @@ use std::sync::Mutex;
 use std::collections::HashSet;
 
 use crate::utility::*;
+use crate::commands::middleware::Middleware;
+use crate::databases::BotConfig;
 
 
-#[derive(PartialEq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
 pub enum CommandType {
     Casual,
     Games,
@@ -42,8 +44,10 @@ impl From<String> for CommandType {
     }
 }
 
+#[derive(Clone)]
 pub struct CommandParams {
     pub message: MessageManager,
+    pub arguments: Arguments,
     pub target: Option<User>,
     pub number: Option<i64>,
     pub time:   Option<u64>,
@@ -51,7 +55,7 @@ pub struct CommandParams {
 
 impl CommandParams {
     pub fn new(message: MessageManager) -> Self {
-        Self { message, target: None, number: None, time: None }
+        Self { message, arguments: Arguments::default(), target: None, number: None, time: None }
     }
     pub fn set_target(mut self, target: Option<User>) -> Self {
         self.target = target;
@@ -69,12 +73,16 @@ impl CommandParams {
 
 pub trait Command: Send + Sync {
 
+    // the level the usage requires, otherwise everyone who is not muted
     fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
         Box::pin(async move {
-            if let Some(muted_role) = &message.get_resolver().resolve_role("Muted").await {
-                return !message.has_role(muted_role[0].id).await;
+            if let Some(level) = self.define_usage().level {
+                return message.has_level(level).await;
+            }
+            match BotConfig::get(message).await.ok().and_then(|config| config.role_muted) {
+                Some(role_muted) => !message.has_role(role_muted).await,
+                None => true,
             }
-            true
         })
     }
 
@@ -82,33 +90,67 @@ pub trait Command: Send + Sync {
 
     fn define_usage(&self) -> UsageBuilder;
 
-    fn display_usage(&self, params: CommandParams, title: String) -> BoxedFuture<'_, ()> {
+    // steps of this command in addition to the ones of every command,
+    // e.g. a cooldown, see `CommandManager::new` for the others
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        Vec::new()
+    }
+
+    // Whether the command needs a valid configuration of the guild. The
+    // commands that repair it run without one.
+    fn needs_config(&self) -> bool {
+        true
+    }
+
+    fn display_usage(&self, params: CommandParams, title: Text) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move { self.reply_usage(&params.message, title, None).await }
+        )
+    }
+
+    fn reply_usage<'a>(&'a self, message: &'a MessageManager, title: Text, problem: Option<String>) -> BoxedFuture<'a, ()> {
         Box::pin(
             async move {
-                let message = &params.message;
-                let usage = self.define_usage().build(&message.get_prefix().unwrap());
+                let locale = message.locale().await;
+                let mut usage = self.define_usage().build(&message.get_prefix().unwrap(), locale);
+                if let Some(problem) = problem {
+                    usage = format!("{}\n\n{}\n{}", usage, Text::new("usage.problem").localize(locale), problem);
+                }
                 let embed = MessageManager::create_embed(|embed| {
                     embed
-                        .title(title)
+                        .title(title.localize(locale))
                         .description(&usage)
-                        .footer(CreateEmbedFooter::new(
-                            format!("Syntax Legend: () = required, [] = optional"),
-                        ))
+                        .footer(CreateEmbedFooter::new(Text::new("usage.legend").localize(locale)))
                 }).await;
                 let _ = message.reply(embed.to_message()).await;
             }
         )
     }
 
+    // The command rejected its arguments, so the use counts as failed.
     fn invalid_usage(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
         Box::pin(
-            async move { self.display_usage(params, "Invalid Usage!".to_string()).await }
+            async move {
+                params.message.mark_failed();
+                self.display_usage(params, "usage.invalid-title".into()).await
+            }
+        )
+    }
+
+    // The usage along with the word of the message that did not fit it.
+    fn invalid_arguments(&self, params: CommandParams, error: ArgumentError) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+                let locale = params.message.locale().await;
+                let problem = format!("```\n{}\n```", error.pointer(&params.message.words, locale));
+                self.reply_usage(&params.message, "usage.invalid-title".into(), Some(problem)).await
+            }
         )
     }
 
 }
 
-impl Triggerable for Box<dyn Command> {
+impl Triggerable for &dyn Command {
 
     fn get_triggers(&self) -> NonEmpty<String> {
         self.define_usage().triggers
@@ -116,6 +158,14 @@ impl Triggerable for Box<dyn Command> {
 
 }
 
+impl Triggerable for Box<dyn Command> {
+
+    fn get_triggers(&self) -> NonEmpty<String> {
+        self.as_ref().get_triggers()
+    }
+
+}
+
 pub struct UserDecorator {
     pub command: Box<dyn Command>,
 }
@@ -128,11 +178,7 @@ impl UserDecorator {
 
             0 => {
                 // prepare message
-                let embed = MessageManager::create_embed(|embed| {
-                    embed
-                        .title("Select a user!")
-                        .description("Here are some suggestions ...")
-                }).await;
+                let embed = message.create_text_embed("select-user.title", "select-user.description").await;
 
                 // prepare dropdown options
                 let last_messages = message.last_messages(20).await;
@@ -176,6 +222,11 @@ impl Command for UserDecorator {
 
     fn define_usage(&self) -> UsageBuilder {
         self.command.define_usage()
+            .decorate(Decoration::User)
+    }
+
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        self.command.middleware()
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -186,7 +237,7 @@ impl Command for UserDecorator {
                     let augmented_params = params.set_target(target);
                     self.command.run(augmented_params.into()).await;
                 } else {
-                    params.message.reply_failure("User not found.").await;
+                    params.message.reply_failure("select-user.not-found").await;
                 }
             }
         )
@@ -207,14 +258,8 @@ impl NumberDecorator {
             }
         }
 
-        let embed = MessageManager::create_embed(|embed| {
-            embed
-                .title("Please provide a number!")
-                .description(&format!(
-                        "The `{}{}` command requires you to provide a number.\nJust respond in the chat.",
-                        message.get_prefix().unwrap(),
-                        message.get_command().unwrap()))
-        }).await;
+        let embed = message.create_text_embed("number.title", text!("number.description",
+            command = format!("{}{}", message.get_prefix().unwrap(), message.get_command().unwrap()))).await;
 
         let helper = message.get_interaction_helper();
         let author = message.get_author();
@@ -248,6 +293,11 @@ impl Command for NumberDecorator {
 
     fn define_usage(&self) -> UsageBuilder {
         self.command.define_usage()
+            .decorate(Decoration::Number)
+    }
+
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        self.command.middleware()
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -258,7 +308,7 @@ impl Command for NumberDecorator {
                     let augmented_params = params.set_number(number);
                     self.command.run(augmented_params.into()).await;
                 } else {
-                    params.message.reply_failure("No number provided.").await;
+                    params.message.reply_failure("number.missing").await;
                 }
             }
         )
@@ -293,6 +343,10 @@ impl Command for TimeDecorator {
         self.command.define_usage()
     }
 
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        self.command.middleware()
+    }
+
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
         Box::pin(
             async move {
@@ -301,7 +355,7 @@ impl Command for TimeDecorator {
                     let augmented_params = params.set_time(time);
                     self.command.run(augmented_params.into()).await;
                 } else {
-                    params.message.reply_failure("No time provided.").await;
+                    params.message.reply_failure("time.missing").await;
                 }
             }
         )
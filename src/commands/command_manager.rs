@@ -1,14 +1,18 @@
 
 use serenity::builder::CreateButton;
 use serenity::all::ButtonStyle;
-use serenity::all::UserId;
+use serenity::all::{ChannelId, UserId};
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, ResolvedValue};
 use strum::IntoEnumIterator;
+use once_cell::sync::OnceCell;
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::str::FromStr;
+use std::time::Instant;
 
 use crate::commands::command::{CommandType, CommandParams};
+use crate::databases::config::ConfigError;
 use crate::utility::*;
 use crate::commands::*;
 use crate::databases::*;
@@ -17,6 +21,25 @@ use crate::databases::*;
 #[cfg(feature = "commands")]
 pub struct CommandManager {
     commands: Vec<Box<dyn Command>>,
+    // the steps around every command, commands may add their own
+    middleware: Vec<Box<dyn Middleware>>,
+}
+
+// The first trigger of every command, configuration keys that name a
+// command are checked against it.
+static TRIGGERS: OnceCell<Vec<String>> = OnceCell::new();
+
+pub fn is_trigger(name: &str) -> bool {
+    TRIGGERS.get().is_some_and(|triggers| triggers.iter().any(|trigger| trigger == name))
+}
+
+// Why the author of a message may not run a command in its channel.
+enum Denial {
+    Disabled,
+    Channels(Vec<ChannelId>),
+    Permission,
+    // the problems of the configuration of the guild
+    Unconfigured(Vec<String>),
 }
 
 #[cfg(feature = "commands")]
@@ -40,6 +63,7 @@ impl CommandManager {
             Box::new( NumberDecorator{ command: Box::new(RemoveReminderCommand{}) }),
             Box::new( TweetCommand{} ),
             Box::new( DeadchatCommand{} ),
+            Box::new( LanguageCommand{} ),
             // games commands
             Box::new( EightBallCommand{} ),
             // moderation commands
@@ -68,6 +92,11 @@ impl CommandManager {
             Box::new( AddNoteCommand{} ),
             Box::new( RemoveNoteCommand{} ),
             Box::new( UserDecorator{ command: Box::new(HistoryCommand{}) }),
+            Box::new( BackupCommand{} ),
+            Box::new( ConfigCommand{} ),
+            Box::new( SearchCommand{} ),
+            Box::new( UserDecorator{ command: Box::new(LevelCommand{}) }),
+            Box::new( CommandStatsCommand{} ),
         ];
         #[cfg(feature = "tickets")]
         let ticket_commands: Vec<Box<dyn Command>> = vec![
@@ -85,26 +114,143 @@ impl CommandManager {
         ];
         #[cfg(feature = "tickets")]
         commands.extend(ticket_commands);
-        let manager = CommandManager { commands };
+        let _ = TRIGGERS.set(commands.iter().map(|command| command.trigger()).collect());
+        let middleware: Vec<Box<dyn Middleware>> = vec![
+            Box::new( DeleteMessage ),
+            Box::new( ReportErrors ),
+            Box::new( Analytics ),
+            #[cfg(feature = "debug")]
+            Box::new( LogCommands ),
+        ];
+        let manager = CommandManager { commands, middleware };
         manager
     }
 
-    async fn run_command(&self, command: &Box<dyn Command>, message: &MessageManager) {
+    // Applies the settings of a command in this guild. A configured
+    // permission level replaces the permission the command defines.
+    async fn check(&self, command: &dyn Command, message: &MessageManager) -> DBResult<Option<Denial>> {
+
+        let settings = CommandSettings::get(message, &command.define_usage().triggers.head).await?;
+        if settings.disabled {
+            return Ok(Some(Denial::Disabled));
+        }
+
+        // allowed channels may also be the categories of channels
+        if !settings.channels.is_empty() {
+            let channel = message.get_channel();
+            let category = message.get_resolver().resolve_guild_channel(channel).await
+                .and_then(|channel| channel.parent_id);
+            if !settings.channels.iter().any(|allowed| *allowed == channel || Some(*allowed) == category) {
+                return Ok(Some(Denial::Channels(settings.channels)));
+            }
+        }
+
+        let permitted = match settings.permission {
+            Some(level) => message.has_level(level).await,
+            None => command.permission(message).await,
+        };
+        if !permitted {
+            return Ok(Some(Denial::Permission));
+        }
+
+        // the problems are only loaded again if the cached configuration is missing
+        if command.needs_config() && BotConfig::get(message).await.is_err() {
+            match BotConfig::load(message.into()).await {
+                Ok(_) => {}
+                Err(ConfigError::Invalid(report)) => return Ok(Some(Denial::Unconfigured(report.issues))),
+                Err(ConfigError::Database(err)) => return Err(err),
+            }
+        }
+        Ok(None)
+    }
+
+    // the commands the author of a message may run in its channel
+    async fn available_commands(&self, message: &MessageManager) -> Vec<&dyn Command> {
+        let mut commands = Vec::new();
+        for command in self.commands.iter() {
+            if let Ok(None) = self.check(command.as_ref(), message).await {
+                commands.push(command.as_ref());
+            }
+        }
+        commands
+    }
+
+    // Runs a command through its middleware, the after hooks see the
+    // outcome of the use however it ended.
+    async fn run_command(&self, command: &dyn Command, message: &MessageManager) {
+
+        let started = Instant::now();
+        let own_middleware = command.middleware();
+        let middleware = self.middleware.iter()
+            .chain(own_middleware.iter())
+            .map(|middleware| middleware.as_ref())
+            .collect::<Vec<&dyn Middleware>>();
 
-        if command.permission(message).await {
+        let mut params = CommandParams::new(message.clone());
+        let outcome = self.run_chain(command, &middleware, &mut params).await;
+        for middleware in middleware.iter().rev() {
+            middleware.after(command, &params, outcome, started.elapsed()).await;
+        }
+    }
+
+    async fn run_chain(&self, command: &dyn Command, middleware: &[&dyn Middleware], params: &mut CommandParams) -> CommandOutcome {
+
+        let message = &params.message;
+        let denial = match self.check(command, message).await {
+            Ok(denial) => denial,
+            Err(err) => {
+                message.reply_db_error(err).await;
+                return CommandOutcome::Failed;
+            }
+        };
+        match denial {
+            Some(Denial::Disabled) => {
+                message.reply_failure("command.disabled").await;
+                return CommandOutcome::Denied;
+            }
+            Some(Denial::Channels(channels)) => {
+                let channels = channels.iter()
+                    .map(|channel| format!("<#{}>", channel))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                message.reply_failure(text!("command.channels", channels = channels)).await;
+                return CommandOutcome::Denied;
+            }
+            Some(Denial::Permission) => {
+                message.reply_failure("command.no-permission").await;
+                return CommandOutcome::Denied;
+            }
+            Some(Denial::Unconfigured(issues)) => {
+                let issues = issues.iter()
+                    .map(|issue| format!("- {}", issue))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                message.reply_failure(text!("command.unconfigured", issues = issues)).await;
+                return CommandOutcome::Denied;
+            }
+            None => {}
+        }
 
-            // execute command
-            message.delete().await;
-            command.run(CommandParams::new(message.clone())).await;
+        // parse arguments
+        params.arguments = match command.define_usage().parse(&message.tokens[1..]) {
+            Ok(arguments) => arguments,
+            Err(error) => {
+                command.invalid_arguments(params.clone(), error).await;
+                return CommandOutcome::Invalid;
+            }
+        };
 
-            // increment executed commands
-            let executed_commands = ConfigDB::get_instance()
-                .get("executed_commands").await.unwrap().to_string().parse::<i64>().unwrap() + 1;
-            ConfigDB::get_instance()
-                .set("executed_commands", &executed_commands.to_string()).await;
+        for middleware in middleware {
+            if !middleware.before(command, params).await {
+                return CommandOutcome::Cancelled;
+            }
+        }
 
-        } else {
-            message.reply_failure("You do not have permission to use this command").await;
+        // execute command
+        command.run(params.clone()).await;
+        match params.message.has_failed() {
+            true  => CommandOutcome::Failed,
+            false => CommandOutcome::Success,
         }
     }
 
@@ -127,86 +273,114 @@ impl CommandManager {
         // execute command if found
         if let Ok(index) = index {
             let command = &self.commands[index];
-            self.run_command(command, message).await;
+            self.run_command(command.as_ref(), message).await;
+        }
+    }
+
+    // One slash command per command, plus `help`. Prefix commands keep working.
+    pub fn application_commands(&self) -> Vec<CreateCommand> {
+        // a command Discord would reject is left out, so the others are still registered
+        let mut commands = self.commands.iter()
+            .filter_map(|command| match command.define_usage().application_command() {
+                Ok(created) => Some(created),
+                Err(err) => {
+                    Logger::err_long(&format!("Skipped the slash command /{}", command.trigger()), &err);
+                    None
+                }
+            })
+            .collect::<Vec<CreateCommand>>();
+        commands.push(CreateCommand::new("help")
+            .description("List all categories, or the commands of a category, or how to use a command")
+            .add_option(CreateCommandOption::new(CommandOptionType::String, "topic", "A category or a command")));
+        commands
+    }
+
+    // Runs a slash command like the text command it stands for. The
+    // interaction is acknowledged right away, since commands may wait for
+    // replies, and the acknowledgement is removed once the command is done.
+    pub async fn execute_interaction(&self, ctx: Context, interaction: CommandInteraction) {
+
+        if let Err(err) = interaction.defer_ephemeral(&ctx.http).await {
+            return Logger::err_long("Failed to acknowledge slash command", &err.to_string());
         }
+
+        let prefix = BotConfig::prefix(interaction.guild_id.into()).await;
+        let name = &interaction.data.name;
+        let options = interaction.data.options();
+        let content = match self.commands.iter().find(|command| command.trigger() == *name) {
+            Some(command) => command.define_usage().application_content(&prefix, &options),
+            None => match options.first().map(|option| &option.value) {
+                Some(ResolvedValue::String(topic)) => format!("{}help {}", prefix, topic),
+                _ => format!("{}help", prefix),
+            },
+        };
+
+        let resolver = Resolver::new(ctx.clone(), interaction.guild_id);
+        let message = MessageManager::from_interaction(resolver, &interaction, content).await;
+        self.execute(&message).await;
+
+        let _ = interaction.delete_response(&ctx.http).await;
     }
 
+    // the level a command requires in the guild of a message
+    async fn required_level(&self, command: &dyn Command, message: &MessageManager) -> PermissionLevel {
+        let usage = command.define_usage();
+        let configured = CommandSettings::get(message, &usage.triggers.head).await
+            .ok()
+            .and_then(|settings| settings.permission);
+        configured.or(usage.level).unwrap_or(PermissionLevel::Everyone)
+    }
+
+    // Browses the commands the author may run here by category, with a page
+    // for each command. The topic may name a category, or a command even if
+    // it is misspelled, to start with.
     async fn display_help(&self, message: &MessageManager) {
 
         // delete message
         message.delete().await;
 
-        // resolve bot user
-        let bot_id: UserId = ConfigDB::get_instance()
-            .get("bot_id").await.unwrap().into();
-        let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
+        // resolve bot user, the help is shown even if the configuration is invalid
+        let bot_id: UserId = match ConfigDB::get_instance().scope(message).get_as("bot_id").await {
+            Ok(bot_id) => bot_id,
+            Err(err) => return message.reply_db_error(err).await,
+        };
+        let Some(bot) = message.get_resolver().resolve_user(bot_id).await else {
+            return message.reply_failure("help.no-bot").await;
+        };
 
-        // display categories
-        let payload = message.payload(None, None);
-        if payload.is_empty() {
-
-            let categories = CommandType::iter()
-                .map(|category| format!("`{}`", category.to_string()))
-                .collect::<Vec<_>>()
-                .join(", ");
-            let description = format!("Commands are categorized into groups which are listed below. If you cannot find the command you are searching for or if you need help with anything else, you can make a ticket through our tickets channel.\n\n**Categories**\n{categories}");
-
-            // create embed
-            let embed = message.get_log_builder()
-                .target(&bot)
-                .no_thumbnail()
-                .title("Available Commands")
-                .description(&description)
-                .build().await;
-            let _ = message.reply(embed.to_message()).await;
+        // only the commands the author may run here are listed
+        let available = self.available_commands(message).await;
+        let mut entries = Vec::new();
+        for command in available.iter() {
+            let level = self.required_level(*command, message).await;
+            entries.push(HelpEntry { command: *command, level });
         }
+        let help = Help::new(message, &bot, entries).await;
 
-        // display help for a specific command
-        else {
-
-            // find command
-            let trigger = &payload.split_whitespace().next().unwrap().to_string();
-
-            for command_type in CommandType::iter() {
-                if command_type.to_string().to_lowercase() == *trigger.to_lowercase() {
-
-                    // filter commands
-                    let mut commands = Vec::new();
-                    for command in self.commands.iter() {
-                        if command.define_usage().command_type == command_type {
-                            commands.push(command);
+        // find the page to start with
+        let payload = message.payload(None, None);
+        let first = match payload.split_whitespace().next() {
+            None => Help::overview_key(),
+            Some(topic) => {
+                let category = help.categories().into_iter()
+                    .find(|category| category.to_string().eq_ignore_ascii_case(topic));
+                match category {
+                    Some(category) => Help::category_key(category, 0),
+                    None => {
+                        let triggerables = available.iter()
+                            .map(|command| command as &dyn Triggerable)
+                            .collect::<Vec<_>>();
+                        match match_triggerables(message, &topic.to_string(), triggerables).await {
+                            Ok(index) => Help::command_key(&available[index].trigger()),
+                            Err(_) => return,
                         }
                     }
-
-                    let mut command_strings = commands.iter()
-                        .map(|command| format!("`{}{}`", message.get_prefix().unwrap(), command.trigger()))
-                        .collect::<Vec<_>>();
-                    command_strings.sort();
-
-                    // create embed
-                    let embed = message.get_log_builder()
-                        .target(&bot)
-                        .no_thumbnail()
-                        .title(format!("{} Commands", command_type.to_string()))
-                        .description(&command_strings.join(", "))
-                        .build().await;
-                    let _ = message.reply(embed.to_message()).await;
-                    return;
                 }
             }
+        };
 
-            // match command
-            let triggerables = self.commands.iter()
-                .map(|command| command as &dyn Triggerable)
-                .collect::<Vec<_>>();
-            let index = match_triggerables(message, &trigger, triggerables).await;
-
-            // display usage
-            if let Ok(index) = index {
-                let command = &self.commands[index];
-                let params = CommandParams::new(message.clone());
-                command.display_usage(params, "Command Description".to_string()).await;
-            }
-        }
+        let pages = help.pages().await;
+        message.get_interaction_helper()
+            .create_navigation(message.get_author().id, pages, &first).await;
     }
 }
@@ -5,6 +5,7 @@ use rand::seq::IndexedRandom;
 use nonempty::{NonEmpty, nonempty};
 
 use crate::commands::command::*;
+use crate::commands::middleware::{Middleware, Cooldown, CooldownBucket};
 use crate::utility::*;
 use crate::databases::*;
 
@@ -13,20 +14,21 @@ pub struct EightBallCommand;
 
 impl EightBallCommand {
 
+    // the answers are messages of the catalog
     pub const OPTIONS: [&'static str; 13] = [
-        "It is certain.",
-        "It is decidedly so.",
-        "Without a doubt.",
-        "Yes definitely.",
-        "You may rely on it.",
-        "Most likely.",
-        "As I see it, yes.",
-        "Very doubtful.",
-        "My reply is no.",
-        "Don't count on it.",
-        "Outlook not so good.",
-        "Ask again later.",
-        "Better not tell you now.",
+        "eightball.answer-1",
+        "eightball.answer-2",
+        "eightball.answer-3",
+        "eightball.answer-4",
+        "eightball.answer-5",
+        "eightball.answer-6",
+        "eightball.answer-7",
+        "eightball.answer-8",
+        "eightball.answer-9",
+        "eightball.answer-10",
+        "eightball.answer-11",
+        "eightball.answer-12",
+        "eightball.answer-13",
     ];
 
     fn random_option() -> &'static str {
@@ -42,38 +44,40 @@ impl Command for EightBallCommand {
             CommandType::Games,
             nonempty!["8ball".to_string(), "eightball".to_string()]
         )
-            .add_required("question")
+            .add_required("question", ArgumentType::Text)
             .example("Will I get a promotion to moderator next week?")
     }
 
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        vec![Box::new(Cooldown::new(CooldownBucket::Channel, 60).uses(3).staff_bypass())]
+    }
+
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
         Box::pin(
             async move {
 
                 let message = &params.message;
-                let content = message.payload(None, None);
-
-                if content.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
+                let content = params.arguments.text("question").unwrap_or_default();
 
                 // resolve bot
-                let bot_id: UserId = ConfigDB::get_instance()
-                    .get("bot_id").await.unwrap().into();
+                let bot_id: UserId = match BotConfig::get(message).await {
+                    Ok(config) => config.bot_id,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
 
                 // create embed
-                let option = EightBallCommand::random_option();
+                let option = message.text(EightBallCommand::random_option()).await;
+                let footer = message.text(text!("eightball.footer", user = message.resolve_name())).await;
                 let embed = message.get_log_builder()
-                    .title("Magic 🎱")
+                    .for_author()
+                    .title("eightball.title")
                     .target(&bot)
                     .no_thumbnail()
-                    .arbitrary_block("Question", content)
-                    .arbitrary_block("Answer", option)
+                    .arbitrary_block("eightball.question", content)
+                    .arbitrary_block("eightball.answer", option)
                     .build().await
-                    .footer(CreateEmbedFooter::new(
-                        format!("Question by {}", message.resolve_name()))
+                    .footer(CreateEmbedFooter::new(footer)
                         .icon_url(message.get_author().face()));
 
                 let _ = message.reply(embed).await;
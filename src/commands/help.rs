@@ -0,0 +1,217 @@
+
+use serenity::builder::{
+    CreateActionRow,
+    CreateButton,
+    CreateEmbedFooter,
+    CreateSelectMenu,
+    CreateSelectMenuKind,
+    CreateSelectMenuOption,
+};
+use serenity::all::ButtonStyle;
+use serenity::model::user::User;
+use strum::IntoEnumIterator;
+
+use std::collections::HashMap;
+
+use crate::commands::command::{Command, CommandType};
+use crate::utility::*;
+
+
+// commands listed on a page of a category
+const PAGE_SIZE: usize = 10;
+
+// A command the author may run, with the level it requires in this guild.
+pub struct HelpEntry<'a> {
+    pub command: &'a dyn Command,
+    pub level: PermissionLevel,
+}
+
+impl HelpEntry<'_> {
+    pub fn trigger(&self) -> String {
+        self.command.define_usage().triggers.head
+    }
+}
+
+// The pages of the help. Each is reached by the key of its page, which
+// buttons and select menu options carry: `help:overview`,
+// `help:category:<category>:<page>` and `help:command:<trigger>`.
+pub struct Help<'a> {
+    message: &'a MessageManager,
+    bot: &'a User,
+    entries: Vec<HelpEntry<'a>>,
+    prefix: String,
+    locale: Locale,
+}
+
+impl<'a> Help<'a> {
+
+    pub async fn new(message: &'a MessageManager, bot: &'a User, mut entries: Vec<HelpEntry<'a>>) -> Help<'a> {
+        entries.sort_by_key(|entry| entry.trigger());
+        Help {
+            message,
+            bot,
+            entries,
+            prefix: message.get_prefix().unwrap_or_default(),
+            locale: message.locale().await,
+        }
+    }
+
+    pub fn overview_key() -> String {
+        "help:overview".to_string()
+    }
+
+    pub fn category_key(category: CommandType, page: usize) -> String {
+        format!("help:category:{}:{}", category.to_string().to_lowercase(), page)
+    }
+
+    pub fn command_key(trigger: &str) -> String {
+        format!("help:command:{}", trigger)
+    }
+
+    // the categories with at least one command the author may run
+    pub fn categories(&self) -> Vec<CommandType> {
+        CommandType::iter()
+            .filter(|category| self.entries.iter()
+                .any(|entry| entry.command.define_usage().command_type == *category))
+            .collect()
+    }
+
+    fn pages_of(&self, category: CommandType) -> Vec<Vec<&HelpEntry<'a>>> {
+        self.entries.iter()
+            .filter(|entry| entry.command.define_usage().command_type == category)
+            .collect::<Vec<_>>()
+            .chunks(PAGE_SIZE)
+            .map(|chunk| chunk.to_vec())
+            .collect()
+    }
+
+    // the page of its category a command is listed on
+    fn listed_on(&self, entry: &HelpEntry) -> String {
+        let category = entry.command.define_usage().command_type;
+        let page = self.pages_of(category).iter()
+            .position(|page| page.iter().any(|listed| listed.trigger() == entry.trigger()))
+            .unwrap_or(0);
+        Help::category_key(category, page)
+    }
+
+    // Select menu options can only have short descriptions.
+    fn shorten(text: &str) -> String {
+        match text.chars().count() > 100 {
+            true  => text.chars().take(99).collect::<String>() + "…",
+            false => text.to_string(),
+        }
+    }
+
+    fn category_menu(&self, selected: Option<CommandType>) -> CreateActionRow {
+        let options = self.categories().into_iter()
+            .map(|category| {
+                let count = self.pages_of(category).iter().map(|page| page.len()).sum::<usize>();
+                CreateSelectMenuOption::new(category.to_string(), Help::category_key(category, 0))
+                    .description(text!("help.category-option", count = count).localize(self.locale))
+                    .default_selection(Some(category) == selected)
+            })
+            .collect();
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new("help:categories", CreateSelectMenuKind::String { options })
+                .placeholder(Text::new("help.select-category").localize(self.locale)))
+    }
+
+    fn command_menu(&self, entries: &[&HelpEntry]) -> CreateActionRow {
+        let options = entries.iter()
+            .map(|entry| {
+                let trigger = entry.trigger();
+                CreateSelectMenuOption::new(format!("{}{}", self.prefix, trigger), Help::command_key(&trigger))
+                    .description(Help::shorten(&entry.command.define_usage().summary(&self.prefix)))
+            })
+            .collect();
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new("help:commands", CreateSelectMenuKind::String { options })
+                .placeholder(Text::new("help.select-command").localize(self.locale)))
+    }
+
+    fn button(&self, key: String, label: &str, disabled: bool) -> CreateButton {
+        CreateButton::new(key)
+            .label(Text::new(label).localize(self.locale))
+            .style(ButtonStyle::Secondary)
+            .disabled(disabled)
+    }
+
+    pub async fn overview(&self) -> NavigationPage {
+        let categories = self.categories().iter()
+            .map(|category| format!("`{}`", category.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let embed = self.message.get_log_builder()
+            .for_author()
+            .target(self.bot)
+            .no_thumbnail()
+            .title("help.title")
+            .description(text!("help.description", categories = categories, prefix = &self.prefix))
+            .build().await;
+        let components = match self.categories().is_empty() {
+            true  => Vec::new(),
+            false => vec![self.category_menu(None)],
+        };
+        (embed, components)
+    }
+
+    async fn category_page(&self, category: CommandType, index: usize, entries: &[&HelpEntry<'a>], count: usize) -> NavigationPage {
+        let lines = entries.iter()
+            .map(|entry| format!("`{}`", entry.command.define_usage().summary(&self.prefix)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let embed = self.message.get_log_builder()
+            .for_author()
+            .target(self.bot)
+            .no_thumbnail()
+            .title(text!("help.category-title", category = category.to_string()))
+            .description(Text::plain(lines))
+            .build().await
+            .footer(CreateEmbedFooter::new(
+                text!("interaction.page", page = index + 1, count = count).localize(self.locale)));
+
+        let mut components = vec![self.category_menu(Some(category)), self.command_menu(entries)];
+        if count > 1 {
+            components.push(CreateActionRow::Buttons(vec![
+                self.button(Help::category_key(category, index.saturating_sub(1)), "interaction.previous", index == 0),
+                self.button(Help::category_key(category, index + 1), "interaction.next", index + 1 == count),
+            ]));
+        }
+        (embed, components)
+    }
+
+    pub async fn command_page(&self, entry: &HelpEntry<'a>) -> NavigationPage {
+        let usage = entry.command.define_usage();
+        let embed = self.message.get_log_builder()
+            .for_author()
+            .target(self.bot)
+            .no_thumbnail()
+            .title(text!("help.command-title", command = format!("{}{}", self.prefix, usage.triggers.head)))
+            .description(Text::plain(usage.build(&self.prefix, self.locale)))
+            .arbitrary("help.category", usage.command_type.to_string())
+            .arbitrary("help.level", format!("`{}`", entry.level))
+            .build().await;
+        let components = vec![
+            self.category_menu(Some(usage.command_type)),
+            CreateActionRow::Buttons(vec![self.button(self.listed_on(entry), "interaction.back", false)]),
+        ];
+        (embed, components)
+    }
+
+    // all pages, so the help can be browsed without running the command again
+    pub async fn pages(&self) -> HashMap<String, NavigationPage> {
+        let mut pages = HashMap::new();
+        pages.insert(Help::overview_key(), self.overview().await);
+        for category in self.categories() {
+            let category_pages = self.pages_of(category);
+            for (index, entries) in category_pages.iter().enumerate() {
+                let page = self.category_page(category, index, entries, category_pages.len()).await;
+                pages.insert(Help::category_key(category, index), page);
+            }
+        }
+        for entry in self.entries.iter() {
+            pages.insert(Help::command_key(&entry.trigger()), self.command_page(entry).await);
+        }
+        pages
+    }
+}
@@ -0,0 +1,313 @@
+#[cfg(feature = "tickets")]
+use serenity::builder::CreateButton;
+#[cfg(feature = "tickets")]
+use serenity::all::ButtonStyle;
+use chrono::Utc;
+
+use std::time::Duration;
+
+use crate::commands::command::{Command, CommandParams};
+use crate::utility::*;
+use crate::databases::*;
+
+
+// A step around every use of a command. `before` runs once the arguments are
+// parsed and may stop the command, `after` runs with the outcome of the use,
+// also when the command was denied or its arguments did not fit. The after
+// hooks run in the reverse order of the before hooks.
+pub trait Middleware: Send + Sync {
+
+    fn before<'a>(&'a self, _command: &'a dyn Command, _params: &'a CommandParams) -> BoxedFuture<'a, bool> {
+        Box::pin(async { true })
+    }
+
+    fn after<'a>(&'a self,
+                 _command: &'a dyn Command,
+                 _params: &'a CommandParams,
+                 _outcome: CommandOutcome,
+                 _elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+// Deletes the message that invoked a command, unless the command was denied.
+pub struct DeleteMessage;
+
+impl Middleware for DeleteMessage {
+
+    fn before<'a>(&'a self, _command: &'a dyn Command, params: &'a CommandParams) -> BoxedFuture<'a, bool> {
+        Box::pin(async move {
+            params.message.delete().await;
+            true
+        })
+    }
+
+    fn after<'a>(&'a self,
+                 _command: &'a dyn Command,
+                 params: &'a CommandParams,
+                 outcome: CommandOutcome,
+                 _elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+            if outcome == CommandOutcome::Invalid {
+                params.message.delete().await;
+            }
+        })
+    }
+}
+
+// Records every use in the command uses table, see `command-stats`, and
+// counts the commands that were run for `about`.
+pub struct Analytics;
+
+impl Middleware for Analytics {
+
+    fn after<'a>(&'a self,
+                 command: &'a dyn Command,
+                 params: &'a CommandParams,
+                 outcome: CommandOutcome,
+                 elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+
+            let message = &params.message;
+            let command_use = CommandUse::new(
+                message.get_author().id.to_string(),
+                message.get_channel().to_string(),
+                elapsed.as_millis() as i64,
+                outcome.to_string());
+            if let Err(err) = CommandUsesDB::get_instance().scope(message)
+                .append(&command.define_usage().triggers.head, command_use).await {
+                Logger::err_long("Failed to record command use", &err.to_string());
+            }
+
+            // increment executed commands
+            if outcome != CommandOutcome::Success && outcome != CommandOutcome::Failed {
+                return;
+            }
+            let executed_commands = ConfigDB::get_instance().global()
+                .get("executed_commands").await
+                .map_or(0, |entry| entry.to_string().parse::<i64>().unwrap_or(0)) + 1;
+            if let Err(err) = ConfigDB::get_instance().global()
+                .set("executed_commands", &executed_commands.to_string()).await {
+                Logger::err_long("Failed to count executed command", &err.to_string());
+            }
+        })
+    }
+}
+
+// Logs the commands that replied with a failure, together with their author.
+pub struct ReportErrors;
+
+impl Middleware for ReportErrors {
+
+    fn after<'a>(&'a self,
+                 _command: &'a dyn Command,
+                 params: &'a CommandParams,
+                 outcome: CommandOutcome,
+                 _elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+            if outcome == CommandOutcome::Failed {
+                let message = &params.message;
+                Logger::warn_long("Command failed", &format!("{} by {} ({})",
+                    message.words.join(" "),
+                    message.resolve_name(),
+                    message.get_author().id));
+            }
+        })
+    }
+}
+
+// Logs every use of a command with its outcome and duration.
+#[cfg(feature = "debug")]
+pub struct LogCommands;
+
+#[cfg(feature = "debug")]
+impl Middleware for LogCommands {
+
+    fn after<'a>(&'a self,
+                 _command: &'a dyn Command,
+                 params: &'a CommandParams,
+                 outcome: CommandOutcome,
+                 elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+            let message = &params.message;
+            Logger::info_long("Command", &format!("{} by {} ({}, {}ms)",
+                message.words.join(" "),
+                message.resolve_name(),
+                outcome,
+                elapsed.as_millis()));
+        })
+    }
+}
+
+// Asks the author to confirm a command before it runs, e.g. before it
+// deletes data. The command is cancelled if nothing was pressed for a minute.
+// Only ticket commands ask for a confirmation yet.
+#[cfg(feature = "tickets")]
+pub struct Confirmation {
+    // a message of the catalog
+    pub prompt: &'static str,
+}
+
+#[cfg(feature = "tickets")]
+impl Middleware for Confirmation {
+
+    fn before<'a>(&'a self, _command: &'a dyn Command, params: &'a CommandParams) -> BoxedFuture<'a, bool> {
+        Box::pin(async move {
+            let message = &params.message;
+            let embed = message.create_text_embed("confirmation.title", self.prompt).await;
+            let confirm = CreateButton::new("confirm")
+                .label(message.text("confirmation.confirm").await)
+                .style(ButtonStyle::Success);
+            let pressed = message.get_interaction_helper()
+                .create_buttons(message.get_author().id, embed, vec![confirm]).await;
+            pressed.as_deref() == Some("confirm")
+        })
+    }
+}
+
+// Who shares the uses of a command with a cooldown.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CooldownBucket {
+    User,
+    Channel,
+    // no command shares its uses across the whole guild yet
+    #[allow(unused)]
+    Global,
+}
+
+// A command may be used `uses` times per `period` seconds in each bucket.
+// A use is reserved before the command runs, so commands sent at once can
+// not both pass, and given back unless the command succeeds, so e.g. a
+// rejected argument does not use up the bucket. The uses are stored in the
+// cooldowns table with the ID of their message, so a restart does not reset
+// them. Every bucket only keeps the uses of its current period.
+pub struct Cooldown {
+    pub bucket: CooldownBucket,
+    pub uses: usize,
+    pub period: i64,
+    pub staff_bypass: bool,
+}
+
+impl Cooldown {
+
+    pub fn new(bucket: CooldownBucket, period: i64) -> Cooldown {
+        Cooldown { bucket, uses: 1, period, staff_bypass: false }
+    }
+
+    pub fn uses(mut self, uses: usize) -> Cooldown {
+        self.uses = uses.max(1);
+        self
+    }
+
+    pub fn staff_bypass(mut self) -> Cooldown {
+        self.staff_bypass = true;
+        self
+    }
+
+    async fn bypassed(&self, message: &MessageManager) -> bool {
+        self.staff_bypass && message.has_level(PermissionLevel::Trial).await
+    }
+
+    fn bucket_key(&self, command: &dyn Command, message: &MessageManager) -> String {
+        let trigger = command.define_usage().triggers.head;
+        match self.bucket {
+            CooldownBucket::User    => format!("{}:user:{}", trigger, message.get_author().id),
+            CooldownBucket::Channel => format!("{}:channel:{}", trigger, message.get_channel()),
+            CooldownBucket::Global  => format!("{}:global", trigger),
+        }
+    }
+
+    // The time the command can be used again, if the bucket is used up.
+    pub async fn available_at(&self, key: &str, message: &MessageManager) -> DBResult<Option<i64>> {
+        let now = Utc::now().timestamp();
+        let uses = CooldownsDB::get_instance().scope(message).query(Query::new()
+            .key(key)
+            .after(now - self.period)
+            .order(Order::Descending)).await?;
+
+        // the bucket frees up once the oldest counted use expires
+        Ok(match uses.len() >= self.uses {
+            true  => uses.get(self.uses - 1).map(|entry| entry.timestamp + self.period),
+            false => None,
+        })
+    }
+
+    // Reserves a use of the bucket for the message, if one is left.
+    async fn reserve(&self, key: &str, message: &MessageManager) -> DBResult<bool> {
+        let cooldowns = CooldownsDB::get_instance().scope(message);
+        let now = Utc::now().timestamp();
+        if !cooldowns.append_limited(key, message.get_id().to_string(), now - self.period, self.uses).await? {
+            return Ok(false);
+        }
+
+        let expired = cooldowns.query(Query::new()
+            .key(key)
+            .before(now - self.period)).await?;
+        for entry in expired {
+            cooldowns.delete_by_id(entry.id).await?;
+        }
+        Ok(true)
+    }
+
+    // Gives back the use reserved for the message, if it reserved one.
+    async fn release(&self, key: &str, message: &MessageManager) -> DBResult<()> {
+        let cooldowns = CooldownsDB::get_instance().scope(message);
+        let id = message.get_id().to_string();
+        for entry in cooldowns.query(Query::new().key(key)).await? {
+            if entry.value == id {
+                cooldowns.delete_by_id(entry.id).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Middleware for Cooldown {
+
+    fn before<'a>(&'a self, command: &'a dyn Command, params: &'a CommandParams) -> BoxedFuture<'a, bool> {
+        Box::pin(async move {
+
+            let message = &params.message;
+            if self.bypassed(message).await {
+                return true;
+            }
+
+            let key = self.bucket_key(command, message);
+            let available_at = match self.reserve(&key, message).await {
+                Ok(true) => return true,
+                Ok(false) => self.available_at(&key, message).await,
+                Err(err) => Err(err),
+            };
+            match available_at {
+                Ok(timestamp) => {
+                    let wait = timestamp.map_or(1, |timestamp| (timestamp - Utc::now().timestamp()).max(1)) as u64;
+                    message.reply_failure(text!("cooldown.wait",
+                        command = format!("{}{}", message.get_prefix().unwrap_or_default(), message.get_command().unwrap_or_default()),
+                        wait = format_duration(wait))).await;
+                }
+                Err(err) => message.reply_db_error(err).await,
+            }
+            false
+        })
+    }
+
+    fn after<'a>(&'a self,
+                 command: &'a dyn Command,
+                 params: &'a CommandParams,
+                 outcome: CommandOutcome,
+                 _elapsed: Duration) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+
+            if outcome == CommandOutcome::Success {
+                return;
+            }
+
+            // staff that bypassed the cooldown did not reserve a use
+            let message = &params.message;
+            let key = self.bucket_key(command, message);
+            if let Err(err) = self.release(&key, message).await {
+                Logger::err_long("Failed to release cooldown", &err.to_string());
+            }
+        })
+    }
+}
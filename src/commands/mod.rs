@@ -4,6 +4,17 @@ pub mod command_manager;
 pub mod command;
 pub use command::{CommandType, Command, UserDecorator, NumberDecorator, TimeDecorator};
 
+pub mod help;
+pub use help::{Help, HelpEntry};
+
+pub mod middleware;
+pub use middleware::{Middleware, DeleteMessage, Analytics, ReportErrors, Cooldown, CooldownBucket};
+#[cfg(feature = "debug")]
+pub use middleware::LogCommands;
+
+#[cfg(test)]
+mod tests;
+
 // ---- src/commands/casual/ ---- //
 
 pub mod casual;
@@ -23,6 +34,7 @@ pub use casual::remind::RemindCommand;
 pub use casual::remove_reminder::RemoveReminderCommand;
 pub use casual::tweet::TweetCommand;
 pub use casual::deadchat::DeadchatCommand;
+pub use casual::language::LanguageCommand;
 
 // ---- src/commands/games/ ---- //
 
@@ -59,6 +71,11 @@ pub use moderation::notes::NotesCommand;
 pub use moderation::add_note::AddNoteCommand;
 pub use moderation::remove_note::RemoveNoteCommand;
 pub use moderation::history::HistoryCommand;
+pub use moderation::backup::BackupCommand;
+pub use moderation::config::ConfigCommand;
+pub use moderation::search::SearchCommand;
+pub use moderation::level::LevelCommand;
+pub use moderation::command_stats::CommandStatsCommand;
 
 // ---- src/commands/tickets/ ---- //
 
@@ -19,32 +19,24 @@ impl Command for AddNoteCommand {
             CommandType::Moderation,
             nonempty!["add-note".to_string(), "addnote".to_string()]
         )
+            .level(PermissionLevel::HeadMod)
             .add_constant(vec!["-label", "-content"], true)
             .example("-label Edate -content Edating is a great fallacy!")
     }
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
         Box::pin(
             async move {
 
                 let message = &params.message;
 
-                if !message.has_parameter("content") || !message.has_parameter("label") {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-
-                let label = Note::escape(message.get_parameter("label"));
-                let content = message.get_parameter("content");
+                let label = Note::escape(params.arguments.text("label").unwrap_or_default().to_string());
+                let content = params.arguments.text("content").unwrap_or_default().to_string();
 
                 let note = Note::new(content);
-                NotesDB::get_instance().set(&label, &note.into()).await;
+                if let Err(err) = NotesDB::get_instance().scope(message).set(&label, note).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 message.reply_success().await;
             }
@@ -0,0 +1,105 @@
+use serenity::all::*;
+use serenity::builder::CreateAttachment;
+use nonempty::{NonEmpty, nonempty};
+
+use crate::commands::command::{CommandType, Command, CommandParams};
+use crate::utility::*;
+use crate::databases::*;
+use crate::databases::Scope;
+
+
+pub struct BackupCommand;
+
+impl BackupCommand {
+
+    // only the rows of the guild the command is used in are exported
+    async fn export(&self, message: &MessageManager, csv: bool) {
+
+        let scope = Some(Scope::from(message));
+        let files = match csv {
+            true  => archive::export_csv(scope).await,
+            false => archive::export_json(scope).await
+                .map(|content| vec![(format!("backup-{}.json", chrono::Utc::now().format("%Y-%m-%d")), content)]),
+        };
+        let files = match files {
+            Ok(files) => files,
+            Err(err) => return message.reply_db_error(err).await,
+        };
+
+        let attachments = files.into_iter()
+            .map(|(name, content)| CreateAttachment::bytes(content.into_bytes(), name))
+            .collect::<Vec<CreateAttachment>>();
+        let reply = CreateMessage::new()
+            .content(message.text("backup.exported").await)
+            .add_files(attachments);
+        if message.reply(reply).await.is_err() {
+            message.reply_failure("backup.upload-failed").await;
+        }
+    }
+
+    async fn import(&self, message: &MessageManager) {
+
+        // the archive is either attached to the command or to the message it replies to
+        let mut attachments = message.get_attachments().await.clone();
+        if attachments.is_empty() && message.is_referencing() {
+            attachments = message.get_referenced().attachments.clone();
+        }
+        if attachments.is_empty() {
+            return message.reply_failure("backup.no-attachment").await;
+        }
+
+        let mut files = Vec::new();
+        for attachment in attachments {
+            let content = attachment.download().await.ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok());
+            match content {
+                Some(content) => files.push((attachment.filename, content)),
+                None => return message.reply_failure(text!("backup.read-failed", file = attachment.filename)).await,
+            }
+        }
+
+        // the rows of other guilds are neither replaced nor accepted
+        let scope = Some(Scope::from(message));
+        let imported = match files[0].0.ends_with(".json") {
+            true if files.len() == 1 => archive::import_json(scope, &files[0].1).await,
+            true => return message.reply_failure("backup.multiple-json").await,
+            false => archive::import_csv(scope, files).await,
+        };
+        match imported {
+            Ok(count) => {
+                let _ = message.reply(message.text(text!("backup.imported", count = count)).await).await;
+            }
+            Err(err) => message.reply_db_error(err).await,
+        }
+    }
+}
+
+impl Command for BackupCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Moderation,
+            nonempty!["backup".to_string()]
+        )
+            .level(PermissionLevel::HeadMod)
+            .new_usage()
+            .add_constant("export", false)
+            .add_optional("-csv", ArgumentType::Flag)
+            .new_usage()
+            .add_constant("import", false)
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+
+                match params.arguments.has("export") {
+                    true  => self.export(message, params.arguments.has("csv")).await,
+                    false => self.import(message).await,
+                }
+            }
+        )
+    }
+}
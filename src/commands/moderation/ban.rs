@@ -13,19 +13,14 @@ pub struct BanCommand;
 
 impl Command for BanCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_mod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["ban".to_string()]
         )
-            .add_required("user")
-            .add_optional("reason")
+            .level(PermissionLevel::Mod)
+            .add_required("user", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
             .example("@JuicyJuggler we could not handle you anymore")
     }
 
@@ -37,18 +32,18 @@ impl Command for BanCommand {
                 let target = &params.target.unwrap();
 
                 // obtain the reason
-                let mut reason = message.payload_without_mentions(None, None);
-                if reason.is_empty() {
-                    reason = "No reason provided.".to_string();
-                }
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
 
                 let resolver = message.get_resolver();
-                if resolver.is_trial(&target).await {
-                    message.reply_failure("You can't ban a moderator.").await;
+                if resolver.has_level(target, PermissionLevel::Trial).await {
+                    message.reply_failure("ban.moderator").await;
                     return;
                 }
 
-                let member = resolver.resolve_member(&target).await;
+                let member = resolver.resolve_member(target).await;
                 if let Some(member) = member {
 
                     // log ban to database
@@ -56,20 +51,17 @@ impl Command for BanCommand {
                         message.get_author().id.to_string(),
                         reason.clone()
                     );
-                    BansDB::get_instance()
-                        .append(&target.id.to_string(), &log.into()).await;
+                    if let Err(err) = BansDB::get_instance().scope(message)
+                        .append(&target.id.to_string(), log).await {
+                        return message.reply_db_error(err).await;
+                    }
 
                     // ban the user and handle potential problems
-                    if let Err(why) = member.ban_with_reason(&resolver, 0, &reason).await {
+                    if let Err(why) = resolver.ban(member.user.id, &reason).await {
 
                         // log error
-                        let warning = MessageManager::create_embed(|embed| {
-                            embed
-                                .title("Failed to ban user")
-                                .description(&format!(
-                                        "The user could not be banned. The reason for this is: `{:?}`. Please consult an Administrator so that they can perform a manual ban or resolve the problem otherwisely.",
-                                        why))
-                        }).await;
+                        let warning = message.create_text_embed("ban.failed-title",
+                            text!("ban.failed", error = why)).await;
                         let _ = message.reply(warning).await;
 
                         return;
@@ -77,39 +69,36 @@ impl Command for BanCommand {
 
                     // log ban to mod logs
                     let log_message = message.get_log_builder()
-                        .title("[BAN]")
-                        .target(&target)
+                        .title("ban.log-title")
+                        .target(target)
                         .color(0xff8200)
                         .staff()
-                        .user(&target)
-                        .arbitrary("Reason", &reason)
+                        .user(target)
+                        .arbitrary("log.reason", &reason)
                         .timestamp()
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
-                    let _ = modlogs.send_message(resolver, log_message.to_message()).await;
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let _ = resolver.send_message(modlogs, log_message).await;
 
                     // inform member of the ban and how to appeal
                     let guild = resolver.resolve_guild(None).await.unwrap();
                     let notify_message = message.get_log_builder()
-                        .title("You've been banned!")
-                        .description(&format!("You have been banned from {} for \"{}\"\nYou can appeal your ban [here](https://dyno.gg/form/f2f3a893) if you believe that we made a mistake!",
-                            guild.name,
-                            reason))
-                        .target(&target)
+                        .for_user(target.id)
+                        .title("ban.notice-title")
+                        .description(text!("ban.notice", guild = guild.name, reason = reason))
+                        .target(target)
                         .no_thumbnail()
                         .color(0xff0000)
                         .build().await;
-                    let sent = target.dm(resolver, notify_message.to_message()).await;
+                    let sent = resolver.direct_message(target.id, notify_message).await;
 
                     match sent {
                         Ok(_)  => message.reply_success().await,
                         Err(_) => {
-                            let embed = MessageManager::create_embed(|embed| {
-                                embed
-                                    .title("Notice")
-                                    .description("I could not send a DM to the user.")
-                            }).await;
+                            let embed = message.create_text_embed("moderation.dm-failed-title", "moderation.dm-failed").await;
                             let _ = message.reply(embed).await;
                         }
                     };
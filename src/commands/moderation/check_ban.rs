@@ -13,18 +13,13 @@ pub struct CheckBanCommand;
 
 impl Command for CheckBanCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["check-ban".to_string(), "checkban".to_string(), "bans".to_string()]
         )
-            .add_required("user-id")
+            .level(PermissionLevel::Trial)
+            .add_required("user-id", ArgumentType::User)
             .example("996364193588592740")
     }
 
@@ -33,13 +28,7 @@ impl Command for CheckBanCommand {
             async move {
 
                 let message = &params.message;
-                let target_id = &message.payload(None, None);
-
-                let id = target_id.parse::<u64>();
-                if id.is_err() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
+                let target_id = &params.arguments.user("user-id").unwrap().to_string();
 
                 // try to resolve target
                 let target = message.get_resolver()
@@ -59,10 +48,11 @@ impl Command for CheckBanCommand {
                             .next();
 
                         // collect information abount bans
+                        let no_reason = message.guild_text("moderation.no-reason").await;
                         let mut all_bans = match current_ban {
                             Some(ban) => {
                                 vec![(
-                                    ban.reason.unwrap_or("No reason provided.".to_string()),
+                                    ban.reason.unwrap_or(no_reason),
                                     None
                                 )]
                             },
@@ -71,7 +61,7 @@ impl Command for CheckBanCommand {
 
                         // add bans the bot has issued that have been revoked by now
                         if target.is_some() {
-                            if let Ok(recorded_bans) = BansDB::get_instance().get_all(target_id).await {
+                            if let Ok(recorded_bans) = BansDB::get_instance().scope(message).get_all(target_id).await {
 
                                 recorded_bans.into_iter()
                                     .for_each(|ban| {
@@ -98,13 +88,16 @@ impl Command for CheckBanCommand {
                         if all_bans.is_empty() {
 
                             // resolve bot
-                            let bot_id: UserId = ConfigDB::get_instance()
-                                .get("bot_id").await.unwrap().into();
+                            let bot_id: UserId = match BotConfig::get(message).await {
+                                Ok(config) => config.bot_id,
+                                Err(err) => return message.reply_db_error(err).await,
+                            };
                             let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
 
                             // create embed
                             let embed = message.get_log_builder()
-                                .title(format!("{} has not been banned before.", name))
+                                .for_author()
+                                .title(text!("check-ban.not-banned", user = name))
                                 .target(&bot)
                                 .no_thumbnail()
                                 .build().await;
@@ -117,8 +110,9 @@ impl Command for CheckBanCommand {
 
                             // create embed
                             let mut builder = message.get_log_builder()
+                                .for_author()
                                 .no_thumbnail()
-                                .title(format!("{}'s Bans", name));
+                                .title(text!("check-ban.title", user = name));
 
                             // add target info if available
                             if let Some(ref target) = target {
@@ -126,15 +120,16 @@ impl Command for CheckBanCommand {
                             }
 
                             // add reasons if available
+                            let no_timestamp = message.text("check-ban.no-timestamp").await;
                             let reasons = all_bans.iter()
                                 .map(|ban| {
                                     match ban.1 {
                                         Some(timestamp) => format!("<t:{}> `>` {}", timestamp, ban.0),
-                                        None => format!("No timestamp available `>` {}", ban.0),
+                                        None => format!("{} `>` {}", no_timestamp, ban.0),
                                     }
                                 })
                                 .collect::<Vec<_>>();
-                            builder = builder.description(reasons.join("\n"));
+                            builder = builder.description(Text::plain(reasons.join("\n")));
 
                             // add bans to embed
                             let embed = builder.build().await;
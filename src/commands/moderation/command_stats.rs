@@ -0,0 +1,110 @@
+use nonempty::{NonEmpty, nonempty};
+use chrono::Utc;
+use strum::IntoEnumIterator;
+
+use std::collections::HashMap;
+
+use crate::commands::command::*;
+use crate::commands::command_manager;
+use crate::utility::*;
+use crate::databases::*;
+
+
+pub struct CommandStatsCommand;
+
+impl CommandStatsCommand {
+
+    const DEFAULT_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+    // the most frequent values with their counts, most frequent first
+    pub fn top(values: impl Iterator<Item = String>, limit: usize) -> Vec<(String, usize)> {
+        let mut counts = HashMap::<String, usize>::new();
+        values.for_each(|value| *counts.entry(value).or_default() += 1);
+        let mut counts = counts.into_iter().collect::<Vec<(String, usize)>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(limit);
+        counts
+    }
+
+    fn ranking(counts: &[(String, usize)], none: &str, format: impl Fn(&str) -> String) -> String {
+        match counts.is_empty() {
+            true  => none.to_string(),
+            false => counts.iter()
+                .map(|(value, count)| format!("{} `>` **{}**", format(value), count))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl Command for CommandStatsCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Moderation,
+            nonempty!["command-stats".to_string(), "commandstats".to_string()]
+        )
+            .level(PermissionLevel::Admin)
+            .add_optional("period", ArgumentType::Duration)
+            .add_optional("command", ArgumentType::Text)
+            .example("7d warn")
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+                let period = params.arguments.duration("period").unwrap_or(Self::DEFAULT_PERIOD);
+                let command = params.arguments.text("command");
+
+                let mut query = Query::new()
+                    .after(Utc::now().timestamp() - period as i64);
+                if let Some(command) = command {
+                    if !command_manager::is_trigger(command) {
+                        return message.reply_failure(text!("command-stats.unknown-command", command = command)).await;
+                    }
+                    query = query.key(command);
+                }
+                let uses = match CommandUsesDB::get_instance().scope(message).query(query).await {
+                    Ok(uses) => uses,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+
+                let commands = Self::top(uses.iter().map(|command_use| command_use.key.clone()), 10);
+                let users = Self::top(uses.iter().map(|command_use| command_use.user_id.clone()), 5);
+                let channels = Self::top(uses.iter().map(|command_use| command_use.channel_id.clone()), 5);
+                let outcomes = CommandOutcome::iter()
+                    .map(|outcome| format!("{}: **{}**",
+                        outcome,
+                        uses.iter().filter(|command_use| command_use.outcome == outcome.to_string()).count()))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let average = match uses.is_empty() {
+                    true  => 0,
+                    false => uses.iter().map(|command_use| command_use.duration).sum::<i64>() / uses.len() as i64,
+                };
+
+                let title = match command {
+                    Some(command) => text!("command-stats.command-title", command = command, period = format_duration(period)),
+                    None => text!("command-stats.title", period = format_duration(period)),
+                };
+                let none = message.text("command-stats.none").await;
+                let uses_count = message.text(text!("command-stats.uses-count", count = uses.len(), average = average)).await;
+                let embed = message.get_log_builder()
+                    .for_author()
+                    .title(title)
+                    .no_thumbnail()
+                    .arbitrary("command-stats.uses", uses_count)
+                    .arbitrary("command-stats.outcomes", outcomes)
+                    .arbitrary_block("command-stats.top-commands", Self::ranking(&commands, &none, |trigger| format!("`{}`", trigger)))
+                    .arbitrary("command-stats.top-users", Self::ranking(&users, &none, |user| format!("<@{}>", user)))
+                    .arbitrary("command-stats.top-channels", Self::ranking(&channels, &none, |channel| format!("<#{}>", channel)))
+                    .build().await;
+
+                let _ = message.reply(embed).await;
+            }
+        )
+    }
+
+}
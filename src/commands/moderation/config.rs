@@ -0,0 +1,264 @@
+use serenity::all::*;
+use nonempty::{NonEmpty, nonempty};
+
+use crate::commands::command::{CommandType, Command, CommandParams};
+use crate::commands::command_manager;
+use crate::databases::config::{self as bot_config, ConfigKind};
+use crate::databases::scope::{Scope, GLOBAL_KEYS};
+use crate::utility::*;
+use crate::databases::*;
+
+
+pub struct ConfigCommand;
+
+impl ConfigCommand {
+
+    // Checks that a value is well formed and that the channel, category or
+    // role it refers to exists in this guild.
+    async fn check_value(&self, message: &MessageManager, kind: ConfigKind, value: &str) -> std::result::Result<(), Text> {
+        bot_config::check_format(kind, value)?;
+        let resolver = message.get_resolver();
+        let exists = match kind {
+            ConfigKind::Channel | ConfigKind::Category => {
+                let channel = resolver.resolve_guild_channel(ChannelId::new(value.parse().unwrap())).await;
+                let is_category = kind == ConfigKind::Category;
+                channel.is_some_and(|channel| (channel.kind == ChannelType::Category) == is_category)
+            }
+            ConfigKind::Location => resolver.resolve_guild_channel(ChannelId::new(value.parse().unwrap())).await.is_some(),
+            ConfigKind::Role => resolver.resolve_guild(None).await
+                .is_some_and(|guild| guild.roles.contains_key(&RoleId::new(value.parse().unwrap()))),
+            _ => true,
+        };
+        match exists {
+            true  => Ok(()),
+            false => Err(text!("config.not-in-guild", kind = kind.to_string().to_lowercase(), value = value)),
+        }
+    }
+
+    fn format_values(values: &[String], locale: Locale) -> String {
+        match values.is_empty() {
+            true  => Text::new("config.not-set").localize(locale),
+            false => values.iter()
+                .map(|value| format!("`{}`", value))
+                .collect::<Vec<String>>()
+                .join(", "),
+        }
+    }
+
+    // the values of a key in this guild, or the inherited global ones
+    async fn display_values(&self, message: &MessageManager, key: &str) -> DBResult<String> {
+        let locale = message.locale().await;
+        let values = bot_config::values(message.into(), key).await?;
+        if !values.is_empty() {
+            return Ok(Self::format_values(&values, locale));
+        }
+        let values = bot_config::values(Scope::Global, key).await?;
+        match values.is_empty() {
+            true  => Ok(Self::format_values(&values, locale)),
+            false => Ok(text!("config.global", values = Self::format_values(&values, locale)).localize(locale)),
+        }
+    }
+
+    async fn get(&self, message: &MessageManager, key: &str) {
+        if bot_config::kind(key).is_none() {
+            return message.reply_failure(text!("config.unknown-key", key = key)).await;
+        }
+        let values = match self.display_values(message, key).await {
+            Ok(values) => values,
+            Err(err) => return message.reply_db_error(err).await,
+        };
+        let embed = message.create_text_embed(text!("config.key-title", key = key), Text::plain(values)).await;
+        let _ = message.reply(embed).await;
+    }
+
+    async fn list(&self, message: &MessageManager) {
+
+        // all known keys and the ones that were set in addition, like retentions
+        let mut keys = bot_config::known_keys().into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        for scope in [Scope::from(message), Scope::Global] {
+            let stored = match get_storage(&DB::Config).get_keys(scope).await {
+                Ok(stored) => stored,
+                Err(err) => return message.reply_db_error(err).await,
+            };
+            let mut stored = stored.into_iter()
+                .filter(|key| bot_config::kind(key).is_some() && !keys.contains(key))
+                .collect::<Vec<String>>();
+            stored.sort();
+            keys.extend(stored);
+        }
+
+        let mut lines = Vec::new();
+        for key in keys {
+            match self.display_values(message, &key).await {
+                Ok(values) => lines.push(format!("**{}** `>` {}", key, values)),
+                Err(err) => return message.reply_db_error(err).await,
+            }
+        }
+        let embed = message.create_text_embed("config.title", Text::plain(lines.join("\n"))).await;
+        let _ = message.reply(embed).await;
+    }
+
+    async fn set(&self, message: &MessageManager, key: &str, values: Vec<String>) {
+
+        let Some(kind) = bot_config::kind(key) else {
+            return message.reply_failure(text!("config.unchangeable", key = key)).await;
+        };
+        if GLOBAL_KEYS.contains(&key) {
+            return message.reply_failure(text!("config.global-key", key = key)).await;
+        }
+        if let Some(trigger) = bot_config::configured_command(key) {
+            if !command_manager::is_trigger(trigger) {
+                return message.reply_failure(text!("config.unknown-command", command = trigger)).await;
+            }
+        }
+        if values.len() > 1 && !bot_config::is_list(key) {
+            return message.reply_failure(text!("config.single-value", key = key)).await;
+        }
+        for value in &values {
+            if let Err(reason) = self.check_value(message, kind, value).await {
+                return message.reply_failure(reason).await;
+            }
+        }
+
+        let staff_id = message.get_author().id.to_string();
+        match bot_config::change(message.into(), key, values, &staff_id).await {
+            Ok(change) => self.reply_change(message, key, &change).await,
+            Err(err) => message.reply_db_error(err).await,
+        }
+    }
+
+    async fn history(&self, message: &MessageManager, key: Option<&str>) {
+
+        let mut query = Query::new()
+            .order(Order::Descending)
+            .limit(15);
+        if let Some(key) = key {
+            query = query.key(key);
+        }
+        let changes = match ConfigHistoryDB::get_instance().scope(message).query(query).await {
+            Ok(changes) => changes,
+            Err(err) => return message.reply_db_error(err).await,
+        };
+
+        let locale = message.locale().await;
+        let description = match changes.is_empty() {
+            true  => Text::new("config.no-changes").localize(locale),
+            false => changes.iter()
+                .map(|change| text!("config.change-line",
+                    id = change.id,
+                    timestamp = change.timestamp,
+                    key = &change.key,
+                    before = Self::format_values(&Self::split(&change.old_value), locale),
+                    after = Self::format_values(&Self::split(&change.new_value), locale),
+                    staff = format!("<@{}>", change.staff_id)).localize(locale))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        };
+        let embed = MessageManager::create_embed(|embed| {
+            embed
+                .title(Text::new("config.history-title").localize(locale))
+                .description(description)
+                .footer(CreateEmbedFooter::new(Text::new("config.history-footer").localize(locale)))
+        }).await;
+        let _ = message.reply(embed).await;
+    }
+
+    // restores the values a key had before the given change
+    async fn rollback(&self, message: &MessageManager, id: i64) {
+
+        let change = match ConfigHistoryDB::get_instance().scope(message).query(Query::new().id(id)).await {
+            Ok(changes) if !changes.is_empty() => changes[0].clone(),
+            Ok(_) => return message.reply_failure("config.change-not-found").await,
+            Err(err) => return message.reply_db_error(err).await,
+        };
+
+        let staff_id = message.get_author().id.to_string();
+        match bot_config::change(message.into(), &change.key, Self::split(&change.old_value), &staff_id).await {
+            Ok(rollback) => self.reply_change(message, &change.key, &rollback).await,
+            Err(err) => message.reply_db_error(err).await,
+        }
+    }
+
+    fn split(values: &str) -> Vec<String> {
+        values.split_whitespace().map(String::from).collect()
+    }
+
+    async fn reply_change(&self, message: &MessageManager, key: &str, change: &ConfigChange) {
+        let locale = message.locale().await;
+        let embed = message.get_log_builder()
+            .for_author()
+            .title("config.log-title")
+            .description(text!("config.changed", key = key))
+            .staff()
+            .arbitrary("config.before", Self::format_values(&Self::split(&change.old_value), locale))
+            .arbitrary("config.after", Self::format_values(&Self::split(&change.new_value), locale))
+            .timestamp()
+            .build().await;
+        let _ = message.reply(embed).await;
+    }
+}
+
+impl Command for ConfigCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Moderation,
+            nonempty!["config".to_string()]
+        )
+            .level(PermissionLevel::Admin)
+            .new_usage()
+            .add_constant("get", false)
+            .add_required("key", ArgumentType::Text)
+            .new_usage()
+            .add_constant("set", false)
+            .add_required("key", ArgumentType::Text)
+            .add_required("value ...", ArgumentType::Text)
+            .new_usage()
+            .add_constant("list", false)
+            .new_usage()
+            .add_constant("history", false)
+            .add_optional("key", ArgumentType::Text)
+            .new_usage()
+            .add_constant("rollback", false)
+            .add_required("change", ArgumentType::Text)
+            .example("set channel_modlogs 1100000000000000000")
+    }
+
+    fn needs_config(&self) -> bool {
+        false
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+
+                let arguments = &params.arguments;
+                let key = arguments.text("key");
+
+                if arguments.has("get") {
+                    self.get(message, key.unwrap_or_default()).await;
+                } else if arguments.has("set") {
+                    let values = arguments.text("value").unwrap_or_default()
+                        .split_whitespace()
+                        .map(|value| value.to_string())
+                        .collect();
+                    self.set(message, key.unwrap_or_default(), values).await;
+                } else if arguments.has("list") {
+                    self.list(message).await;
+                } else if arguments.has("history") {
+                    self.history(message, key).await;
+                } else {
+                    let change = arguments.text("change").unwrap_or_default();
+                    match change.trim_start_matches('#').parse::<i64>() {
+                        Ok(id) => self.rollback(message, id).await,
+                        Err(_) => self.invalid_usage(params).await,
+                    }
+                }
+            }
+        )
+    }
+}
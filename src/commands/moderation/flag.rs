@@ -13,23 +13,15 @@ pub struct FlagCommand;
 
 impl Command for FlagCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["manually-flag".to_string(), "manual-flag".to_string(), "manflag".to_string()]
         )
-            .add_required("user")
-            .add_optional("reason")
-            .new_usage()
-            .add_required("user")
-            .add_optional("reason")
-            .add_constant("-monthly", false)
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
+            .add_optional("-monthly", ArgumentType::Flag)
             .example("@GoodGirl repeatedly being bad -monthly")
     }
 
@@ -41,11 +33,11 @@ impl Command for FlagCommand {
                 let target = &params.target.unwrap();
 
                 // obtain parameters
-                let mut reason = message.payload_without_mentions(None, Some(vec!["-monthly".to_string()]));
-                if reason.is_empty() {
-                    reason = "No reason provided.".to_string();
-                }
-                let monthly = message.has_parameter("monthly");
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
+                let monthly = params.arguments.has("monthly");
 
                 // log to database
                 let log = FlagLog::new(
@@ -53,24 +45,28 @@ impl Command for FlagCommand {
                     reason,
                     monthly,
                 );
-                FlagsDB::get_instance()
-                    .append(&target.id.to_string(), &log.into()).await;
+                if let Err(err) = FlagsDB::get_instance().scope(message)
+                    .append(&target.id.to_string(), log).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 // log to mod logs
                 let timestamp_now = chrono::Utc::now().timestamp();
                 let embed = message.get_log_builder()
-                    .title("[FLAG]")
+                    .title("flag.log-title")
                     .target(&target)
                     .color(0xff8200)
                     .staff()
                     .user(&target)
-                    .labeled_timestamp("Flag Until", match monthly {
+                    .labeled_timestamp("flag.until", match monthly {
                         true  => timestamp_now + (30 * 24 * 60 * 60),
                         false => timestamp_now + (7  * 24 * 60 * 60)
                     })
                     .build().await;
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let _ = modlogs.send_message(message, embed.to_message()).await;
 
                 message.reply_success().await;
@@ -14,21 +14,14 @@ pub struct FlagsCommand;
 
 impl Command for FlagsCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["flags".to_string()]
         )
-            .add_required("user")
-            .new_usage()
-            .add_required("user")
-            .add_constant("-more", false)
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("-more", ArgumentType::Flag)
             .example("@BadBoy -more")
     }
 
@@ -39,7 +32,7 @@ impl Command for FlagsCommand {
                 let message = &params.message;
                 let target = &params.target.unwrap();
 
-                let flags = FlagsDB::get_instance()
+                let flags = FlagsDB::get_instance().scope(message)
                     .get_all(&target.id.to_string()).await;
 
                 if let Ok(mut flags) = flags {
@@ -49,7 +42,7 @@ impl Command for FlagsCommand {
 
                     // get amount of flags to display
                     let limit = min(total_flags,
-                        match message.has_parameter("more") {
+                        match params.arguments.has("more") {
                             true => 24,
                             false => 5
                         });
@@ -58,21 +51,21 @@ impl Command for FlagsCommand {
                     // create embed
                     let name = message.get_resolver().resolve_name(target);
                     let mut builder = message.get_log_builder()
+                        .for_author()
                         .target(target)
-                        .title(format!("{}'s Flags", name))
+                        .title(text!("flags.title", user = name))
                         .no_thumbnail();
 
                     // add flags to embed
                     let embed = match flags.len() {
-                        0 => builder.description("No registered flags.").build().await,
+                        0 => builder.description("flags.none").build().await,
                         len @ _ => {
                             for flag in flags.into_iter() {
                                 builder = builder.mod_log(&ModLog::from(flag));
                             }
+                            let footer = message.text(text!("flags.footer", count = len, total = total_flags)).await;
                             builder.build().await
-                                .footer(CreateEmbedFooter::new(
-                                    format!("Displaying {} of {} Flags", len, total_flags)
-                                ))
+                                .footer(CreateEmbedFooter::new(footer))
                         }
                     };
 
@@ -2,6 +2,7 @@
 use serenity::all::ChannelId;
 use nonempty::{NonEmpty, nonempty};
 use chrono::Utc;
+use strum::IntoEnumIterator;
 
 use std::iter::FromIterator;
 use std::collections::HashMap;
@@ -15,18 +16,15 @@ pub struct HistoryCommand;
 
 impl Command for HistoryCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["history".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("-archived", ArgumentType::Flag)
+            .add_optional("-removed", ArgumentType::Flag)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -35,6 +33,7 @@ impl Command for HistoryCommand {
 
                 let message = &params.message;
                 let target  = &params.target.unwrap();
+                let locale  = message.locale().await;
 
                 let mut history = Vec::<(i64, DB, String)>::new();
                 let symbols = vec![
@@ -46,16 +45,61 @@ impl Command for HistoryCommand {
                 let symbols: HashMap<String, &str> = HashMap::from_iter(symbols.into_iter());
 
                 // fetch moderation logs
-                let warnings = WarningsDB::get_instance().get_all(&target.id.to_string()).await.unwrap();
-                let mutes    = MutesDB::get_instance().get_all(&target.id.to_string()).await.unwrap();
-                let bans     = BansDB::get_instance().get_all(&target.id.to_string()).await.unwrap();
-                let flags    = FlagsDB::get_instance().get_all(&target.id.to_string()).await.unwrap();
+                let logs = (
+                    WarningsDB::get_instance().scope(message).get_all(&target.id.to_string()).await,
+                    MutesDB::get_instance().scope(message).get_all(&target.id.to_string()).await,
+                    BansDB::get_instance().scope(message).get_all(&target.id.to_string()).await,
+                    FlagsDB::get_instance().scope(message).get_all(&target.id.to_string()).await,
+                );
+                let (warnings, mutes, bans, flags) = match logs {
+                    (Ok(warnings), Ok(mutes), Ok(bans), Ok(flags)) => (warnings, mutes, bans, flags),
+                    (Err(err), _, _, _) | (_, Err(err), _, _) | (_, _, Err(err), _) | (_, _, _, Err(err)) =>
+                        return message.reply_db_error(err).await,
+                };
 
                 // construct correctly sorted history
                 warnings.into_iter().for_each(|warning| history.push((warning.timestamp, DB::Warnings, warning.reason)));
                 mutes.into_iter().for_each(|mute| history.push((mute.timestamp, DB::Mutes, mute.reason)));
                 bans.into_iter().for_each(|ban| history.push((ban.timestamp, DB::Bans, ban.reason)));
                 flags.into_iter().for_each(|flag| history.push((flag.timestamp, DB::Flags, flag.reason)));
+
+                // include records that were moved to the archive by the retention job
+                if params.arguments.has("archived") {
+                    let archived = match ArchiveDB::get_instance().scope(message).get_all(&target.id.to_string()).await {
+                        Ok(archived) => archived,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    for log in archived {
+                        let Some(table) = DB::iter().find(|table| table.to_string() == log.table && symbols.contains_key(&log.table)) else {
+                            continue;
+                        };
+                        match log.restore::<ModLog>() {
+                            Ok(log) => history.push((log.timestamp, table,
+                                text!("history.archived", reason = log.reason).localize(locale))),
+                            Err(err) => return message.reply_db_error(err).await,
+                        }
+                    }
+                }
+
+                // include records that were removed by staff
+                if params.arguments.has("removed") {
+                    let removed = match RemovalsDB::get_instance().scope(message).get_all(&target.id.to_string()).await {
+                        Ok(removed) => removed,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    for removal in removed {
+                        let Some(table) = DB::iter().find(|table| table.to_string() == removal.table && symbols.contains_key(&removal.table)) else {
+                            continue;
+                        };
+                        match removal.restore::<ModLog>() {
+                            Ok(log) => history.push((log.timestamp, table, text!("history.removed",
+                                reason = log.reason,
+                                staff = format!("<@{}>", removal.staff_id),
+                                removal_reason = removal.reason).localize(locale))),
+                            Err(err) => return message.reply_db_error(err).await,
+                        }
+                    }
+                }
                 history.sort_by(|a, b| a.0.cmp(&b.0));
 
                 // construct description
@@ -69,10 +113,11 @@ impl Command for HistoryCommand {
 
                 // create embed
                 let embed = message.get_log_builder()
-                    .title(format!("{}'s Moderation History", message.get_resolver().resolve_name(target)))
+                    .for_author()
+                    .title(text!("history.title", user = message.get_resolver().resolve_name(target)))
                     .target(target)
                     .no_thumbnail()
-                    .description(description)
+                    .description(Text::plain(description))
                     .build().await;
 
                 let _ = message.reply(embed).await;
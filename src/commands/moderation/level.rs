@@ -0,0 +1,60 @@
+use nonempty::{NonEmpty, nonempty};
+
+use crate::commands::command::*;
+use crate::utility::*;
+use crate::databases::*;
+
+
+pub struct LevelCommand;
+
+impl Command for LevelCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Moderation,
+            nonempty!["level".to_string()]
+        )
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .example("@Poggy")
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+                let target = &params.target.unwrap();
+                let resolver = message.get_resolver();
+
+                let config = match BotConfig::get(message).await {
+                    Ok(config) => config,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let level = resolver.level(target).await;
+
+                // the roles of the member that grant the level
+                let member_roles = resolver.resolve_member(target).await
+                    .map(|member| member.roles)
+                    .unwrap_or_default();
+                let roles = config.roles(level).iter()
+                    .filter(|role| member_roles.contains(role))
+                    .map(|role| format!("<@&{}>", role))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                let mut embed = message.get_log_builder()
+                    .for_author()
+                    .target(target)
+                    .title(text!("level.title", user = resolver.resolve_name(target)))
+                    .arbitrary("level.level", format!("`{}`", level));
+                if let Some(key) = level.key() {
+                    embed = embed.arbitrary("level.granted-by", format!("{} `>` `{}`", roles, key));
+                }
+
+                let _ = message.reply(embed.build().await).await;
+            }
+        )
+    }
+
+}
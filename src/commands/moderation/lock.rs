@@ -17,18 +17,13 @@ pub struct LockCommand;
 
 impl Command for LockCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["lock".to_string(), "lock-user".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -38,15 +33,15 @@ impl Command for LockCommand {
                 let message = &params.message;
                 let target = &params.target.clone().unwrap();
 
-                if message.get_resolver().is_trial(&target).await {
-                    message.reply_failure("You cannot lock a moderator!").await;
+                if message.get_resolver().has_level(target, PermissionLevel::Trial).await {
+                    message.reply_failure("lock.moderator").await;
                     return;
                 }
 
                 if let Some(channel) = message.resolve_guild_channel().await {
 
                     // revoke member's permissions
-                    let handler = PermissionHandler::new(&message.get_resolver(), &channel);
+                    let handler = PermissionHandler::new(message.get_resolver(), &channel);
                     handler.deny_member(
                         vec![&Permissions::SEND_MESSAGES, &Permissions::VIEW_CHANNEL],
                         &target.id)
@@ -54,15 +49,17 @@ impl Command for LockCommand {
 
                     // log user lock to modlogs
                     let embed = message.get_log_builder()
-                        .title("[LOCK]")
+                        .title("lock.log-title")
                         .target(target)
                         .staff()
                         .user(target)
                         .channel()
                         .timestamp()
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let _ = modlogs.send_message(message, embed.to_message()).await;
 
                     message.reply_success().await;
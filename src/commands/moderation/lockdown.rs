@@ -18,19 +18,13 @@ pub struct LockdownCommand;
 
 impl Command for LockdownCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_mod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["lockdown".to_string()]
         )
-            .new_usage()
-            .add_constant("-end", false)
+            .level(PermissionLevel::Mod)
+            .add_optional("-end", ArgumentType::Flag)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -38,19 +32,18 @@ impl Command for LockdownCommand {
             async move {
 
                 let message = &params.message;
-                let end_lockdown = message.has_parameter("end");
+                let end_lockdown = params.arguments.has("end");
 
-                let categories: Vec<ChannelId> = ConfigDB::get_instance()
-                    .get_all("category_lockdown").await.unwrap()
-                    .into_iter()
-                    .map(|category| category.into())
-                    .collect();
+                let categories = match BotConfig::get(message).await {
+                    Ok(config) => config.category_lockdown.clone(),
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // get role id of @everyone
                 if let Some(guild) = message.get_guild() {
                     let everyone_role = guild.everyone_role();
                     let notification = message.get_log_builder()
-                        .title(if end_lockdown { "Lockdown ended" } else { "Server has been locked down!" })
+                        .title(if end_lockdown { "lockdown.ended" } else { "lockdown.started" })
                         .timestamp()
                         .build().await;
 
@@ -70,20 +63,22 @@ impl Command for LockdownCommand {
                                     }
 
                                     // send notification to channel
-                                    let _ = channel.send_message(message, notification.to_message()).await;
+                                    let _ = message.get_resolver().send_message(channel.id, notification).await;
                                 }
                             }).await;
                     }
 
                     // log lockdown to modlogs
                     let embed = message.get_log_builder()
-                        .title(if end_lockdown { "[LOCKDOWN END]" } else { "[LOCKDOWN]" })
+                        .title(if end_lockdown { "lockdown.end-log-title" } else { "lockdown.log-title" })
                         .staff()
                         .timestamp()
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
-                    let _ = modlogs.send_message(message, embed.to_message()).await;
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let _ = message.get_resolver().send_message(modlogs, embed).await;
 
                     message.reply_success().await;
                 }
@@ -24,3 +24,8 @@ pub mod notes;
 pub mod add_note;
 pub mod remove_note;
 pub mod history;
+pub mod backup;
+pub mod config;
+pub mod search;
+pub mod level;
+pub mod command_stats;
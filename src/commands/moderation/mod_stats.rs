@@ -44,18 +44,13 @@ impl ModStatsCommand {
 
 impl Command for ModStatsCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["mod-stats".to_string(),"modstats".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::HeadMod)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -66,30 +61,31 @@ impl Command for ModStatsCommand {
                 let target = &params.target.unwrap();
 
                 // fetch moderation logs
-                let warnings = WarningsDB::get_instance()
-                    .get_by_staff(&target.id.to_string()).await;
-                let mutes = MutesDB::get_instance()
-                    .get_by_staff(&target.id.to_string()).await;
-                let bans = BansDB::get_instance()
-                    .get_by_staff(&target.id.to_string()).await;
+                let logs = (
+                    WarningsDB::get_instance().scope(message).get_by_staff(&target.id.to_string()).await,
+                    MutesDB::get_instance().scope(message).get_by_staff(&target.id.to_string()).await,
+                    BansDB::get_instance().scope(message).get_by_staff(&target.id.to_string()).await,
+                );
+                let (warnings, mutes, bans) = match logs {
+                    (Ok(warnings), Ok(mutes), Ok(bans)) => (warnings, mutes, bans),
+                    (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => return message.reply_db_error(err).await,
+                };
 
                 let (warns_last_day, warns_last_week, warns_last_month) = ModStatsCommand::distribution(warnings);
                 let (mutes_last_day, mutes_last_week, mutes_last_month) = ModStatsCommand::distribution(mutes);
                 let (bans_last_day, bans_last_week, bans_last_month)    = ModStatsCommand::distribution(bans);
 
                 // create embed
+                let locale = message.locale().await;
+                let counts = |warnings, mutes, bans| text!("mod-stats.counts",
+                    warnings = warnings, mutes = mutes, bans = bans).localize(locale);
                 let embed = message.get_log_builder()
-                    .title(format!("{}'s Moderation Statistics", message.get_resolver().resolve_name(target)))
+                    .for_author()
+                    .title(text!("mod-stats.title", user = message.get_resolver().resolve_name(target)))
                     .target(target)
-                    .arbitrary("Last Day",
-                        format!("Warnings: **{}**\nMutes: **{}**\nBans: **{}**",
-                            warns_last_day, mutes_last_day, bans_last_day))
-                    .arbitrary("Last Week",
-                        format!("Warnings: **{}**\nMutes: **{}**\nBans: **{}**",
-                            warns_last_week, mutes_last_week, bans_last_week))
-                    .arbitrary("Last Month",
-                        format!("Warnings: **{}**\nMutes: **{}**\nBans: **{}**",
-                            warns_last_month, mutes_last_month, bans_last_month))
+                    .arbitrary("mod-stats.last-day", counts(warns_last_day, mutes_last_day, bans_last_day))
+                    .arbitrary("mod-stats.last-week", counts(warns_last_week, mutes_last_week, bans_last_week))
+                    .arbitrary("mod-stats.last-month", counts(warns_last_month, mutes_last_month, bans_last_month))
                     .build().await;
 
                 let _ = message.reply(embed).await;
@@ -13,19 +13,14 @@ pub struct MuteCommand;
 
 impl Command for MuteCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["mute".to_string()]
         )
-            .add_required("user")
-            .add_optional("reason")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
             .example("@BadBoy continuously being bad")
     }
 
@@ -34,58 +29,61 @@ impl Command for MuteCommand {
             async move {
 
                 let message = &params.message;
-                let mentions = message.get_mentions().await;
-
-                // check if a user is mentioned
-                if mentions.len() == 0 {
-                    self.invalid_usage(params).await;
-                    return;
-                }
 
                 // check if the user is a moderator
                 let resolver = message.get_resolver();
-                let target = &resolver.resolve_user(mentions[0]).await.unwrap();
-                if resolver.is_trial(&target).await {
-                    message.reply_failure("You can't mute a moderator.").await;
+                let target = &resolver.resolve_user(params.arguments.user("user").unwrap()).await.unwrap();
+                if resolver.has_level(target, PermissionLevel::Trial).await {
+                    message.reply_failure("mute.moderator").await;
                     return;
                 }
 
                 // check if member is already muted
-                let role_muted = &resolver.resolve_role("Muted").await.unwrap()[0].id;
-                let member = resolver.resolve_member(&target).await.unwrap();
-                if member.roles.contains(role_muted) {
-                    message.reply_failure(&format!("<@{}> is already muted.", target.id.to_string())).await;
+                let role_muted = match BotConfig::get(message).await {
+                    Ok(config) => config.role_muted,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let Some(role_muted) = role_muted else {
+                    return message.reply_failure("mute.no-role").await;
+                };
+                let member = resolver.resolve_member(target).await.unwrap();
+                if member.roles.contains(&role_muted) {
+                    message.reply_failure(text!("mute.already-muted", user = format!("<@{}>", target.id))).await;
                     return;
                 }
 
-                member.add_role(&resolver, role_muted).await.unwrap();
+                resolver.add_role(member.user.id, role_muted).await.unwrap();
 
                 // obtain the reason
-                let mut reason = message.payload_without_mentions(None, None);
-                if reason.is_empty() {
-                    reason = "No reason provided.".to_string();
-                }
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
 
                 // log mute to database
                 let log = ModLog::new(
                     message.get_author().id.to_string(),
                     reason.clone()
                 );
-                MutesDB::get_instance()
-                    .append(&target.id.to_string(), &log.into()).await;
+                if let Err(err) = MutesDB::get_instance().scope(message)
+                    .append(&target.id.to_string(), log).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 // log mute to mod logs
                 let log_message = message.get_log_builder()
-                    .title("[MUTE]")
-                    .target(&target)
+                    .title("mute.log-title")
+                    .target(target)
                     .staff()
-                    .user(&target)
-                    .arbitrary("Reason", &reason)
+                    .user(target)
+                    .arbitrary("log.reason", &reason)
                     .timestamp()
                     .build().await;
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
-                let _ = modlogs.send_message(resolver, log_message.to_message()).await;
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let _ = resolver.send_message(modlogs, log_message).await;
 
                 message.reply_success().await;
 
@@ -19,7 +19,7 @@ impl Command for NotesCommand {
             CommandType::Moderation,
             nonempty!["notes".to_string(),"cc".to_string()]
         )
-            .add_optional("label")
+            .add_optional("label", ArgumentType::Text)
             .new_usage()
             .add_constant("-list", false)
             .example("edate")
@@ -30,37 +30,40 @@ impl Command for NotesCommand {
             async move {
 
                 let message = &params.message;
-                let label = message.payload(None, None);
+                let label = params.arguments.text("label").unwrap_or_default().to_string();
 
                 // list all notes
-                if message.has_parameter("list") || label.is_empty() {
+                if params.arguments.has("list") || label.is_empty() {
 
                     // get all notes
-                    let mut notes = NotesDB::get_instance()
-                        .get_keys().await;
+                    let mut notes = match NotesDB::get_instance().scope(message).get_keys().await {
+                        Ok(notes) => notes,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     notes.sort();
                     notes = notes.into_iter()
                         .map(|key| format!("`{}`", Note::deescape(key)))
                         .collect::<Vec<String>>();
 
                     // create embed
-                    let embed = MessageManager::create_embed(|embed| {
-                        embed
-                            .title("List of all Notes")
-                            .description(&notes.join(", "))
-                    }).await;
+                    let embed = message.create_text_embed("notes.title", Text::plain(notes.join(", "))).await;
                     let _ = message.reply(embed).await;
 
                 // display single note
                 } else {
 
-                    let note_keys = NotesDB::get_instance().get_keys().await;
+                    let note_keys = match NotesDB::get_instance().scope(message).get_keys().await {
+                        Ok(note_keys) => note_keys,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let note_keys = note_keys
                         .iter()
                         .map(|key| key.as_str())
                         .collect::<Vec<&str>>();
-                    let notes = NotesDB::get_instance()
-                        .get_multiple(note_keys).await.unwrap();
+                    let notes = match NotesDB::get_instance().scope(message).get_multiple(note_keys).await {
+                        Ok(notes) => notes,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
 
                     // match note
                     let triggerables = notes.iter()
@@ -16,19 +16,14 @@ pub struct PurgeCommand;
 
 impl Command for PurgeCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["purge".to_string()]
         )
-            .add_required("amount (1..100)")
-            .add_optional("user")
+            .level(PermissionLevel::HeadMod)
+            .add_required("amount (1..100)", ArgumentType::Integer)
+            .add_optional("user", ArgumentType::User)
             .example("15 @EvilCorp")
     }
 
@@ -47,39 +42,40 @@ impl Command for PurgeCommand {
                 if let Some(channel) = message.resolve_guild_channel().await {
 
                     // check if the category is protected
-                    let category_protected_purge = ConfigDB::get_instance()
-                        .get_all("category_protected_purge").await.unwrap()
-                        .into_iter()
-                        .map(|category| category.to_string())
-                        .collect::<Vec<_>>();
-                    let category = &channel.parent_id.unwrap().to_string();
+                    let category_protected_purge = match BotConfig::get(message).await {
+                        Ok(config) => config.category_protected_purge.clone(),
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let category = &channel.parent_id.unwrap();
                     if category_protected_purge.contains(category) {
-                        message.reply_failure("You can not purge here.").await;
+                        message.reply_failure("purge.protected").await;
                         return;
                     }
 
                     // get messages to delete
                     let mut last_messages = message.last_messages(purge_size).await;
-                    let mentions = message.get_mentions().await;
-                    if !mentions.is_empty() {
-                        last_messages.retain(|message| message.author.id == mentions[0]);
+                    if let Some(user) = params.arguments.user("user") {
+                        last_messages.retain(|message| message.author.id == user);
                     }
 
                     // delete messages
-                    let _ = channel.delete_messages(message, last_messages).await;
+                    let message_ids = last_messages.iter().map(|message| message.id).collect();
+                    let _ = message.get_resolver().delete_messages(channel.id, message_ids).await;
 
                     // log to mod logs
                     let embed = message.get_log_builder()
-                        .title("[PURGE]")
+                        .title("purge.log-title")
                         .target(message.get_author())
                         .staff()
-                        .arbitrary("Amount", format!("**{}** Message(s)", purge_size))
+                        .arbitrary("purge.amount", message.guild_text(text!("purge.messages", count = purge_size)).await)
                         .channel()
                         .timestamp()
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
-                    let _ = modlogs.send_message(message, embed.to_message()).await;
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let _ = message.get_resolver().send_message(modlogs, embed).await;
 
                     message.reply_success().await;
                 }
@@ -15,13 +15,8 @@ impl Command for RemoveAfkCommand {
             CommandType::Moderation,
             nonempty!["remove-afk".to_string()]
         )
-            .add_required("user")
-    }
-
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -32,16 +27,19 @@ impl Command for RemoveAfkCommand {
                 let target_id = &params.target.unwrap().id.to_string();
 
                 // check if user is afk
-                let afk = AfkDB::get_instance()
-                    .get(&target_id).await;
+                let afk = AfkDB::get_instance().scope(message)
+                    .get(target_id).await;
                 if afk.is_err() {
-                    message.reply_failure("This user is not afk.").await;
+                    message.reply_failure("remove-afk.not-afk").await;
                     return;
                 }
 
                 // remove afk message
-                AfkDB::get_instance()
-                    .set(&target_id, "This afk message was **removed** by a moderator.").await;
+                let removed = message.guild_text("remove-afk.removed").await;
+                if let Err(err) = AfkDB::get_instance().scope(message)
+                    .set(target_id, &removed).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 message.reply_success().await;
             }
@@ -19,14 +19,10 @@ impl Command for RemoveNoteCommand {
             CommandType::Moderation,
             nonempty!["remove-note".to_string(),"removenote".to_string()]
         )
-            .add_required("label")
-            .example("Deprecated Note")
-    }
-
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
+            .level(PermissionLevel::HeadMod)
+            .add_required("label", ArgumentType::Text)
+            .add_optional("-reason", ArgumentType::Text)
+            .example("Deprecated Note -reason replaced by the new rules")
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -35,12 +31,37 @@ impl Command for RemoveNoteCommand {
 
                 let message = &params.message;
 
-                let label = Note::escape(message.payload(None, None));
-                if label.is_empty() {
-                    self.invalid_usage(params).await;
+                let label = Note::escape(params.arguments.text("label").unwrap_or_default().to_string());
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
+
+                // remove note, it is kept as a tombstone
+                let staff_id = message.get_author().id.to_string();
+                let removed = match remove_records(&DB::Notes, message, &Query::new().key(&label), &staff_id, &reason).await {
+                    Ok(removed) => removed,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                if removed.is_empty() {
+                    message.reply_failure("remove-note.not-found").await;
                     return;
                 }
-                NotesDB::get_instance().delete(&label).await;
+
+                // log to mod logs
+                let log_message = message.get_log_builder()
+                    .title("remove-note.log-title")
+                    .description(text!("remove-note.removed", note = Note::deescape(label)))
+                    .color(0xff8200)
+                    .staff()
+                    .arbitrary("log.reason", &reason)
+                    .timestamp()
+                    .build().await;
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let _ = modlogs.send_message(message, log_message.to_message()).await;
 
                 message.reply_success().await;
             }
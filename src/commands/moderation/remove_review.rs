@@ -18,13 +18,9 @@ impl Command for RemoveReviewCommand {
             CommandType::Moderation,
             nonempty!["remove-review".to_string(), "remove-ticket-review".to_string()]
         )
-            .add_required("database ID")
-    }
-
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
+            .level(PermissionLevel::HeadMod)
+            .add_required("database ID", ArgumentType::Integer)
+            .add_optional("reason", ArgumentType::Text)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -34,16 +30,23 @@ impl Command for RemoveReviewCommand {
                 let message = &params.message;
                 let review_id = params.number.unwrap();
 
-                let review = TicketReviewsDB::get_instance()
-                    .query("", &format!("OR id = {}", review_id)).await;
-                if review.is_err() || review.clone().unwrap().is_empty() {
-                    message.reply_failure("Review not found.").await;
+                // obtain the reason
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
+
+                // remove review, it is kept as a tombstone
+                let staff_id = message.get_author().id.to_string();
+                let review = match remove_records(&DB::TicketReviews, message, &Query::new().id(review_id), &staff_id, &reason).await {
+                    Ok(review) => review,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                if review.is_empty() {
+                    message.reply_failure("remove-review.not-found").await;
                     return;
                 }
-                let review = &review.unwrap()[0];
-
-                // remove review
-                TicketReviewsDB::get_instance().delete_by_id(review_id).await;
+                let review = &review[0];
 
                 // resolve target
                 let user_id = UserId::from(review.key.parse::<u64>().unwrap());
@@ -51,16 +54,19 @@ impl Command for RemoveReviewCommand {
 
                 // log to mod logs
                 let log_message = message.get_log_builder()
-                    .title("[REMOVE REVIEW]")
-                    .description(&format!("Removed review with **ID {}**", review_id))
+                    .title("remove-review.log-title")
+                    .description(text!("remove-review.removed", id = review_id))
                     .target(&target)
                     .color(0xff8200)
                     .staff()
                     .user(&target)
+                    .arbitrary("log.reason", &reason)
                     .timestamp()
                     .build().await;
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let _ = modlogs.send_message(message, log_message.to_message()).await;
 
                 message.reply_success().await;
@@ -18,14 +18,10 @@ impl Command for RemoveWarningCommand {
             CommandType::Moderation,
             nonempty!["remove-warn".to_string(), "remove-warning".to_string()],
         )
-            .add_required("database ID")
-            .example("12")
-    }
-
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
+            .level(PermissionLevel::HeadMod)
+            .add_required("database ID", ArgumentType::Integer)
+            .add_optional("reason", ArgumentType::Text)
+            .example("12 issued by mistake")
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -35,33 +31,43 @@ impl Command for RemoveWarningCommand {
                 let message = &params.message;
                 let warn_id = params.number.unwrap();
 
-                let warn = WarningsDB::get_instance()
-                    .query("", &format!("OR id = {}", warn_id)).await;
-                if warn.is_err() || warn.clone().unwrap().is_empty() {
-                    message.reply_failure("Warning not found.").await;
+                // obtain the reason
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
+
+                // remove warning, it is kept as a tombstone
+                let staff_id = message.get_author().id.to_string();
+                let warn = match remove_records(&DB::Warnings, message, &Query::new().id(warn_id), &staff_id, &reason).await {
+                    Ok(warn) => warn,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                if warn.is_empty() {
+                    message.reply_failure("remove-warning.not-found").await;
                     return;
                 }
 
-                // remove warning
-                WarningsDB::get_instance().delete_by_id(warn_id).await;
-
                 // resolve target
-                let log = &warn.unwrap()[0];
+                let log = &warn[0];
                 let user_id = UserId::from(log.key.parse::<u64>().unwrap());
                 let target = message.get_resolver().resolve_user(user_id).await.unwrap();
 
                 // log to mod logs
                 let log_message = message.get_log_builder()
-                    .title("[REMOVE WARNING]")
-                    .description(&format!("Removed warning with **ID {}**", warn_id))
+                    .title("remove-warning.log-title")
+                    .description(text!("remove-warning.removed", id = warn_id))
                     .target(&target)
                     .color(0xff8200)
                     .staff()
                     .user(&target)
+                    .arbitrary("log.reason", &reason)
                     .timestamp()
                     .build().await;
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let _ = modlogs.send_message(message, log_message.to_message()).await;
 
                 message.reply_success().await;
@@ -13,10 +13,17 @@ pub struct ReviewCommand;
 
 impl ReviewCommand {
 
+    // whether a field of a ticket log has the label of the message in any language
+    #[cfg(feature = "tickets")]
+    fn is_field(field: &EmbedField, id: &str) -> bool {
+        Catalog::get_instance().arguments(id, &field.name).is_some()
+    }
+
     fn review_ticket<'a>(
         reviewee: User,
         reviewer: &'a User,
         transcript_url: String,
+        approved: bool,
         notes: String,
         dump_channel: ChannelId,
         message: &'a MessageManager) -> BoxedFuture<'a, ()>
@@ -24,39 +31,43 @@ impl ReviewCommand {
         Box::pin(async move {
 
             if reviewee.id == reviewer.id {
-                message.reply_failure("You cannot review yourself.").await;
+                message.reply_failure("review.yourself").await;
                 return;
             }
 
             // log review into database
-            let approved = message.has_parameter("approve");
             let log = TicketReviewLog::new(
                 reviewer.id.to_string(),
                 approved,
                 notes.clone(),
             );
-            TicketReviewsDB::get_instance()
-                .append(&reviewee.id.to_string(), &log.into()).await;
+            if let Err(err) = TicketReviewsDB::get_instance().scope(message)
+                .append(&reviewee.id.to_string(), log).await {
+                return message.reply_db_error(err).await;
+            }
 
             // create review embed
-            let review_amount = TicketReviewsDB::get_instance()
-                .get_all(&reviewee.id.to_string()).await.unwrap().len();
-            let transcript_button = CreateButton::new_link(transcript_url).label("Transcript");
+            let review_amount = match TicketReviewsDB::get_instance().scope(message).get_all(&reviewee.id.to_string()).await {
+                Ok(reviews) => reviews.len(),
+                Err(err) => return message.reply_db_error(err).await,
+            };
+            let locale = Locale::of_guild(message).await;
+            let localize = |text: Text| text.localize(locale);
+            let transcript_button = CreateButton::new_link(transcript_url).label(localize("ticket-log.transcript".into()));
+            let verdict = match approved { true => "review.approved", false => "review.denied" };
             let embed = MessageManager::create_embed(|embed|
                 embed
-                    .field("Review:", match approved { true => "Approved", false => "Denied" }, false)
-                    .field("Notes:",
-                        format!("{}\n\n***Please DM <@{}> if you need more information or have any questions.***",
-                            notes,
-                            reviewer.id.to_string()),
-                            false)
+                    .field(localize("review.review-field".into()), localize(verdict.into()), false)
+                    .field(localize("review.notes-field".into()),
+                        localize(text!("review.notes", notes = notes, reviewer = format!("<@{}>", reviewer.id))),
+                        false)
                     .footer(CreateEmbedFooter::new(
-                            format!("Reviewed by {}", message.get_resolver().resolve_name(reviewer)))
+                            localize(text!("review.reviewed-by", staff = message.get_resolver().resolve_name(reviewer))))
                             .icon_url(reviewer.face()))
             ).await
                 .to_message()
                 .button(transcript_button)
-                .content(format!("<@{}> now has **{}** ticket review(s)", reviewee.id, review_amount));
+                .content(localize(text!("review.review-count", user = format!("<@{}>", reviewee.id), count = review_amount)));
 
             let _ = dump_channel.send_message(message, embed).await;
         })
@@ -66,22 +77,17 @@ impl ReviewCommand {
 
 impl Command for ReviewCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["review".to_string()]
         )
+            .level(PermissionLevel::HeadMod)
             .add_constant("-approve", false)
-            .add_optional("notes")
+            .add_optional("notes", ArgumentType::Text)
             .new_usage()
             .add_constant("-deny", false)
-            .add_optional("notes")
+            .add_optional("notes", ArgumentType::Text)
             .example("-approve You did a good job!")
     }
 
@@ -95,11 +101,13 @@ impl Command for ReviewCommand {
 
                 // ---- Sanity Checks ---- //
 
-                let review_channels = ConfigDB::get_instance()
-                    .get_multiple(vec!["channel_suggestions", "channel_transcripts", "channel_admin"]).await.unwrap()
-                    .into_iter()
-                    .map(|entry| entry.value.to_string())
-                    .collect::<Vec<_>>();
+                let review_channels = match BotConfig::get(message).await {
+                    Ok(config) => [config.channel_suggestions, config.channel_transcripts, config.channel_admin]
+                        .iter()
+                        .map(|channel| channel.to_string())
+                        .collect::<Vec<_>>(),
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // fail if not in a review channel
                 if !review_channels.contains(&channel.to_string()) {
@@ -107,34 +115,27 @@ impl Command for ReviewCommand {
                         .map(|c| format!("<#{}>", c))
                         .collect::<Vec<_>>()
                         .join(", ");
-                    message.reply_failure(&format!(
-                            "This command can only be used in {}.",
-                            allowed_channels)).await;
+                    message.reply_failure(text!("command.channels", channels = allowed_channels)).await;
                     return;
                 }
 
                 if !message.is_referencing() {
-                    message.reply_failure("Please reply to the message you want to review.").await;
+                    message.reply_failure("review.no-reply").await;
                     return;
                 }
                 let referenced_message = message.get_referenced();
 
                 if referenced_message.embeds.is_empty() {
-                    message.reply_failure("Pleaes reply to a reviewable message.").await;
+                    message.reply_failure("review.not-reviewable").await;
                     return;
                 }
                 let reviewee_embed = &referenced_message.embeds[0];
 
-                if !(message.has_parameter("approve") || message.has_parameter("deny")) {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-                let approve = message.has_parameter("approve");
-
-                let mut notes = message.payload(None, Some(vec!["approve".to_string(), "deny".to_string()]));
-                if notes.is_empty() {
-                    notes = "No notes provided.".to_string();
-                }
+                let approve = params.arguments.has("approve");
+                let notes = match params.arguments.text("notes") {
+                    Some(notes) => notes.to_string(),
+                    None => message.guild_text("review.no-notes").await,
+                };
 
 
                 // ---- Implementation ---- //
@@ -144,17 +145,20 @@ impl Command for ReviewCommand {
 
                     // extract suggestion and author
                     let suggestion = reviewee_embed.description.clone().unwrap();
-                    let original_author = reviewee_embed.footer.clone().unwrap()
-                        .text.clone().split("by ").last().unwrap().to_string();
+                    let footer = reviewee_embed.footer.clone().unwrap().text;
+                    let original_author = Catalog::get_instance().arguments("suggest.footer", &footer)
+                        .and_then(|mut arguments| arguments.remove("user"))
+                        .unwrap_or(footer);
 
+                    let reviewed = message.guild_text(text!("review.suggestion-footer",
+                        user = original_author, staff = message.resolve_name())).await;
                     let embed = message.get_log_builder()
                         .no_thumbnail()
-                        .title(format!("Suggestion {}", match approve { true => "Approved", false => "Denied" }))
-                        .arbitrary_block("Suggestion", &suggestion)
-                        .arbitrary_block("Review", &notes)
+                        .title(match approve { true => "review.suggestion-approved", false => "review.suggestion-denied" })
+                        .arbitrary_block("review.suggestion", &suggestion)
+                        .arbitrary_block("review.review", &notes)
                         .build().await
-                        .footer(CreateEmbedFooter::new(
-                            format!("Suggested by {} - reviewed by {}", original_author, message.resolve_name()))
+                        .footer(CreateEmbedFooter::new(reviewed)
                             .icon_url(message.get_author().face()));
 
                     let edit = EditMessage::new().embed(embed);
@@ -173,7 +177,7 @@ impl Command for ReviewCommand {
                         // extract staff
                         let regex = RegexManager::get_id_regex();
                         let staff_ids = fields.iter()
-                            .find(|field| field.name == "Staff")
+                            .find(|field| Self::is_field(field, "ticket-log.staff"))
                             .unwrap().value.clone()
                             .split(" ")
                             .map(|id| UserId::from(regex.find(&id).unwrap().as_str().parse::<u64>().unwrap()))
@@ -189,39 +193,37 @@ impl Command for ReviewCommand {
 
                         // extract transcript url
                         let transcript_url = fields.iter()
-                            .find(|field| field.name == "Transcript")
+                            .find(|field| Self::is_field(field, "ticket-log.transcript"))
                             .unwrap().value.clone()
-                            .split("[External Link](").last().unwrap()
+                            .split("](").last().unwrap()
                             .split(")").next().unwrap().to_string();
 
                         let category = TicketType::from(fields.iter()
-                            .find(|field| field.name == "Category")
+                            .find(|field| Self::is_field(field, "ticket-log.category"))
                             .unwrap().value.clone());
-                        let dump_channel: ChannelId = match category {
-                            TicketType::StaffReport => ConfigDB::get_instance()
-                                .get("channel_headmod").await.unwrap().into(),
-                            _ => ConfigDB::get_instance()
-                                .get("channel_reviews").await.unwrap().into()
+                        let config = match BotConfig::get(message).await {
+                            Ok(config) => config,
+                            Err(err) => return message.reply_db_error(err).await,
+                        };
+                        let dump_channel = match category {
+                            TicketType::StaffReport => config.channel_headmod,
+                            _ => config.channel_reviews,
                         };
 
                         // find reviewer and reviewee, and then call ReviewCommand::review_ticket
                         let reviewer = message.get_author();
                         match staff.len() {
                             0 => unreachable!(),
-                            1 => ReviewCommand::review_ticket(staff[0].clone(), reviewer, transcript_url, notes, dump_channel, message).await,
+                            1 => ReviewCommand::review_ticket(staff[0].clone(), reviewer, transcript_url, approve, notes, dump_channel, message).await,
                             _ => {
-                                let embed = MessageManager::create_embed(|embed|
-                                    embed
-                                        .title("Who are you reviewing?")
-                                        .description("Select the staff member you are reviewing.")
-                                ).await;
+                                let embed = message.create_text_embed("review.select-title", "review.select").await;
 
                                 message.get_interaction_helper()
                                     .create_user_dropdown_interaction(
                                         reviewer.id,
                                         embed,
                                         staff,
-                                        |reviewee: User| ReviewCommand::review_ticket(reviewee, reviewer, transcript_url, notes, dump_channel, message)).await;
+                                        |reviewee: User| ReviewCommand::review_ticket(reviewee, reviewer, transcript_url, approve, notes, dump_channel, message)).await;
                             }
                         };
                     }
@@ -16,18 +16,14 @@ pub struct RoleCommand;
 
 impl Command for RoleCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["role".to_string()]
         )
-            .add_required(vec!["user", "rolenames"])
+            .level(PermissionLevel::HeadMod)
+            .add_required("user", ArgumentType::User)
+            .add_required("rolenames", ArgumentType::Text)
             .example("@UnhappyCustomer Europe Blue")
     }
 
@@ -39,18 +35,13 @@ impl Command for RoleCommand {
                 let target = &params.target.clone().unwrap();
 
                 // get roles
-                let payload = message.payload_without_mentions(None, None);
-                let rolenames = payload
+                let rolenames = params.arguments.text("rolenames").unwrap_or_default()
                     .split_whitespace()
                     .collect::<Vec<_>>();
-                if rolenames.is_empty() {
-                    self.invalid_usage(params).await;
-                    return;
-                }
 
                 let roles = message.resolve_role(rolenames).await;
                 if roles.is_none() {
-                    message.reply_failure("Invalid role(s) provided. Please check your spelling!").await;
+                    message.reply_failure("role.invalid").await;
                     return;
                 }
 
@@ -74,17 +65,19 @@ impl Command for RoleCommand {
                         // log role update to modlogs
                         let embed = message.get_log_builder()
                             .title(match has_role {
-                                true => "[ROLE REMOVED]",
-                                false => "[ROLE ADDED]",
+                                true => "role.removed-log-title",
+                                false => "role.added-log-title",
                             })
                             .target(target)
                             .staff()
                             .user(target)
-                            .arbitrary("Role", format!("<@&{}>", &role.id))
+                            .arbitrary("role.role", format!("<@&{}>", &role.id))
                             .timestamp()
                             .build().await;
-                        let modlogs: ChannelId = ConfigDB::get_instance()
-                            .get("channel_modlogs").await.unwrap().into();
+                        let modlogs: ChannelId = match BotConfig::get(message).await {
+                            Ok(config) => config.channel_modlogs,
+                            Err(err) => return message.reply_db_error(err).await,
+                        };
                         let _ = modlogs.send_message(message, embed.to_message()).await;
 
                         message.reply_success().await;
@@ -0,0 +1,100 @@
+use serenity::builder::CreateEmbed;
+use nonempty::{NonEmpty, nonempty};
+
+use crate::commands::command::*;
+use crate::databases::search::{self, SearchHit};
+use crate::utility::*;
+use crate::databases::*;
+
+
+pub struct SearchCommand;
+
+impl SearchCommand {
+
+    const PAGE_SIZE: usize = 10;
+
+    fn symbol(table: &DB) -> &'static str {
+        match table {
+            DB::Warnings => "⚠️ ",
+            DB::Mutes    => "🔇",
+            DB::Bans     => "🔨",
+            DB::Flags    => "🚩",
+            _            => "📝",
+        }
+    }
+
+    fn describe(hit: &SearchHit, locale: Locale) -> String {
+        let text = match hit.text.chars().count() > 100 {
+            true  => format!("{}...", hit.text.chars().take(100).collect::<String>()),
+            false => hit.text.clone(),
+        };
+        match &hit.staff_id {
+            Some(staff_id) => format!("{} **{}** #{} <t:{}:d> {} `>` {}",
+                Self::symbol(&hit.table),
+                hit.table,
+                hit.id,
+                hit.timestamp,
+                text!("search.by", user = format!("<@{}>", hit.key), staff = format!("<@{}>", staff_id)).localize(locale),
+                text),
+            None => format!("{} **{}** #{} <t:{}:d> `{}` `>` {}",
+                Self::symbol(&hit.table),
+                hit.table,
+                hit.id,
+                hit.timestamp,
+                hit.key,
+                text),
+        }
+    }
+}
+
+impl Command for SearchCommand {
+
+    fn define_usage(&self) -> UsageBuilder {
+        UsageBuilder::new(
+            CommandType::Moderation,
+            nonempty!["search".to_string()]
+        )
+            .level(PermissionLevel::Trial)
+            .add_required("text ...", ArgumentType::Text)
+            .example("scam links")
+    }
+
+    fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
+        Box::pin(
+            async move {
+
+                let message = &params.message;
+                let text = params.arguments.text("text").unwrap_or_default();
+
+                let hits = match search::search(message, text).await {
+                    Ok(hits) => hits,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                if hits.is_empty() {
+                    return message.reply_failure(text!("search.nothing", text = text)).await;
+                }
+
+                // one page per chunk of matches
+                let locale = message.locale().await;
+                let title = text!("search.title", count = hits.len(), text = text).localize(locale);
+                let mut pages = Vec::<CreateEmbed>::new();
+                for chunk in hits.chunks(Self::PAGE_SIZE) {
+                    let description = chunk.iter()
+                        .map(|hit| Self::describe(hit, locale))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    let title = title.clone();
+                    pages.push(MessageManager::create_embed(|embed| {
+                        embed
+                            .title(title)
+                            .description(description)
+                    }).await);
+                }
+
+                message.get_interaction_helper()
+                    .create_pagination(message.get_author().id, pages).await;
+            }
+        )
+    }
+
+}
@@ -16,18 +16,13 @@ pub struct SlowmodeCommand;
 
 impl Command for SlowmodeCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["slowmode".to_string(), "slow".to_string()]
         )
-            .add_required("delay (0 .. 21600s)")
+            .level(PermissionLevel::Trial)
+            .add_required("delay (0 .. 21600s)", ArgumentType::Integer)
             .new_usage()
             .add_constant("-off", false)
             .example("11")
@@ -38,35 +33,24 @@ impl Command for SlowmodeCommand {
             async move {
 
                 let message = &params.message;
-                let payload = message.payload_without_mentions(None, None);
-
-                let mut time_delay = 0;
-                match payload.parse::<u16>() {
-                    Ok(delay) => time_delay = delay,
-                    Err(_) => {
-                        if !message.has_parameter("off") {
-                            self.invalid_usage(params).await;
-                            return;
-                        }
+                let time_delay = match params.arguments.integer("delay").unwrap_or(0) {
+                    delay @ 0..=21600 => delay as u16,
+                    _ => {
+                        self.invalid_usage(params).await;
+                        return;
                     }
                 };
 
-                if time_delay > 21600 {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-
                 if let Some(channel) = message.resolve_guild_channel().await {
 
                     // check if the category is protected
-                    let category_protected_slowmode = ConfigDB::get_instance()
-                        .get_all("category_protected_slowmode").await.unwrap()
-                        .into_iter()
-                        .map(|category| category.to_string())
-                        .collect::<Vec<_>>();
-                    let category = &channel.parent_id.unwrap().to_string();
+                    let category_protected_slowmode = match BotConfig::get(message).await {
+                        Ok(config) => config.category_protected_slowmode.clone(),
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let category = &channel.parent_id.unwrap();
                     if category_protected_slowmode.contains(category) {
-                        message.reply_failure("You can not use slowmode here.").await;
+                        message.reply_failure("slowmode.protected").await;
                         return;
                     }
 
@@ -77,19 +61,21 @@ impl Command for SlowmodeCommand {
 
                     // log to mod logs
                     let delay_string = match time_delay {
-                        0 => "off".to_string(),
+                        0 => message.guild_text("slowmode.off").await,
                         _ => format!("{}s", time_delay)
                     };
                     let embed = message.get_log_builder()
-                        .title("[SLOWMODE]")
+                        .title("slowmode.log-title")
                         .target(message.get_author())
                         .staff()
-                        .arbitrary("Delay", delay_string)
+                        .arbitrary("slowmode.delay", delay_string)
                         .channel()
                         .timestamp()
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let _ = modlogs.send_message(message, embed.to_message()).await;
 
                     message.reply_success().await;
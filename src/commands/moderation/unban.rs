@@ -13,19 +13,14 @@ pub struct UnbanCommand;
 
 impl Command for UnbanCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["unban".to_string()]
         )
-            .add_required("user-id")
-            .add_optional("reason")
+            .level(PermissionLevel::HeadMod)
+            .add_required("user-id", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
             .example("@RecoveredRobin has promised to behave")
     }
 
@@ -35,13 +30,7 @@ impl Command for UnbanCommand {
 
                 let message = &params.message;
                 let resolver = &message.get_resolver();
-                let mentions = message.get_mentions().await;
-
-                if mentions.len() != 1 {
-                    self.invalid_usage(params).await;
-                    return;
-                }
-                let target_id = mentions[0];
+                let target_id = params.arguments.user("user-id").unwrap();
 
                 // try to resolve target
                 let target = resolver.resolve_user(target_id).await;
@@ -51,77 +40,78 @@ impl Command for UnbanCommand {
                 };
 
                 // obtain the reason
-                let mut reason = message.payload_without_mentions(None, Some(vec![target_id.to_string()]));
-                if reason.is_empty() {
-                    reason = "No reason provided.".to_string();
-                }
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
 
-                if let Some(guild_id) = message.get_guild() {
+                if let Some(guild) = resolver.resolve_guild(None).await {
 
                     // unban the user
-                    let _ = guild_id.unban(&message, target_id).await;
-
-                    // clear databases
-                    WarningsDB::get_instance().delete(&target_id.to_string()).await;
-                    MutesDB::get_instance().delete(&target_id.to_string()).await;
-                    FlagsDB::get_instance().delete(&target_id.to_string()).await;
+                    let _ = resolver.unban(target_id).await;
+
+                    // clear databases, the records are kept as tombstones
+                    let staff_id = message.get_author().id.to_string();
+                    let query = Query::new().key(target_id.to_string());
+                    let mut removed = 0;
+                    for table in [DB::Warnings, DB::Mutes, DB::Flags] {
+                        match remove_records(&table, message, &query, &staff_id, &reason).await {
+                            Ok(records) => removed += records.len(),
+                            Err(err) => return message.reply_db_error(err).await,
+                        }
+                    }
 
                     // get reason of last ban
-                    let last_ban = BansDB::get_instance()
+                    let last_ban = BansDB::get_instance().scope(message)
                         .get_last(&target_id.to_string(), 1).await;
                     let ban_reason = match last_ban {
-                        Ok(bans) => {
-                            match bans.is_empty() {
-                                true  => "No reason provided.".to_string(),
-                                false => bans[0].reason.clone()
-                            }
-                        },
-                        Err(_) => "No reason provided.".to_string()
+                        Ok(bans) if !bans.is_empty() => bans[0].reason.clone(),
+                        _ => message.guild_text("moderation.no-reason").await,
                     };
 
                     // log unban to mod logs
                     let target = match target {
                         Some(ref target) => target,
                         None => {
-                            let bot_id: UserId = ConfigDB::get_instance()
-                                .get("bot_id").await.unwrap().into();
+                            let bot_id: UserId = match BotConfig::get(message).await {
+                                Ok(config) => config.bot_id,
+                                Err(err) => return message.reply_db_error(err).await,
+                            };
                             &resolver.resolve_user(bot_id).await.unwrap()
                         }
                     };
                     let embed = message.get_log_builder()
-                        .title("[UNBAN]")
-                        .description(&format!("{} has been unbanned", name))
+                        .title("unban.log-title")
+                        .description(text!("unban.unbanned", user = name))
                         .color(0xff8200)
                         .staff()
-                        .arbitrary("Ban Reason", &ban_reason)
-                        .arbitrary("Unban Reason", &reason)
+                        .arbitrary("unban.ban-reason", &ban_reason)
+                        .arbitrary("unban.unban-reason", &reason)
+                        .arbitrary("unban.removed-records", removed.to_string())
                         .timestamp()
                         .build().await;
-                    let unbanlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_unbanlogs").await.unwrap().into();
-                    let _ = unbanlogs.send_message(message, embed.to_message()).await;
+                    let (unbanlogs, modlogs) = match BotConfig::get(message).await {
+                        Ok(config) => (config.channel_unbanlogs, config.channel_modlogs),
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
+                    let _ = resolver.send_message(unbanlogs, embed.clone()).await;
+                    let _ = resolver.send_message(modlogs, embed).await;
 
                     // inform member of their unban
-                    let guild = resolver.resolve_guild(None).await.unwrap();
                     let notify_message = message.get_log_builder()
-                        .title("You've been unbanned!")
-                        .description(&format!(
-                            "You have been unbanned from {}\nFeel free to join us again [here](discord.gg/vent)!",
-                            guild.name))
+                        .for_user(target.id)
+                        .title("unban.notice-title")
+                        .description(text!("unban.notice", guild = guild.name))
                         .target(&target)
                         .no_thumbnail()
                         .color(0xff0000)
                         .build().await;
-                    let sent = target.dm(resolver, notify_message.to_message()).await;
+                    let sent = resolver.direct_message(target.id, notify_message).await;
 
                     match sent {
                         Ok(_)  => message.reply_success().await,
                         Err(_) => {
-                            let embed = MessageManager::create_embed(|embed| {
-                                embed
-                                    .title("Notice")
-                                    .description("I could not send a DM to the user.")
-                            }).await;
+                            let embed = message.create_text_embed("moderation.dm-failed-title", "moderation.dm-failed").await;
                             let _ = message.reply(embed).await;
                         }
                     };
@@ -13,18 +13,13 @@ pub struct UnflagCommand;
 
 impl Command for UnflagCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["unflag".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::HeadMod)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -34,29 +29,33 @@ impl Command for UnflagCommand {
                 let message = &params.message;
                 let target = &params.target.unwrap();
 
-                let last_flag = FlagsDB::get_instance()
+                let last_flag = FlagsDB::get_instance().scope(message)
                     .get_last(&target.id.to_string(), 1).await;
 
                 if let Ok(last_flag) = last_flag {
 
                     if last_flag.is_empty() {
-                        message.reply_failure(&format!("<@{}> has not been flagged.", target.id)).await;
+                        message.reply_failure(text!("unflag.not-flagged", user = format!("<@{}>", target.id))).await;
                         return;
                     }
 
                     // delete last flag
-                    FlagsDB::get_instance()
-                        .delete_by_id(last_flag[0].id).await;
+                    if let Err(err) = FlagsDB::get_instance().scope(message)
+                        .delete_by_id(last_flag[0].id).await {
+                        return message.reply_db_error(err).await;
+                    }
 
                     // log to mod logs
                     let embed = message.get_log_builder()
-                        .title("[UNFLAG]")
+                        .title("unflag.log-title")
                         .color(0xff8200)
                         .staff()
                         .user(&target)
                         .build().await;
-                    let modlogs: ChannelId = ConfigDB::get_instance()
-                        .get("channel_modlogs").await.unwrap().into();
+                    let modlogs: ChannelId = match BotConfig::get(message).await {
+                        Ok(config) => config.channel_modlogs,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                     let _ = modlogs.send_message(message, embed.to_message()).await;
 
                     message.reply_success().await;
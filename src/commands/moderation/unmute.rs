@@ -13,23 +13,19 @@ pub struct UnmuteCommand;
 
 impl Command for UnmuteCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["unmute".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
             .new_usage()
-            .add_required("user")
+            .add_required("user", ArgumentType::User)
             .add_constant("-flag", false)
-            .add_required("reason")
-            .add_constant("-monthly", false)
+            .add_required("reason", ArgumentType::Text)
+            .add_optional("-monthly", ArgumentType::Flag)
             .example("@BadBoy -flag")
     }
 
@@ -42,52 +38,60 @@ impl Command for UnmuteCommand {
 
                 // check if the user is a moderator
                 let resolver = message.get_resolver();
-                if resolver.is_trial(&target).await {
-                    message.reply_failure("You can't mute a moderator.").await;
+                if resolver.has_level(target, PermissionLevel::Trial).await {
+                    message.reply_failure("mute.moderator").await;
                     return;
                 }
 
                 // check if member is already muted
-                let role_muted = &resolver.resolve_role("Muted").await.unwrap()[0].id;
-                let member = resolver.resolve_member(&target).await.unwrap();
-                if !member.roles.contains(role_muted) {
-                    message.reply_failure(&format!("<@{}> is not muted.", target.id.to_string())).await;
+                let role_muted = match BotConfig::get(message).await {
+                    Ok(config) => config.role_muted,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let Some(role_muted) = role_muted else {
+                    return message.reply_failure("mute.no-role").await;
+                };
+                let member = resolver.resolve_member(target).await.unwrap();
+                if !member.roles.contains(&role_muted) {
+                    message.reply_failure(text!("unmute.not-muted", user = format!("<@{}>", target.id))).await;
                     return;
                 }
 
-                member.remove_role(&resolver, role_muted).await.unwrap();
+                resolver.remove_role(member.user.id, role_muted).await.unwrap();
                 let mut builder = message.get_log_builder()
-                    .title("[UNMUTE]")
-                    .target(&target)
+                    .title("unmute.log-title")
+                    .target(target)
                     .color(0xff8200)
                     .staff()
-                    .user(&target)
+                    .user(target)
                     .timestamp();
 
-                let last_mute = MutesDB::get_instance()
-                    .get_last(&target.id.to_string(), 1).await.unwrap();
+                let last_mute = match MutesDB::get_instance().scope(message).get_last(&target.id.to_string(), 1).await {
+                    Ok(last_mute) => last_mute,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // obtain the reason
-                let mut reason = message.payload_without_mentions(None, None);
-                if reason.is_empty() {
-                    match last_mute.is_empty() {
-                        true  => reason = "No reason provided".to_string(),
-                        false => reason = last_mute[0].reason.clone(),
-                    }
-                }
+                let reason = match (params.arguments.text("reason"), last_mute.first()) {
+                    (Some(reason), _) => reason.to_string(),
+                    (None, Some(mute)) => mute.reason.clone(),
+                    (None, None) => message.guild_text("moderation.no-reason").await,
+                };
 
                 // log mute to database
                 let log = ModLog::new(
                     message.get_author().id.to_string(),
                     reason.clone()
                 );
-                UnmutesDB::get_instance()
-                    .append(&target.id.to_string(), &log.into()).await;
+                if let Err(err) = UnmutesDB::get_instance().scope(message)
+                    .append(&target.id.to_string(), log).await {
+                    return message.reply_db_error(err).await;
+                }
 
                 // flag member if specified
-                if message.has_parameter("flag") {
+                if params.arguments.has("flag") {
 
-                    let monthly = message.has_parameter("monthly");
+                    let monthly = params.arguments.has("monthly");
 
                     // log flag to database
                     let log = FlagLog::new(
@@ -95,11 +99,13 @@ impl Command for UnmuteCommand {
                         reason.clone(),
                         monthly
                     );
-                    FlagsDB::get_instance()
-                        .append(&target.id.to_string(), &log.into()).await;
+                    if let Err(err) = FlagsDB::get_instance().scope(message)
+                        .append(&target.id.to_string(), log).await {
+                        return message.reply_db_error(err).await;
+                    }
 
                     let timestamp_now = chrono::Utc::now().timestamp();
-                    builder = builder.labeled_timestamp("Flag Until", match monthly {
+                    builder = builder.labeled_timestamp("flag.until", match monthly {
                         true  => timestamp_now + (30 * 24 * 60 * 60),
                         false => timestamp_now + (7  * 24 * 60 * 60)
                     });
@@ -107,10 +113,12 @@ impl Command for UnmuteCommand {
                 }
 
                 // log to mod logs
-                let log = builder.build().await.to_message();
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
-                let _ = modlogs.send_message(message, log).await;
+                let log = builder.build().await;
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let _ = resolver.send_message(modlogs, log).await;
 
                 message.reply_success().await;
             }
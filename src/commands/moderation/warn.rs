@@ -13,19 +13,14 @@ pub struct WarnCommand;
 
 impl Command for WarnCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["warn".to_string()]
         )
-            .add_required("user")
-            .add_optional("reason")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("reason", ArgumentType::Text)
             .example("@BadBoy being bad")
     }
 
@@ -34,48 +29,44 @@ impl Command for WarnCommand {
             async move {
 
                 let message = &params.message;
-                let mentions = message.get_mentions().await;
-
-                // check if a user is mentioned
-                if mentions.len() == 0 {
-                    self.invalid_usage(params).await;
-                    return;
-                }
 
                 // check if the user is a moderator
                 let resolver = message.get_resolver();
-                let target = resolver.resolve_user(mentions[0]).await;
+                let target = resolver.resolve_user(params.arguments.user("user").unwrap()).await;
 
                 if target.is_none() {
-                    message.reply_failure("User not found.").await;
+                    message.reply_failure("select-user.not-found").await;
                     return
                 }
                 let target = &target.unwrap();
 
-                if resolver.is_trial(&target).await {
-                    message.reply_failure("You can't warn a moderator.").await;
+                if resolver.has_level(target, PermissionLevel::Trial).await {
+                    message.reply_failure("warn.moderator").await;
                     return;
                 }
 
                 // obtain the reason
-                let mut reason = message.payload_without_mentions(None, None);
-                if reason.is_empty() {
-                    reason = "No reason provided.".to_string();
-                }
+                let reason = match params.arguments.text("reason") {
+                    Some(reason) => reason.to_string(),
+                    None => message.guild_text("moderation.no-reason").await,
+                };
 
                 // log to database
                 let log = ModLog::new(
                     message.get_author().id.to_string(),
                     reason.clone(),
                 );
-                WarningsDB::get_instance()
-                    .append(&target.id.to_string(), &log.into()).await;
+                if let Err(err) = WarningsDB::get_instance().scope(message)
+                    .append(&target.id.to_string(), log).await {
+                    return message.reply_db_error(err).await;
+                }
 
-                // create embed
+                // create embed, in the language of the warned member
+                let locale = Locale::of_user(message, target.id).await;
                 let embed = MessageManager::create_embed(|embed|
                     embed
-                        .title(&format!("Warning"))
-                        .description(&format!("You have been warned for `>` {}", reason))
+                        .title(Text::new("warn.title").localize(locale))
+                        .description(text!("warn.warned", reason = &reason).localize(locale))
                         .color(0xff0000)
                 ).await;
                 let _ = message.reply(format!("<@{}>", target.id)).await;
@@ -83,22 +74,24 @@ impl Command for WarnCommand {
 
                 // log to mod logs
                 let log_message = message.get_log_builder()
-                    .title("[WARNING]")
-                    .target(&target)
+                    .title("warn.log-title")
+                    .target(target)
                     .color(0xff8200)
                     .staff()
-                    .user(&target)
-                    .arbitrary("Reason", &reason)
+                    .user(target)
+                    .arbitrary("log.reason", &reason)
                     .timestamp()
                     .build().await;
-                let modlogs: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
-                let _ = modlogs.send_message(message, log_message.to_message()).await;
+                let modlogs: ChannelId = match BotConfig::get(message).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
+                let _ = resolver.send_message(modlogs, log_message).await;
 
                 // check if the user has been warned too many times
                 #[cfg(feature = "auto_moderation")]
                 AutoModerator::get_instance()
-                    .check_warnings(message, &target).await;
+                    .check_warnings(message, target).await;
 
             }
         )
@@ -14,19 +14,15 @@ pub struct WarningsCommand;
 
 impl Command for WarningsCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Moderation,
             nonempty!["warnings".to_string()]
         )
-            .add_required("user")
-            .add_optional("more")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
+            .add_optional("-more", ArgumentType::Flag)
+            .add_optional("-removed", ArgumentType::Flag)
             .example("@BadBoy")
     }
 
@@ -37,7 +33,7 @@ impl Command for WarningsCommand {
                 let message = &params.message;
                 let target = &params.target.unwrap();
 
-                let warnings = WarningsDB::get_instance()
+                let warnings = WarningsDB::get_instance().scope(message)
                     .get_all(&target.id.to_string()).await;
 
                 if let Ok(mut warnings) = warnings {
@@ -47,7 +43,7 @@ impl Command for WarningsCommand {
 
                     // get amount of warnings to display
                     let limit = min(total_warnings,
-                        match message.has_parameter("more") {
+                        match params.arguments.has("more") {
                             true => 24,
                             false => 5
                         });
@@ -56,21 +52,49 @@ impl Command for WarningsCommand {
                     // create embed
                     let name = message.get_resolver().resolve_name(target);
                     let mut builder = message.get_log_builder()
+                        .for_author()
                         .target(target)
-                        .title(format!("{}'s Warnings", name))
+                        .title(text!("warnings.title", user = name))
                         .no_thumbnail();
 
+                    // get removed warnings, if requested
+                    let removed = match params.arguments.has("removed") {
+                        true => match RemovalsDB::get_instance().scope(message).get_all(&target.id.to_string()).await {
+                            Ok(removed) => removed.into_iter()
+                                .filter(|removal| removal.table == DB::Warnings.to_string())
+                                .collect::<Vec<RemovalLog>>(),
+                            Err(err) => return message.reply_db_error(err).await,
+                        },
+                        false => Vec::new(),
+                    };
+
+                    // an embed holds at most 25 fields
+                    let total_removed = removed.len();
+                    let removed_limit = min(total_removed, 25 - limit);
+                    let removed = &removed[total_removed - removed_limit..];
+
                     // add warnings to embed
-                    let embed = match warnings.len() {
-                        0 => builder.description("No registered warnings.").build().await,
-                        len @ _ => {
+                    let embed = match warnings.len() + removed.len() {
+                        0 => builder.description("warnings.none").build().await,
+                        _ => {
+                            let len = warnings.len();
                             for warning in warnings.into_iter() {
                                 builder = builder.mod_log(&warning);
                             }
+                            for removal in removed {
+                                match removal.restore::<ModLog>() {
+                                    Ok(warning) => builder = builder.removed_log(&warning, removal),
+                                    Err(err) => return message.reply_db_error(err).await,
+                                }
+                            }
+                            let footer = match params.arguments.has("removed") {
+                                true  => text!("warnings.footer-removed", count = len, total = total_warnings,
+                                    removed = removed.len(), total_removed = total_removed),
+                                false => text!("warnings.footer", count = len, total = total_warnings),
+                            };
+                            let footer = message.text(footer).await;
                             builder.build().await
-                                .footer(CreateEmbedFooter::new(
-                                    format!("Displaying {} of {} Warnings", len, total_warnings)
-                                ))
+                                .footer(CreateEmbedFooter::new(footer))
                         }
                     };
 
@@ -0,0 +1,476 @@
+use serenity::model::prelude::*;
+
+use std::sync::Arc;
+
+use crate::commands::command_manager::CommandManager;
+use crate::utility::*;
+use crate::databases::*;
+
+
+// Commands run against a guild in memory. Time is paused, so replies that
+// are deleted after a few seconds do not slow the tests down.
+
+async fn setup() -> (Arc<FakeGuild>, CommandManager) {
+    let guild = FakeGuild::populated("Test Guild").await.expect("Failed to configure guild");
+    (guild, CommandManager::new())
+}
+
+// runs a command in a channel, as the user of the given name
+async fn run(guild: &Arc<FakeGuild>, manager: &CommandManager, channel: &str, author: &str, content: &str) -> MessageManager {
+    let channel = guild.channel_named(channel);
+    let author = guild.user_named(author);
+    let message = guild.message(channel, &author, content).await;
+    manager.execute(&message).await;
+    message
+}
+
+fn mention(guild: &FakeGuild, name: &str) -> String {
+    format!("<@{}>", guild.user_named(name).id)
+}
+
+// the outcome of the last use of a command
+async fn outcome(guild: &FakeGuild, trigger: &str) -> String {
+    CommandUsesDB::get_instance().scope(guild.guild_id)
+        .get_last(trigger, 1).await
+        .expect("Failed to read command uses")
+        .first()
+        .map(|command_use| command_use.outcome.clone())
+        .unwrap_or_default()
+}
+
+// the titles of all embeds, logs show theirs as the author
+fn titles(messages: &[Message]) -> Vec<String> {
+    messages.iter()
+        .flat_map(|message| message.embeds.iter())
+        .filter_map(|embed| embed.title.clone()
+            .or(embed.author.as_ref().map(|author| author.name.clone())))
+        .collect()
+}
+
+// the last embed the bot sent in a channel
+fn last_embed(guild: &FakeGuild, channel: &str) -> Embed {
+    guild.messages_in(guild.channel_named(channel)).iter()
+        .rev()
+        .find_map(|message| message.embeds.first().cloned())
+        .expect("No embed sent")
+}
+
+fn sends_messages(overwrite: &PermissionOverwrite) -> Option<bool> {
+    match (overwrite.allow.send_messages(), overwrite.deny.send_messages()) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn warn_records_and_logs_the_warning() {
+    let (guild, manager) = setup().await;
+    let target = guild.user_named("member");
+
+    let content = format!("?warn {} spamming", mention(&guild, "member"));
+    let message = run(&guild, &manager, "general", "trial", &content).await;
+
+    assert!(!message.has_failed());
+    assert_eq!(outcome(&guild, "warn").await, "success");
+    let warnings = WarningsDB::get_instance().scope(guild.guild_id)
+        .get_all(&target.id.to_string()).await.unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].reason, "spamming");
+
+    // the command is replaced by the warning
+    let general = guild.messages_in(guild.channel_named("general"));
+    assert!(general.iter().all(|message| message.author.id == guild.bot.id));
+    assert!(general.iter().any(|message| message.content == mention(&guild, "member")));
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("modlogs"))), vec!["[WARNING]"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn moderators_cannot_be_warned() {
+    let (guild, manager) = setup().await;
+    let target = guild.user_named("mod");
+
+    let content = format!("?warn {}", mention(&guild, "mod"));
+    let message = run(&guild, &manager, "general", "trial", &content).await;
+
+    assert!(message.has_failed());
+    assert_eq!(outcome(&guild, "warn").await, "failed");
+    let warnings = WarningsDB::get_instance().scope(guild.guild_id)
+        .get_all(&target.id.to_string()).await.unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn members_cannot_warn() {
+    let (guild, manager) = setup().await;
+
+    let content = format!("?warn {}", mention(&guild, "visitor"));
+    run(&guild, &manager, "general", "member", &content).await;
+
+    assert_eq!(outcome(&guild, "warn").await, "denied");
+    assert!(guild.messages_in(guild.channel_named("modlogs")).is_empty());
+}
+
+#[cfg(feature = "auto_moderation")]
+#[tokio::test(start_paused = true)]
+async fn third_warning_mutes_automatically() {
+    let (guild, manager) = setup().await;
+    let target = guild.user_named("member");
+
+    for reason in ["one", "two", "three"] {
+        let content = format!("?warn {} {}", mention(&guild, "member"), reason);
+        run(&guild, &manager, "general", "mod", &content).await;
+    }
+
+    assert!(guild.member_roles(target.id).contains(&guild.role_named("Muted")));
+    assert!(titles(&guild.messages_in(guild.channel_named("modlogs"))).contains(&"[AUTOMATIC MUTE]".to_string()));
+    assert!(titles(&guild.messages_in(guild.channel_named("muted"))).contains(&"Automatic Mute".to_string()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn mute_adds_the_muted_role_once() {
+    let (guild, manager) = setup().await;
+    let target = guild.user_named("member");
+    let content = format!("?mute {} trolling", mention(&guild, "member"));
+
+    let message = run(&guild, &manager, "general", "trial", &content).await;
+    assert!(!message.has_failed());
+    assert_eq!(guild.member_roles(target.id), vec![guild.role_named("member"), guild.role_named("Muted")]);
+    let mutes = MutesDB::get_instance().scope(guild.guild_id)
+        .get_all(&target.id.to_string()).await.unwrap();
+    assert_eq!(mutes.len(), 1);
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("modlogs"))), vec!["[MUTE]"]);
+
+    // muting again changes nothing
+    let message = run(&guild, &manager, "general", "trial", &content).await;
+    assert!(message.has_failed());
+    let mutes = MutesDB::get_instance().scope(guild.guild_id)
+        .get_all(&target.id.to_string()).await.unwrap();
+    assert_eq!(mutes.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn mute_needs_a_configured_role() {
+    let (guild, manager) = setup().await;
+    config::change(guild.guild_id.into(), "role_muted", Vec::new(), "1").await.unwrap();
+
+    let content = format!("?mute {} trolling", mention(&guild, "member"));
+    let message = run(&guild, &manager, "general", "trial", &content).await;
+    assert!(message.has_failed());
+    assert_eq!(guild.member_roles(guild.user_named("member").id), vec![guild.role_named("member")]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn purge_deletes_the_last_messages() {
+    let (guild, manager) = setup().await;
+    let general = guild.channel_named("general");
+    let member = guild.user_named("member");
+    let visitor = guild.user_named("visitor");
+    for i in 0..5 {
+        guild.message(general, &member, &format!("member {}", i)).await;
+        guild.message(general, &visitor, &format!("visitor {}", i)).await;
+    }
+
+    // only the messages of the user among the last four
+    let content = format!("?purge 4 {}", mention(&guild, "visitor"));
+    let message = run(&guild, &manager, "general", "headmod", &content).await;
+    assert!(!message.has_failed());
+    let remaining = guild.messages_in(general).into_iter()
+        .filter(|message| message.author.id != guild.bot.id)
+        .map(|message| message.content)
+        .collect::<Vec<String>>();
+    assert_eq!(remaining, vec![
+        "member 0", "visitor 0", "member 1", "visitor 1", "member 2", "visitor 2", "member 3", "member 4"]);
+
+    run(&guild, &manager, "general", "headmod", "?purge 3").await;
+    let remaining = guild.messages_in(general).into_iter()
+        .filter(|message| message.author.id != guild.bot.id)
+        .count();
+    assert_eq!(remaining, 5);
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("modlogs"))), vec!["[PURGE]", "[PURGE]"]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn purge_respects_protected_categories_and_levels() {
+    let (guild, manager) = setup().await;
+    let staff_chat = guild.channel_named("staff-chat");
+    let member = guild.user_named("member");
+    guild.message(staff_chat, &member, "evidence").await;
+
+    let message = run(&guild, &manager, "staff-chat", "headmod", "?purge 5").await;
+    assert!(message.has_failed());
+    run(&guild, &manager, "general", "mod", "?purge 5").await;
+    assert_eq!(outcome(&guild, "purge").await, "denied");
+
+    assert!(guild.messages_in(staff_chat).iter().any(|message| message.content == "evidence"));
+    assert!(guild.messages_in(guild.channel_named("modlogs")).is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn lockdown_denies_and_restores_sending() {
+    let (guild, manager) = setup().await;
+    let everyone = PermissionOverwriteType::Role(RoleId::new(guild.guild_id.get()));
+    let locked = ["general", "memes"].map(|name| guild.channel_named(name));
+    let everyone_sends = |channel: ChannelId| guild.overwrites(channel).iter()
+        .find(|overwrite| overwrite.kind == everyone)
+        .and_then(sends_messages);
+
+    run(&guild, &manager, "modlogs", "mod", "?lockdown").await;
+    for channel in locked {
+        assert_eq!(everyone_sends(channel), Some(false));
+        assert_eq!(titles(&guild.messages_in(channel)), vec!["Server has been locked down!"]);
+    }
+    assert!(guild.overwrites(guild.channel_named("modlogs")).is_empty());
+
+    run(&guild, &manager, "modlogs", "mod", "?lockdown -end").await;
+    for channel in locked {
+        assert_eq!(everyone_sends(channel), Some(true));
+    }
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("modlogs"))), vec!["[LOCKDOWN]", "[LOCKDOWN END]"]);
+}
+
+#[cfg(feature = "tickets")]
+#[tokio::test(start_paused = true)]
+async fn tickets_are_opened_claimed_and_closed() {
+    let (guild, manager) = setup().await;
+    let member = guild.user_named("member");
+    let visitor = guild.user_named("visitor");
+    let moderator = guild.user_named("mod");
+    let member_sends = |channel: ChannelId, user: UserId| guild.overwrites(channel).iter()
+        .find(|overwrite| overwrite.kind == PermissionOverwriteType::Member(user))
+        .and_then(sends_messages);
+
+    // the ticket is a channel in the ticket category, named after the member
+    let content = format!("?open {} d", mention(&guild, "member"));
+    run(&guild, &manager, "general", "trial", &content).await;
+    let ticket = guild.channel_named("member");
+    let channel = guild.channel(ticket).unwrap();
+    assert_eq!(channel.parent_id, Some(guild.channel_named("Tickets")));
+    assert_eq!(channel.topic.as_deref(), Some("Discussion"));
+    assert_eq!(member_sends(ticket, member.id), Some(true));
+    assert!(guild.messages_in(ticket).iter().any(|message| message.content.contains(&mention(&guild, "member"))));
+
+    run(&guild, &manager, "member", "mod", "?claim").await;
+    assert_eq!(member_sends(ticket, moderator.id), Some(true));
+    let message = run(&guild, &manager, "member", "mod", "?claim").await;
+    assert!(message.has_failed());
+
+    let content = format!("?add {}", mention(&guild, "visitor"));
+    run(&guild, &manager, "member", "mod", &content).await;
+    assert_eq!(member_sends(ticket, visitor.id), Some(true));
+    let content = format!("?remove {}", mention(&guild, "visitor"));
+    run(&guild, &manager, "member", "mod", &content).await;
+    assert_eq!(member_sends(ticket, visitor.id), Some(false));
+
+    // members cannot close tickets
+    run(&guild, &manager, "member", "member", "?close").await;
+    assert_eq!(outcome(&guild, "close").await, "denied");
+    assert!(guild.channel(ticket).is_some());
+
+    run(&guild, &manager, "member", "mod", "?close").await;
+    assert!(guild.channel(ticket).is_none());
+    assert!(TicketHandler::get_instance().get_ticket(&ticket).await.is_none());
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("transcripts"))), vec!["Ticket Log"]);
+}
+
+#[cfg(feature = "tickets")]
+#[tokio::test(start_paused = true)]
+async fn closing_a_muted_ticket_reminds_the_staff() {
+    let (guild, manager) = setup().await;
+    let moderator = guild.user_named("mod");
+
+    let content = format!("?open {} m", mention(&guild, "member"));
+    run(&guild, &manager, "general", "mod", &content).await;
+    run(&guild, &manager, "member", "mod", "?close").await;
+
+    let reminders = guild.direct_messages_to(moderator.id);
+    assert_eq!(reminders.len(), 1);
+    assert!(reminders[0].content.starts_with("Do not forget to `unmute`"));
+}
+
+#[cfg(feature = "tickets")]
+#[tokio::test(start_paused = true)]
+async fn ticket_commands_need_a_ticket() {
+    let (guild, manager) = setup().await;
+
+    for command in ["?claim", "?close"] {
+        let message = run(&guild, &manager, "general", "mod", command).await;
+        assert!(message.has_failed());
+    }
+    assert!(guild.messages_in(guild.channel_named("transcripts")).is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn members_read_replies_in_their_language() {
+    let (guild, manager) = setup().await;
+    let member = guild.user_named("member");
+    let stored = || async {
+        LocalesDB::get_instance().global().get(&member.id.to_string()).await.ok().map(|entry| entry.value)
+    };
+
+    run(&guild, &manager, "general", "member", "?language de").await;
+    assert_eq!(stored().await.as_deref(), Some("de"));
+    assert!(titles(&guild.messages_in(guild.channel_named("general"))).contains(&"Sprache".to_string()));
+
+    // logs are still written in the language of the guild
+    let content = format!("?warn {} spamming", mention(&guild, "member"));
+    run(&guild, &manager, "general", "mod", &content).await;
+    assert_eq!(titles(&guild.messages_in(guild.channel_named("modlogs"))), vec!["[WARNING]"]);
+
+    let message = run(&guild, &manager, "general", "member", "?language klingon").await;
+    assert!(message.has_failed());
+    assert_eq!(stored().await.as_deref(), Some("de"));
+
+    run(&guild, &manager, "general", "member", "?language -reset").await;
+    assert_eq!(stored().await, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn help_only_lists_permitted_commands() {
+    let (guild, manager) = setup().await;
+
+    run(&guild, &manager, "general", "member", "?help").await;
+    assert!(last_embed(&guild, "general").description.unwrap().contains("`Casual`"));
+
+    run(&guild, &manager, "general", "member", "?help moderation").await;
+    let description = last_embed(&guild, "general").description.unwrap();
+    assert!(description.contains("`?notes"));
+    assert!(!description.contains("`?check-ban"));
+
+    // commands the author may not run are not found either
+    let message = run(&guild, &manager, "general", "member", "?help warn").await;
+    assert!(message.has_failed());
+
+    run(&guild, &manager, "general", "trial", "?help moderation").await;
+    let description = last_embed(&guild, "general").description.unwrap();
+    assert!(description.contains("`?check-ban"));
+    assert!(!description.contains("`?ban"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn help_shows_categories_and_commands() {
+    let (guild, manager) = setup().await;
+
+    run(&guild, &manager, "general", "mod", "?help moderation").await;
+    let category = last_embed(&guild, "general");
+    assert_eq!(category.author.unwrap().name, "Moderation Commands");
+    assert!(category.description.unwrap().contains("`?ban (user) [reason]`"));
+    assert!(category.footer.unwrap().text.starts_with("Page 1 of "));
+
+    run(&guild, &manager, "general", "mod", "?help warn").await;
+    let command = last_embed(&guild, "general");
+    assert_eq!(command.author.unwrap().name, "?warn");
+    let level = command.fields.iter().find(|field| field.name == "Required Level").unwrap();
+    assert_eq!(level.value, "`trial`");
+}
+
+#[tokio::test(start_paused = true)]
+async fn rejected_uses_do_not_count_for_the_cooldown() {
+    let (guild, manager) = setup().await;
+    guild.create_role("Tweets");
+
+    let content = format!("?tweet {}", "a".repeat(281));
+    let message = run(&guild, &manager, "general", "member", &content).await;
+    assert!(message.has_failed());
+
+    run(&guild, &manager, "general", "member", "?tweet hello").await;
+    assert_eq!(outcome(&guild, "tweet").await, "success");
+
+    run(&guild, &manager, "general", "member", "?tweet hello again").await;
+    assert_eq!(outcome(&guild, "tweet").await, "cancelled");
+}
+
+#[tokio::test(start_paused = true)]
+async fn simultaneous_uses_share_the_cooldown() {
+    let (guild, manager) = setup().await;
+    guild.create_role("Tweets");
+
+    let (first, second) = tokio::join!(
+        run(&guild, &manager, "general", "member", "?tweet hello"),
+        run(&guild, &manager, "general", "member", "?tweet hello again"));
+    assert_ne!(first.has_failed(), second.has_failed());
+}
+
+#[tokio::test(start_paused = true)]
+async fn unconfigured_guilds_can_be_configured() {
+    let (_, manager) = setup().await;
+    let guild = FakeGuild::new("New Guild");
+    let config = ConfigDB::get_instance().scope(guild.guild_id);
+    config.set("color_primary", "#7289da").await.unwrap();
+    config.set("bot_id", &guild.bot.id.to_string()).await.unwrap();
+    guild.create_channel_in("general", None);
+    let modlogs = guild.create_channel_in("modlogs", None);
+    let owner = guild.create_member("owner", &[]);
+    guild.set_owner(owner.id);
+    guild.create_member("member", &[]);
+
+    // commands that need the configuration report what is missing
+    let message = run(&guild, &manager, "general", "member", "?8ball will it work").await;
+    assert!(message.has_failed());
+    assert_eq!(outcome(&guild, "8ball").await, "denied");
+
+    // only the owner may repair it, with the default prefix
+    let content = format!("?config set channel_modlogs {}", modlogs);
+    run(&guild, &manager, "general", "member", &content).await;
+    assert_eq!(outcome(&guild, "config").await, "denied");
+    run(&guild, &manager, "general", "owner", &content).await;
+    assert_eq!(outcome(&guild, "config").await, "success");
+    let stored = ConfigDB::get_instance().scope(guild.guild_id).get("channel_modlogs").await.unwrap();
+    assert_eq!(stored.value, modlogs.to_string());
+
+    run(&guild, &manager, "general", "owner", "?help moderation").await;
+    assert!(last_embed(&guild, "general").description.unwrap().contains("`?config"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn retentions_are_validated_when_set() {
+    let (guild, manager) = setup().await;
+
+    for value in ["soon", "0d"] {
+        let message = run(&guild, &manager, "general", "admin", &format!("?config set retention_warnings {}", value)).await;
+        assert!(message.has_failed());
+    }
+    run(&guild, &manager, "general", "admin", "?config set retention_warnings 30d").await;
+    let stored = ConfigDB::get_instance().scope(guild.guild_id).get("retention_warnings").await.unwrap();
+    assert_eq!(stored.value, "30d");
+}
+
+#[tokio::test(start_paused = true)]
+async fn changed_prefixes_apply_at_once() {
+    let (guild, manager) = setup().await;
+
+    run(&guild, &manager, "general", "member", "?8ball will it work").await;
+    assert_eq!(outcome(&guild, "8ball").await, "success");
+    run(&guild, &manager, "general", "admin", "?config set command_prefix !").await;
+
+    let general = guild.channel_named("general");
+    let message = guild.message(general, &guild.user_named("member"), "?8ball will it still work").await;
+    assert!(!message.is_command());
+    let message = run(&guild, &manager, "general", "member", "!8ball will it still work").await;
+    assert_eq!(message.get_prefix().as_deref(), Some("!"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn command_settings_apply_at_once() {
+    let (guild, manager) = setup().await;
+
+    run(&guild, &manager, "general", "member", "?8ball will it work").await;
+    assert_eq!(outcome(&guild, "8ball").await, "success");
+
+    run(&guild, &manager, "general", "admin", "?config set disabled_8ball true").await;
+    run(&guild, &manager, "general", "member", "?8ball will it work").await;
+    assert_eq!(outcome(&guild, "8ball").await, "denied");
+
+    run(&guild, &manager, "general", "admin", "?config set disabled_8ball false").await;
+    run(&guild, &manager, "general", "member", "?8ball will it work").await;
+    assert_eq!(outcome(&guild, "8ball").await, "success");
+}
+
+#[tokio::test(start_paused = true)]
+async fn help_fails_without_the_bot_user() {
+    let (guild, manager) = setup().await;
+    config::change(guild.guild_id.into(), "bot_id", vec!["1".to_string()], "1").await.unwrap();
+
+    let message = run(&guild, &manager, "general", "member", "?help").await;
+    assert!(message.has_failed());
+}
@@ -10,18 +10,13 @@ pub struct AddMemberToTicketCommand;
 
 impl Command for AddMemberToTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["add-user".to_string(), "add".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -37,18 +32,19 @@ impl Command for AddMemberToTicketCommand {
                     Some(ticket) => {
 
                         if ticket.present_members.lock().await.contains(&member) {
-                            message.reply_failure(&format!("<@{}> is already in this ticket!", member)).await;
+                            message.reply_failure(text!("add.already-added", user = format!("<@{}>", member))).await;
                             return;
                         }
 
                         ticket.add_member(&member).await;
+                        let added = message.guild_text(text!("ticket.added", user = format!("<@{}>", member))).await;
                         let embed = MessageManager::create_embed(|embed| {
                             embed
-                                .description(format!("Added <@{}>", member))
+                                .description(added)
                             }).await;
                         let _ = message.reply(embed).await;
                     },
-                    None => message.reply_failure("This channel is not a ticket!").await
+                    None => message.reply_failure("ticket.not-a-ticket").await
                 }
             }
         )
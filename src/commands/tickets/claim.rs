@@ -10,17 +10,12 @@ pub struct ClaimTicketCommand;
 
 impl Command for ClaimTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["claim".to_string()]
         )
+            .level(PermissionLevel::Trial)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -36,18 +31,19 @@ impl Command for ClaimTicketCommand {
                     Some(ticket) => {
 
                         if ticket.present_staff.lock().await.contains(&staff) {
-                            message.reply_failure("You have already claimed this ticket!").await;
+                            message.reply_failure("claim.already-claimed").await;
                             return;
                         }
 
                         ticket.add_staff(&staff).await;
+                        let claimed = message.guild_text(text!("ticket.claimed", staff = format!("<@{}>", staff))).await;
                         let embed = MessageManager::create_embed(|embed| {
                             embed
-                                .description(format!("Claimed by <@{}>", staff))
+                                .description(claimed)
                             }).await;
                         let _ = message.reply(embed).await;
                     },
-                    None => message.reply_failure("This channel is not a ticket!").await
+                    None => message.reply_failure("ticket.not-a-ticket").await
                 }
             }
         )
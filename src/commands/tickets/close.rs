@@ -13,20 +13,13 @@ pub struct CloseTicketCommand;
 
 impl Command for CloseTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["close".to_string()]
         )
-            .new_usage()
-            .add_constant("-tw", false)
-            .add_optional("trigger warning")
+            .level(PermissionLevel::Trial)
+            .add_optional("-tw trigger warning", ArgumentType::Text)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -42,18 +35,21 @@ impl Command for CloseTicketCommand {
                     Some(ticket) => {
 
                         // send close message
+                        let closed = message.guild_text(text!("ticket.closed", staff = format!("<@{}>", staff))).await;
                         let embed = MessageManager::create_embed(|embed| {
                             embed
-                                .description(format!("Closed by <@{}>", staff))
+                                .description(closed)
                             }).await;
                         let _ = message.reply(embed).await;
 
                         // obtain channel to dump log
-                        let dump_channel: ChannelId = match ticket.ticket_type {
-                            TicketType::StaffReport => ConfigDB::get_instance()
-                                .get("channel_admin").await.unwrap().into(),
-                            _ => ConfigDB::get_instance()
-                                .get("channel_transcripts").await.unwrap().into()
+                        let config = match BotConfig::get(message).await {
+                            Ok(config) => config,
+                            Err(err) => return message.reply_db_error(err).await,
+                        };
+                        let dump_channel = match ticket.ticket_type {
+                            TicketType::StaffReport => config.channel_admin,
+                            _ => config.channel_transcripts,
                         };
 
                         // produce transcript
@@ -64,11 +60,12 @@ impl Command for CloseTicketCommand {
                             .close_ticket(&ticket.channel.id).await;
 
                         // get the host url (where this bot is hosted)
-                        let web_url = ConfigDB::get_instance().get("web_url").await.unwrap();
+                        let web_url = &config.web_url;
 
                         // obtain ticket information
                         let transcript_url = format!(
-                            "[External Link]({}/transcripts/transcript-{}---{}---.html?auth)",
+                            "[{}]({}/transcripts/transcript-{}---{}---.html?auth)",
+                            message.guild_text("ticket-log.external-link").await,
                             web_url,
                             ticket.channel.name,
                             ticket.uuid);
@@ -84,32 +81,31 @@ impl Command for CloseTicketCommand {
                         // construct log
                         let ticket_type: String = ticket.ticket_type.into();
                         let mut builder = message.get_log_builder()
-                            .title("Ticket Log")
+                            .title("ticket-log.title")
                             .no_thumbnail()
-                            .arbitrary("Category", &ticket_type)
-                            .arbitrary("Staff", &staff)
-                            .arbitrary("Members", &members)
-                            .arbitrary("Transcript", &transcript_url);
+                            .arbitrary("ticket-log.category", &ticket_type)
+                            .arbitrary("ticket-log.staff", &staff)
+                            .arbitrary("ticket-log.members", &members)
+                            .arbitrary("ticket-log.transcript", &transcript_url);
 
                         // add field for trigger warning if specified
-                        if message.has_parameter("tw") {
+                        if let Some(trigger_warning) = params.arguments.text("tw") {
                             builder = builder.arbitrary_block(
-                                "⚠️ Trigger Warning",
-                                message.get_parameter("tw")
+                                "ticket-log.trigger-warning",
+                                trigger_warning
                             );
                         }
 
                         // send log
                         let embed = builder.build().await;
-                        let _ = dump_channel.send_message(message, embed.to_message()).await;
+                        let _ = message.get_resolver().send_message(dump_channel, embed).await;
 
                         if ticket.ticket_type == TicketType::Muted {
-                            let dms = message.get_author().create_dm_channel(message).await.unwrap();
-                            let note = format!("Do not forget to `unmute`, `flag`, or `ban` the member(s) in {}", ticket.channel.name);
-                            let _ = dms.send_message(message, note.to_message()).await;
+                            let note = message.text(text!("close.muted-reminder", channel = &ticket.channel.name)).await;
+                            let _ = message.get_resolver().direct_message(message.get_author().id, note).await;
                         }
                     },
-                    None => message.reply_failure("This channel is not a ticket!").await
+                    None => message.reply_failure("ticket.not-a-ticket").await
                 }
             }
         )
@@ -10,21 +10,16 @@ pub struct OpenTicketCommand;
 
 impl Command for OpenTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["open".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
             .add_constant("m", false)
             .new_usage()
-            .add_required("user")
+            .add_required("user", ArgumentType::User)
             .add_constant("d", false)
             .example("@ModAnnoyer m")
     }
@@ -36,19 +31,17 @@ impl Command for OpenTicketCommand {
                 let message = params.message;
                 let target  = &params.target.unwrap();
 
-                // reject if incorrect type
-                let ticket_type = message.payload_without_mentions(None, None).to_lowercase();
-                if ticket_type != "m" && ticket_type != "d" {
-                    message.reply_failure("The type of a Ticket can be either `m` (Muted) or `d` (Discussion).").await;
-                    return;
-                }
+                let ticket_type = match params.arguments.has("m") {
+                    true  => "m".to_string(),
+                    false => "d".to_string(),
+                };
 
                 // create ticket
                 let ticket = TicketHandler::get_instance()
                     .new_ticket(message.get_resolver(), target, ticket_type.clone().into()).await;
 
                 if let Err(_) = ticket {
-                    message.reply_failure("Failed to create ticket.").await;
+                    message.reply_failure("open.failed").await;
                     return;
                 }
             }
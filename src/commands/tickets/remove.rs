@@ -10,18 +10,13 @@ pub struct RemoveMemberFromTicketCommand;
 
 impl Command for RemoveMemberFromTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["remove-user".to_string(), "remove".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::Trial)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -37,18 +32,19 @@ impl Command for RemoveMemberFromTicketCommand {
                     Some(ticket) => {
 
                         if !ticket.present_members.lock().await.contains(&member) {
-                            message.reply_failure(&format!("<@{}> is not in this ticket!", member)).await;
+                            message.reply_failure(text!("remove.not-added", user = format!("<@{}>", member))).await;
                             return;
                         }
 
                         ticket.remove_member(&member).await;
+                        let removed = message.guild_text(text!("ticket.removed", user = format!("<@{}>", member))).await;
                         let embed = MessageManager::create_embed(|embed| {
                             embed
-                                .description(format!("Removed <@{}>", member))
+                                .description(removed)
                             }).await;
                         let _ = message.reply(embed).await;
                     },
-                    None => message.reply_failure("This channel is not a ticket!").await
+                    None => message.reply_failure("ticket.not-a-ticket").await
                 }
             }
         )
@@ -7,6 +7,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use crate::commands::command::*;
+use crate::commands::middleware::{Middleware, Confirmation};
 use crate::utility::*;
 use crate::databases::*;
 
@@ -15,17 +16,16 @@ pub struct ResetTicketsCommand;
 
 impl Command for ResetTicketsCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_admin().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["monthly-reset".to_string()]
         )
+            .level(PermissionLevel::Admin)
+    }
+
+    fn middleware(&self) -> Vec<Box<dyn Middleware>> {
+        vec![Box::new(Confirmation { prompt: "monthly-reset.confirmation" })]
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -34,12 +34,16 @@ impl Command for ResetTicketsCommand {
 
                 let message = &params.message;
 
-                let keys = TicketReviewsDB::get_instance()
-                    .get_keys().await;
+                let keys = match TicketReviewsDB::get_instance().scope(message).get_keys().await {
+                    Ok(keys) => keys,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
 
                 // delete all reviews fom the database
                 for key in keys {
-                    TicketReviewsDB::get_instance().delete(&key).await;
+                    if let Err(err) = TicketReviewsDB::get_instance().scope(message).delete(&key).await {
+                        return message.reply_db_error(err).await;
+                    }
                 }
 
                 // delete reviews from local storage
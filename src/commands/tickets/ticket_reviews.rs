@@ -14,18 +14,13 @@ pub struct TicketReviewsCommand;
 
 impl Command for TicketReviewsCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["ticket-reviews".to_string(), "reviews".to_string()]
         )
-            .add_required("user")
+            .level(PermissionLevel::HeadMod)
+            .add_required("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -34,8 +29,9 @@ impl Command for TicketReviewsCommand {
 
                 let message = &params.message;
                 let target = &params.target.unwrap();
+                let locale = message.locale().await;
 
-                let reviews = TicketReviewsDB::get_instance()
+                let reviews = TicketReviewsDB::get_instance().scope(message)
                     .get_all(&target.id.to_string()).await;
 
                 if let Ok(reviews) = reviews {
@@ -49,33 +45,36 @@ impl Command for TicketReviewsCommand {
                     match reviews.len() {
                         0 => {
                             let embed = message.get_log_builder()
+                                .for_author()
                                 .target(target)
                                 .no_thumbnail()
-                                .title(format!("{}'s Reviews", name))
-                                .description("No reviews found.")
+                                .title(text!("ticket-reviews.title", user = &name))
+                                .description("ticket-reviews.none")
                                 .build().await;
                             let _ = message.reply(embed).await;
                         },
                         _ => {
                             for (index, chunk) in reviews.iter().enumerate() {
                                 let mut builder = message.get_log_builder()
+                                    .for_author()
                                     .target(target)
                                     .no_thumbnail()
-                                    .title(format!("{}'s Reviews", name));
+                                    .title(text!("ticket-reviews.title", user = &name));
 
                                 // add fields for each review
                                 for entry in chunk.iter() {
 
+                                    let verdict = match entry.approved { true => "review.approved", false => "review.denied" };
                                     builder = builder.arbitrary_block(
-                                        format!("**Database ID**: {}", entry.id),
-                                        format!("<@{}> `>` {}\n**Notes**: {}",
-                                            target.id,
-                                            match entry.approved { true => "Approved", false => "Denied" },
-                                            entry.notes));
+                                        text!("log.database-id", id = entry.id),
+                                        text!("ticket-reviews.review",
+                                            user = format!("<@{}>", target.id),
+                                            verdict = Text::new(verdict).localize(locale),
+                                            notes = &entry.notes).localize(locale));
                                 }
                                 let embed = builder.build().await
                                     .footer(CreateEmbedFooter::new(
-                                        format!("Page {} of {}", index + 1, reviews.len())));
+                                        text!("ticket-reviews.page", page = index + 1, pages = reviews.len()).localize(locale)));
 
                                 let _ = message.reply(embed).await;
                             }
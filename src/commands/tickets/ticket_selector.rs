@@ -13,17 +13,12 @@ pub struct TicketSelectorCommand;
 
 impl Command for TicketSelectorCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_admin().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["select-ticket".to_string(), "ticket-selector".to_string()]
         )
+            .level(PermissionLevel::Admin)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -31,8 +26,10 @@ impl Command for TicketSelectorCommand {
             async move {
 
                 let message = params.message;
-                let bot_id: UserId = ConfigDB::get_instance()
-                    .get("bot_id").await.unwrap().into();
+                let bot_id: UserId = match BotConfig::get(&message).await {
+                    Ok(config) => config.bot_id,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
 
                 let reactions = vec![ReactionType::Unicode("📁".to_string()),
@@ -42,11 +39,11 @@ impl Command for TicketSelectorCommand {
 
                 let selector = message.get_log_builder()
                     .target(&bot)
-                    .title("Kalopsia's Support Tickets")
-                    .arbitrary_block("📁 Staff Report", "> Report a member of the staff team to a Head-Moderator or Administrator. Be sure to have evidence ready.")
-                    .arbitrary_block("💼 User Report",  "> Report a user of the server to the staff team. Be sure to have evidence ready.")
-                    .arbitrary_block("📔 Bug Report",   "> Help us improve the server by reporting bugs or issues you encounter.")
-                    .arbitrary_block("🤔 Question",     "> Do you have any questions about the server? Ask them here.")
+                    .title("ticket-selector.title")
+                    .arbitrary_block("ticket-selector.staff-report", message.guild_text("ticket-selector.staff-report-description").await)
+                    .arbitrary_block("ticket-selector.user-report",  message.guild_text("ticket-selector.user-report-description").await)
+                    .arbitrary_block("ticket-selector.bug-report",   message.guild_text("ticket-selector.bug-report-description").await)
+                    .arbitrary_block("ticket-selector.question",     message.guild_text("ticket-selector.question-description").await)
                     .build().await
                     .to_message()
                     .reactions(reactions);
@@ -18,13 +18,8 @@ impl Command for TicketStatsCommand {
             CommandType::Tickets,
             nonempty!["ticket-stats".to_string()]
         )
-            .add_optional("user")
-    }
-
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_headmod().await
-        })
+            .level(PermissionLevel::HeadMod)
+            .add_optional("user", ArgumentType::User)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -33,21 +28,26 @@ impl Command for TicketStatsCommand {
 
                 let message = &params.message;
 
-                let mut target_ids = message.get_mentions().await.into_iter()
+                let mut target_ids = params.arguments.user("user").into_iter()
                     .map(|user_id| user_id.to_string())
                     .collect::<Vec<_>>();
                 if target_ids.is_empty() {
-                    target_ids = TicketReviewsDB::get_instance()
-                        .get_keys().await;
+                    target_ids = match TicketReviewsDB::get_instance().scope(message).get_keys().await {
+                        Ok(target_ids) => target_ids,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
                 }
 
                 // resolve bot
-                let bot_id: UserId = ConfigDB::get_instance()
-                    .get("bot_id").await.unwrap().into();
+                let bot_id: UserId = match BotConfig::get(message).await {
+                    Ok(config) => config.bot_id,
+                    Err(err) => return message.reply_db_error(err).await,
+                };
                 let bot = message.get_resolver().resolve_user(bot_id).await.unwrap();
 
                 let mut builder = message.get_log_builder()
-                    .title("Ticket Statistics")
+                    .for_author()
+                    .title("ticket-stats.title")
                     .target(&bot);
 
                 // collect statistics for each target
@@ -57,18 +57,20 @@ impl Command for TicketStatsCommand {
                     let user = message.get_resolver().resolve_user(user_id).await.unwrap();
                     let name = message.get_resolver().resolve_name(&user);
 
-                    let reviews = TicketReviewsDB::get_instance()
-                        .get_all(&target_id).await.unwrap();
+                    let reviews = match TicketReviewsDB::get_instance().scope(message).get_all(&target_id).await {
+                        Ok(reviews) => reviews,
+                        Err(err) => return message.reply_db_error(err).await,
+                    };
 
                     if reviews.is_empty() {
                         builder = builder
-                            .arbitrary_block(name, "No reviews found.".to_string());
+                            .arbitrary_block(Text::plain(name), message.text("ticket-stats.no-reviews").await);
                         continue;
                     }
 
                     let approved = reviews.iter().filter(|review| review.approved).count();
                     builder = builder
-                        .arbitrary_block(name, format!(
+                        .arbitrary_block(Text::plain(name), format!(
                             "{}% ({}/{})", approved * 100 / reviews.len(), approved, reviews.len()
                         ));
                 }
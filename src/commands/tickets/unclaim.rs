@@ -10,17 +10,12 @@ pub struct UnclaimTicketCommand;
 
 impl Command for UnclaimTicketCommand {
 
-    fn permission<'a>(&'a self, message: &'a MessageManager) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            message.is_trial().await
-        })
-    }
-
     fn define_usage(&self) -> UsageBuilder {
         UsageBuilder::new(
             CommandType::Tickets,
             nonempty!["unclaim".to_string()]
         )
+            .level(PermissionLevel::Trial)
     }
 
     fn run(&self, params: CommandParams) -> BoxedFuture<'_, ()> {
@@ -36,18 +31,19 @@ impl Command for UnclaimTicketCommand {
                     Some(ticket) => {
 
                         if !ticket.present_staff.lock().await.contains(&staff) {
-                            message.reply_failure("You have not claimed this ticket").await;
+                            message.reply_failure("unclaim.not-claimed").await;
                             return;
                         }
 
                         ticket.remove_staff(&staff).await;
+                        let unclaimed = message.guild_text(text!("ticket.unclaimed", staff = format!("<@{}>", staff))).await;
                         let embed = MessageManager::create_embed(|embed| {
                             embed
-                                .description(format!("Unclaimed by <@{}>", staff))
+                                .description(unclaimed)
                             }).await;
                         let _ = message.reply(embed).await;
                     },
-                    None => message.reply_failure("This channel is not a ticket!").await
+                    None => message.reply_failure("ticket.not-a-ticket").await
                 }
             }
         )
@@ -0,0 +1,521 @@
+use serde::{Serialize, Deserialize};
+use rusqlite::types::Value;
+use strum::IntoEnumIterator;
+
+use crate::databases::database::DB;
+use crate::databases::schema::*;
+use crate::databases::query::*;
+use crate::databases::migrations::latest_version;
+use crate::databases::wrappers::get_storage;
+use crate::databases::error::*;
+use crate::databases::config::ConfigCache;
+use crate::utility::Singleton;
+use crate::databases::scope::Scope;
+
+
+// Bump this whenever the layout of the archive itself changes. Changes to
+// the tables are covered by the schema version stored with every table.
+pub const ARCHIVE_VERSION: i64 = 2;
+
+// The bot token is never written to a backup and never replaced by one.
+const PRIVATE_KEYS: [(DB, &str); 1] = [(DB::Config, "token")];
+
+#[derive(Serialize, Deserialize)]
+pub struct Archive {
+    pub version: i64,
+    pub created: i64,
+    pub tables: Vec<TableDump>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableDump {
+    pub name: String,
+    pub schema_version: i64,
+    pub columns: Vec<String>,
+    pub rows: Vec<RowDump>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RowDump {
+    pub id: i64,
+    pub key: String,
+    pub guild_id: String,
+    pub timestamp: i64,
+    pub values: Vec<serde_json::Value>,
+}
+
+fn is_private(identifier: &DB, key: &str) -> bool {
+    PRIVATE_KEYS.iter().any(|(table, private)| table == identifier && *private == key)
+}
+
+fn find_table(name: &str) -> DBResult<DB> {
+    DB::iter()
+        .find(|identifier| identifier.to_string() == name)
+        .ok_or(DBError::Corrupt(format!("unknown table '{}'", name)))
+}
+
+// the rows of the guild, or of all guilds without one
+fn rows_of(scope: Option<Scope>) -> Query {
+    match scope {
+        Some(scope) => Query::new().scope(scope),
+        None => Query::new(),
+    }
+}
+
+// all rows of a table that may leave the bot, ordered by insertion
+async fn export_rows(identifier: &DB, scope: Option<Scope>) -> DBResult<Vec<DBRow>> {
+    let rows = get_storage(identifier)
+        .query_rows(&rows_of(scope).order(Order::Ascending)).await?;
+    Ok(rows.into_iter()
+        .filter(|row| !is_private(identifier, &row.key))
+        .collect())
+}
+
+// Replaces the rows of the guild, or of all guilds without one, with the
+// given rows. Rows with private keys are dropped and the current private
+// rows are kept instead.
+async fn restore(scope: Option<Scope>, tables: Vec<(DB, Vec<DBRow>)>) -> DBResult<usize> {
+    let mut count = 0;
+    for (identifier, rows) in tables {
+        let storage = get_storage(&identifier);
+        let mut rows = rows.into_iter()
+            .filter(|row| !is_private(&identifier, &row.key))
+            .collect::<Vec<DBRow>>();
+        count += rows.len();
+        // the ids of the archive win, the kept rows get fresh ones
+        rows.extend(storage.query_rows(&rows_of(scope)).await?
+            .into_iter()
+            .filter(|row| is_private(&identifier, &row.key)));
+        storage.restore_rows(scope, rows).await?;
+    }
+    ConfigCache::get_instance().invalidate(scope.unwrap_or(Scope::Global));
+    Ok(count)
+}
+
+pub fn json_value(value: Value, column_type: ColumnType) -> serde_json::Value {
+    match (value, column_type) {
+        (Value::Integer(value), ColumnType::Boolean) => serde_json::Value::Bool(value != 0),
+        (Value::Integer(value), _) => value.into(),
+        (Value::Text(value), _)    => value.into(),
+        (Value::Real(value), _)    => value.into(),
+        _ => serde_json::Value::Null,
+    }
+}
+
+pub fn from_json_value(value: &serde_json::Value, column: &Column) -> DBResult<Value> {
+    let converted = match (column.column_type, value) {
+        (ColumnType::Text, serde_json::Value::String(value)) => Some(Value::Text(value.clone())),
+        (ColumnType::Integer, serde_json::Value::Number(value)) => value.as_i64().map(Value::Integer),
+        (ColumnType::Boolean, serde_json::Value::Bool(value)) => Some(Value::Integer(*value as i64)),
+        (ColumnType::Boolean, serde_json::Value::Number(value)) => value.as_i64()
+            .filter(|value| *value == 0 || *value == 1)
+            .map(Value::Integer),
+        _ => None,
+    };
+    converted.ok_or(DBError::Corrupt(format!("invalid value {} for column '{}'", value, column.name)))
+}
+
+// The position of every column of the table in the dumped columns. Columns
+// that are missing from the dump are filled with their default value.
+fn column_positions(columns: &[Column], names: &[String]) -> DBResult<Vec<Option<usize>>> {
+    if let Some(unknown) = names.iter().find(|name| !columns.iter().any(|column| column.name == name.as_str())) {
+        return Err(DBError::Corrupt(format!("unknown column '{}'", unknown)));
+    }
+    Ok(columns.iter()
+        .map(|column| names.iter().position(|name| name == column.name))
+        .collect())
+}
+
+// Exports the rows of the guild, or of all guilds without one.
+pub async fn export_json(scope: Option<Scope>) -> DBResult<String> {
+    let mut tables = Vec::new();
+    for identifier in DB::iter() {
+        let columns = get_storage(&identifier).columns();
+        let rows = export_rows(&identifier, scope).await?
+            .into_iter()
+            .map(|row| RowDump {
+                id: row.id,
+                key: row.key,
+                guild_id: row.guild_id,
+                timestamp: row.timestamp,
+                values: row.values.into_iter()
+                    .zip(columns)
+                    .map(|(value, column)| json_value(value, column.column_type))
+                    .collect(),
+            })
+            .collect();
+        tables.push(TableDump {
+            name: identifier.to_string(),
+            schema_version: latest_version(),
+            columns: columns.iter().map(|column| column.name.to_string()).collect(),
+            rows,
+        });
+    }
+    let archive = Archive {
+        version: ARCHIVE_VERSION,
+        created: chrono::Utc::now().timestamp(),
+        tables,
+    };
+    serde_json::to_string_pretty(&archive)
+        .map_err(|err| DBError::Io(err.to_string()))
+}
+
+// Validates the whole archive before anything is written. Tables that are
+// not part of the archive are left untouched, just like the rows of other
+// guilds if a guild is given. Returns the restored row count.
+pub async fn import_json(scope: Option<Scope>, content: &str) -> DBResult<usize> {
+
+    let archive: Archive = serde_json::from_str(content)
+        .map_err(|err| DBError::Corrupt(format!("invalid archive: {}", err)))?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(DBError::Corrupt(format!("unsupported archive version {}", archive.version)));
+    }
+
+    let mut tables = Vec::new();
+    for table in archive.tables {
+        let identifier = find_table(&table.name)?;
+        if tables.iter().any(|(other, _)| other == &identifier) {
+            return Err(DBError::Corrupt(format!("table '{}' appears twice", table.name)));
+        }
+        if table.schema_version > latest_version() {
+            return Err(DBError::Corrupt(format!("table '{}' is from a newer version", table.name)));
+        }
+        let columns = get_storage(&identifier).columns();
+        let positions = column_positions(columns, &table.columns)?;
+        let mut rows = Vec::new();
+        for row in table.rows {
+            if row.values.len() != table.columns.len() {
+                return Err(DBError::Corrupt(format!("row {} of '{}' has the wrong length", row.id, table.name)));
+            }
+            let values = columns.iter()
+                .zip(&positions)
+                .map(|(column, position)| match position {
+                    Some(position) => from_json_value(&row.values[*position], column),
+                    None => Ok(column.column_type.default_value()),
+                })
+                .collect::<DBResult<Vec<Value>>>()?;
+            rows.push(DBRow { id: row.id, key: row.key, guild_id: row.guild_id, timestamp: row.timestamp, values });
+        }
+        check_ids(&table.name, &rows)?;
+        check_scope(scope, &table.name, &rows)?;
+        tables.push((identifier, rows));
+    }
+
+    restore(scope, tables).await
+}
+
+fn check_ids(table: &str, rows: &[DBRow]) -> DBResult<()> {
+    let mut ids = rows.iter().map(|row| row.id).collect::<Vec<i64>>();
+    ids.sort();
+    match ids.windows(2).find(|pair| pair[0] == pair[1]) {
+        Some(pair) => Err(DBError::Corrupt(format!("id {} appears twice in '{}'", pair[0], table))),
+        None => Ok(()),
+    }
+}
+
+// a guild can only restore its own rows
+fn check_scope(scope: Option<Scope>, table: &str, rows: &[DBRow]) -> DBResult<()> {
+    let Some(scope) = scope else {
+        return Ok(());
+    };
+    match rows.iter().find(|row| row.guild_id != scope.id()) {
+        Some(row) => Err(DBError::Corrupt(format!("row {} of '{}' belongs to another guild", row.id, table))),
+        None => Ok(()),
+    }
+}
+
+// Every table is written to its own file named `<table>.csv`. The first
+// line holds the column names, booleans are written as `true` or `false`.
+pub async fn export_csv(scope: Option<Scope>) -> DBResult<Vec<(String, String)>> {
+    let mut files = Vec::new();
+    for identifier in DB::iter() {
+        let columns = get_storage(&identifier).columns();
+        let mut header = vec!["id", "key", "guild_id", "timestamp"];
+        header.extend(columns.iter().map(|column| column.name));
+        let mut lines = vec![csv_line(header.into_iter().map(String::from))];
+        for row in export_rows(&identifier, scope).await? {
+            let mut fields = vec![row.id.to_string(), row.key, row.guild_id, row.timestamp.to_string()];
+            fields.extend(row.values.into_iter()
+                .zip(columns)
+                .map(|(value, column)| match json_value(value, column.column_type) {
+                    serde_json::Value::String(value) => value,
+                    value => value.to_string(),
+                }));
+            lines.push(csv_line(fields.into_iter()));
+        }
+        files.push((format!("{}.csv", identifier), lines.join("\r\n") + "\r\n"));
+    }
+    Ok(files)
+}
+
+// Restores the tables of the given `<table>.csv` files, like `import_json`.
+pub async fn import_csv(scope: Option<Scope>, files: Vec<(String, String)>) -> DBResult<usize> {
+
+    let mut tables = Vec::new();
+    for (name, content) in files {
+        let table = name.strip_suffix(".csv")
+            .ok_or(DBError::Corrupt(format!("'{}' is not a csv file", name)))?;
+        let identifier = find_table(table)?;
+        if tables.iter().any(|(other, _)| other == &identifier) {
+            return Err(DBError::Corrupt(format!("table '{}' appears twice", table)));
+        }
+        let columns = get_storage(&identifier).columns();
+
+        let mut records = parse_csv(&content)?.into_iter();
+        let header = records.next()
+            .ok_or(DBError::Corrupt(format!("'{}' is empty", name)))?;
+        if header.len() < 4 || header[..4] != ["id", "key", "guild_id", "timestamp"] {
+            return Err(DBError::Corrupt(format!("'{}' has an invalid header", name)));
+        }
+        let positions = column_positions(columns, &header[4..])?;
+
+        let mut rows = Vec::new();
+        for (line, record) in records.enumerate() {
+            let invalid = || DBError::Corrupt(format!("line {} of '{}' is invalid", line + 2, name));
+            if record.len() != header.len() {
+                return Err(invalid());
+            }
+            let values = columns.iter()
+                .zip(&positions)
+                .map(|(column, position)| match position {
+                    Some(position) => parse_csv_value(&record[position + 4], column),
+                    None => Ok(column.column_type.default_value()),
+                })
+                .collect::<DBResult<Vec<Value>>>()?;
+            rows.push(DBRow {
+                id: record[0].parse().map_err(|_| invalid())?,
+                key: record[1].clone(),
+                guild_id: record[2].clone(),
+                timestamp: record[3].parse().map_err(|_| invalid())?,
+                values,
+            });
+        }
+        check_ids(table, &rows)?;
+        check_scope(scope, table, &rows)?;
+        tables.push((identifier, rows));
+    }
+
+    restore(scope, tables).await
+}
+
+fn parse_csv_value(field: &str, column: &Column) -> DBResult<Value> {
+    let value = match column.column_type {
+        ColumnType::Text    => Some(Value::Text(field.to_string())),
+        ColumnType::Integer => field.parse::<i64>().ok().map(Value::Integer),
+        ColumnType::Boolean => match field {
+            "true"  | "1" => Some(Value::Integer(1)),
+            "false" | "0" => Some(Value::Integer(0)),
+            _ => None,
+        },
+    };
+    value.ok_or(DBError::Corrupt(format!("invalid value '{}' for column '{}'", field, column.name)))
+}
+
+// fields are quoted whenever they contain a separator, quote or line break
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    fields
+        .map(|field| match field.contains([',', '"', '\n', '\r']) {
+            true  => format!("\"{}\"", field.replace('"', "\"\"")),
+            false => field,
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn parse_csv(content: &str) -> DBResult<Vec<Vec<String>>> {
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') => match chars.peek() {
+                Some('"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                _ => quoted = false,
+            },
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {},
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(DBError::Corrupt("unterminated quote in csv file".to_string()));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::databases::*;
+    use crate::databases::testing::guild;
+
+    async fn warnings(scope: Scope) -> Vec<(i64, String)> {
+        WarningsDB::get_instance().scope(scope).query(Query::new().order(Order::Ascending)).await
+            .expect("Failed to read warnings")
+            .into_iter()
+            .map(|warning| (warning.id, warning.reason))
+            .collect()
+    }
+
+    async fn warn(scope: Scope, user: &str, reason: &str) {
+        WarningsDB::get_instance().scope(scope)
+            .append(user, ModLog::new("1".to_string(), reason.to_string())).await
+            .expect("Failed to warn");
+    }
+
+    // a guild with text, boolean and integer values to back up
+    async fn filled_guild() -> Scope {
+        let scope = guild();
+        warn(scope, "10", "spam, \"links\"\nand more").await;
+        warn(scope, "11", "").await;
+        FlagsDB::get_instance().scope(scope)
+            .append("10", FlagLog::new("1".to_string(), "alt".to_string(), true)).await.unwrap();
+        RemindersDB::get_instance().scope(scope)
+            .append("10", ScheduleLog::new(1_700_000_000, "stretch".to_string(), "5".to_string())).await.unwrap();
+        scope
+    }
+
+    async fn snapshot(scope: Scope) -> Vec<Vec<DBRow>> {
+        let mut tables = Vec::new();
+        for identifier in [DB::Warnings, DB::Flags, DB::Reminders] {
+            tables.push(get_storage(&identifier)
+                .query_rows(&Query::new().scope(scope).order(Order::Ascending)).await.unwrap());
+        }
+        tables
+    }
+
+    // changes every table after the export
+    async fn change(scope: Scope) {
+        warn(scope, "12", "after the export").await;
+        FlagsDB::get_instance().scope(scope).delete("10").await.unwrap();
+        RemindersDB::get_instance().scope(scope).delete("10").await.unwrap();
+    }
+
+    #[test]
+    fn csv_fields_keep_separators_quotes_and_line_breaks() {
+        let fields = vec!["plain", "a,b", "say \"hi\"", "two\nlines", "crlf\r\nend", "", "\""]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        let line = csv_line(fields.clone().into_iter());
+        assert_eq!(line, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\"crlf\r\nend\",,\"\"\"\"");
+
+        let content = format!("{}\r\n{}\n", line, csv_line(vec!["last".to_string()].into_iter()));
+        assert_eq!(parse_csv(&content).unwrap(), vec![fields, vec!["last".to_string()]]);
+
+        // the last line does not need a line break
+        assert_eq!(parse_csv("a,b").unwrap(), vec![vec!["a".to_string(), "b".to_string()]]);
+        assert!(matches!(parse_csv("a,\"b\nc"), Err(DBError::Corrupt(_))));
+    }
+
+    #[tokio::test]
+    async fn json_backups_restore_every_value() {
+        let scope = filled_guild().await;
+        let before = snapshot(scope).await;
+        let exported = export_json(Some(scope)).await.unwrap();
+
+        change(scope).await;
+        assert_eq!(import_json(Some(scope), &exported).await.unwrap(), 4);
+        assert_eq!(snapshot(scope).await, before);
+    }
+
+    #[tokio::test]
+    async fn csv_backups_restore_every_value() {
+        let scope = filled_guild().await;
+        let before = snapshot(scope).await;
+        let files = export_csv(Some(scope)).await.unwrap();
+
+        change(scope).await;
+        assert_eq!(import_csv(Some(scope), files).await.unwrap(), 4);
+        assert_eq!(snapshot(scope).await, before);
+    }
+
+    #[tokio::test]
+    async fn malformed_backups_are_rejected() {
+        let scope = filled_guild().await;
+        let before = snapshot(scope).await;
+        let exported = export_json(Some(scope)).await.unwrap();
+        let header = "id,key,guild_id,timestamp,staff_id,reason\n";
+        let row = |fields: &str| format!("{}{}\n", header, fields);
+
+        let archives = [
+            "not json".to_string(),
+            exported.replace(&format!("\"version\": {}", ARCHIVE_VERSION), "\"version\": 0"),
+            exported.replace("\"name\": \"warnings\"", "\"name\": \"unknown\""),
+            exported.replace("\"name\": \"flags\"", "\"name\": \"warnings\""),
+            exported.replace("\"staff_id\"", "\"unknown\""),
+        ];
+        for archive in archives {
+            assert!(matches!(import_json(Some(scope), &archive).await, Err(DBError::Corrupt(_))), "{}", archive);
+        }
+
+        let files = [
+            ("unknown.csv", row(&format!("1,10,{},0,1,spam", scope.id()))),
+            ("warnings.txt", row(&format!("1,10,{},0,1,spam", scope.id()))),
+            ("warnings.csv", String::new()),
+            ("warnings.csv", format!("key,id\n10,1,{}\n", scope.id())),
+            ("warnings.csv", row(&format!("1,10,{},0,1", scope.id()))),
+            ("warnings.csv", row(&format!("one,10,{},0,1,spam", scope.id()))),
+            ("warnings.csv", row(&format!("1,10,{},0,1,\"spam", scope.id()))),
+            ("flags.csv", format!("id,key,guild_id,timestamp,monthly\n1,10,{},0,maybe\n", scope.id())),
+        ];
+        for (name, content) in files {
+            let result = import_csv(Some(scope), vec![(name.to_string(), content.clone())]).await;
+            assert!(matches!(result, Err(DBError::Corrupt(_))), "{}: {}", name, content);
+        }
+
+        // nothing is written unless the whole backup is valid
+        let mut files = export_csv(Some(scope)).await.unwrap();
+        files.push(("unknown.csv".to_string(), String::new()));
+        assert!(import_csv(Some(scope), files).await.is_err());
+        assert_eq!(snapshot(scope).await, before);
+    }
+
+    #[tokio::test]
+    async fn guild_backups_leave_other_guilds_alone() {
+        let (first, second) = (guild(), guild());
+        warn(first, "10", "spam").await;
+        warn(second, "20", "flood").await;
+        NotesDB::get_instance().scope(second)
+            .set("rules", Note::new("be nice".to_string())).await.unwrap();
+
+        let exported = export_json(Some(first)).await.unwrap();
+        let archive: Archive = serde_json::from_str(&exported).unwrap();
+        assert!(archive.tables.iter()
+            .flat_map(|table| table.rows.iter())
+            .all(|row| row.guild_id == first.id()));
+
+        // the import replaces the rows of its guild only
+        warn(first, "10", "after the export").await;
+        let before = warnings(second).await;
+        assert_eq!(import_json(Some(first), &exported).await.unwrap(), 1);
+        assert_eq!(warnings(first).await.into_iter().map(|(_, reason)| reason).collect::<Vec<_>>(), vec!["spam"]);
+        assert_eq!(warnings(second).await, before);
+        assert!(NotesDB::get_instance().scope(second).has("rules").await.unwrap());
+
+        // the backup of one guild can not be imported into another
+        let files = export_csv(Some(first)).await.unwrap();
+        assert!(matches!(import_json(Some(second), &exported).await, Err(DBError::Corrupt(_))));
+        assert!(matches!(import_csv(Some(second), files).await, Err(DBError::Corrupt(_))));
+        assert_eq!(warnings(second).await, before);
+    }
+}
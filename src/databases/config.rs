@@ -0,0 +1,643 @@
+use serenity::all::{ChannelId, Colour, RoleId, UserId};
+use once_cell::sync::Lazy;
+use strum::IntoEnumIterator;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::databases::*;
+use crate::databases::wrappers::Scoped;
+use crate::utility::*;
+use crate::impl_singleton;
+
+
+// The typed configuration of a guild. It is validated as a whole when it is
+// loaded, so code that reads it never runs into a missing or malformed key.
+#[derive(Debug, Clone, Default)]
+pub struct BotConfig {
+    pub command_prefix: String,
+    pub locale: Locale,
+    pub bot_id: UserId,
+    pub web_url: String,
+
+    pub channel_suggestions: ChannelId,
+    pub channel_event_suggestions: ChannelId,
+    pub channel_transcripts: ChannelId,
+    pub channel_headmod: ChannelId,
+    pub channel_reviews: ChannelId,
+    pub channel_unbanlogs: ChannelId,
+    pub channel_verify: ChannelId,
+    pub channel_welcome: ChannelId,
+    pub channel_tweets: ChannelId,
+    pub channel_admin: ChannelId,
+    pub channel_member_count: ChannelId,
+    pub channel_messagelogs: ChannelId,
+    pub channel_muted: ChannelId,
+    pub channel_modlogs: ChannelId,
+    pub channel_tickets: ChannelId,
+    pub channel_protected_log: Vec<ChannelId>,
+
+    pub category_music: Option<ChannelId>,
+    pub category_tickets: ChannelId,
+    pub category_lockdown: Vec<ChannelId>,
+    pub category_protected_slowmode: Vec<ChannelId>,
+    pub category_protected_purge: Vec<ChannelId>,
+
+    pub role_admin: Vec<RoleId>,
+    pub role_headmod: Vec<RoleId>,
+    pub role_mod: Vec<RoleId>,
+    pub role_trial: Vec<RoleId>,
+    pub role_trusted: Vec<RoleId>,
+    pub role_member: Vec<RoleId>,
+    pub role_muted: Option<RoleId>,
+    pub role_auto_mute: Option<RoleId>,
+}
+
+// the prefix of guilds that did not choose one
+pub const DEFAULT_PREFIX: &str = "?";
+
+// All problems with the configuration of a guild, reported at once.
+#[derive(Debug, Clone)]
+pub struct ConfigReport {
+    pub scope: Scope,
+    pub issues: Vec<String>,
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid configuration ({})", self.scope)?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Database(DBError),
+    Invalid(ConfigReport),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Database(err) => write!(f, "{}", err),
+            ConfigError::Invalid(report) => write!(f, "{}", report),
+        }
+    }
+}
+
+impl From<DBError> for ConfigError {
+    fn from(err: DBError) -> ConfigError {
+        ConfigError::Database(err)
+    }
+}
+
+impl From<ConfigError> for DBError {
+    fn from(err: ConfigError) -> DBError {
+        match err {
+            ConfigError::Database(err) => err,
+            ConfigError::Invalid(report) => DBError::Corrupt(report.to_string()),
+        }
+    }
+}
+
+// Reads the keys of a scope and collects their problems instead of failing
+// on the first one. Missing values are replaced by their default.
+struct ConfigReader<'a> {
+    config: Scoped<'a, DBEntry>,
+    issues: Vec<String>,
+}
+
+impl ConfigReader<'_> {
+
+    fn report(&mut self, key: &str, err: DBError) -> DBResult<()> {
+        match err {
+            DBError::NotFound(_) => self.issues.push(format!("`{}` is not set", key)),
+            DBError::Corrupt(reason) => self.issues.push(format!("`{}` is invalid: {}", key, reason)),
+            err => return Err(err),
+        }
+        Ok(())
+    }
+
+    async fn text(&mut self, key: &str) -> DBResult<String> {
+        match self.config.get(key).await {
+            Ok(value) => Ok(value.to_string()),
+            Err(err) => self.report(key, err).map(|_| String::new()),
+        }
+    }
+
+    async fn required<V: TryFrom<DBEntry, Error = DBError> + Default>(&mut self, key: &str) -> DBResult<V> {
+        match self.config.get_as(key).await {
+            Ok(value) => Ok(value),
+            Err(err) => self.report(key, err).map(|_| V::default()),
+        }
+    }
+
+    async fn optional<V: TryFrom<DBEntry, Error = DBError>>(&mut self, key: &str) -> DBResult<Option<V>> {
+        match self.config.get_as(key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(DBError::NotFound(_)) => Ok(None),
+            Err(err) => self.report(key, err).map(|_| None),
+        }
+    }
+
+    async fn list<V: TryFrom<DBEntry, Error = DBError>>(&mut self, key: &str) -> DBResult<Vec<V>> {
+        let mut values = Vec::new();
+        for entry in self.config.get_all(key).await? {
+            match V::try_from(entry) {
+                Ok(value) => values.push(value),
+                Err(err) => self.report(key, err)?,
+            }
+        }
+        Ok(values)
+    }
+
+    // a list that needs at least one value
+    async fn required_list<V: TryFrom<DBEntry, Error = DBError>>(&mut self, key: &str) -> DBResult<Vec<V>> {
+        let values = self.list(key).await?;
+        if values.is_empty() {
+            self.report(key, DBError::NotFound(key.to_string()))?;
+        }
+        Ok(values)
+    }
+}
+
+impl BotConfig {
+
+    // Reads and validates the configuration of a scope. Keys that are not
+    // set for a guild fall back to their global value.
+    pub async fn load(scope: Scope) -> std::result::Result<BotConfig, ConfigError> {
+
+        let mut reader = ConfigReader {
+            config: ConfigDB::get_instance().scope(scope),
+            issues: Vec::new(),
+        };
+
+        // embeds are styled without a guild, the color is only validated here
+        reader.required::<Colour>("color_primary").await?;
+
+        let config = BotConfig {
+            command_prefix: BotConfig::prefix(scope).await,
+            locale: reader.optional("locale").await?.unwrap_or_default(),
+            bot_id: reader.required("bot_id").await?,
+            web_url: reader.text("web_url").await?,
+
+            channel_suggestions: reader.required("channel_suggestions").await?,
+            channel_event_suggestions: reader.required("channel_event_suggestions").await?,
+            channel_transcripts: reader.required("channel_transcripts").await?,
+            channel_headmod: reader.required("channel_headmod").await?,
+            channel_reviews: reader.required("channel_reviews").await?,
+            channel_unbanlogs: reader.required("channel_unbanlogs").await?,
+            channel_verify: reader.required("channel_verify").await?,
+            channel_welcome: reader.required("channel_welcome").await?,
+            channel_tweets: reader.required("channel_tweets").await?,
+            channel_admin: reader.required("channel_admin").await?,
+            channel_member_count: reader.required("channel_member_count").await?,
+            channel_messagelogs: reader.required("channel_messagelogs").await?,
+            channel_muted: reader.required("channel_muted").await?,
+            channel_modlogs: reader.required("channel_modlogs").await?,
+            channel_tickets: reader.required("channel_tickets").await?,
+            channel_protected_log: reader.list("channel_protected_log").await?,
+
+            category_music: reader.optional("category_music").await?,
+            category_tickets: reader.required("category_tickets").await?,
+            category_lockdown: reader.list("category_lockdown").await?,
+            category_protected_slowmode: reader.list("category_protected_slowmode").await?,
+            category_protected_purge: reader.list("category_protected_purge").await?,
+
+            role_admin: reader.required_list("role_admin").await?,
+            role_headmod: reader.required_list("role_headmod").await?,
+            role_mod: reader.required_list("role_mod").await?,
+            role_trial: reader.required_list("role_trial").await?,
+            role_trusted: reader.list("role_trusted").await?,
+            role_member: reader.list("role_member").await?,
+            role_muted: reader.optional("role_muted").await?,
+            role_auto_mute: reader.optional("role_auto_mute").await?,
+        };
+
+        match reader.issues.is_empty() {
+            true  => Ok(config),
+            false => Err(ConfigError::Invalid(ConfigReport { scope, issues: reader.issues })),
+        }
+    }
+
+    // The command prefix of a scope, cached on its own so that commands are
+    // still recognized while other keys are missing or invalid.
+    pub async fn prefix(scope: Scope) -> String {
+        let cache = ConfigCache::get_instance();
+        let cached = cache.prefixes.read().expect("Could not get config cache")
+            .get(&scope)
+            .cloned();
+        if let Some(prefix) = cached {
+            return prefix;
+        }
+
+        let prefix = match ConfigDB::get_instance().scope(scope).get("command_prefix").await {
+            Ok(prefix) if !prefix.value.is_empty() => prefix.value,
+            Ok(_) | Err(DBError::NotFound(_)) => DEFAULT_PREFIX.to_string(),
+            Err(err) => {
+                Logger::err_long("Failed to get command prefix", &err.to_string());
+                return DEFAULT_PREFIX.to_string();
+            }
+        };
+        cache.prefixes.write().expect("Could not get config cache")
+            .insert(scope, prefix.clone());
+        prefix
+    }
+
+    // The color of all embeds. It can only be set globally, as embeds are
+    // styled without a guild, and is cached like the prefix.
+    pub async fn color_primary() -> Colour {
+        let cache = ConfigCache::get_instance();
+        if let Some(color) = *cache.color_primary.read().expect("Could not get config cache") {
+            return color;
+        }
+
+        match ConfigDB::get_instance().global().get_as::<Colour>("color_primary").await {
+            Ok(color) => {
+                *cache.color_primary.write().expect("Could not get config cache") = Some(color);
+                color
+            }
+            Err(err) => {
+                Logger::err_long("Failed to get primary color", &err.to_string());
+                Colour::default()
+            }
+        }
+    }
+
+    // The roles of exactly this level, read on their own like the prefix,
+    // so the configuration can be repaired by the ones allowed to. Malformed
+    // roles are skipped.
+    pub async fn level_roles(scope: Scope, level: PermissionLevel) -> Vec<RoleId> {
+        let Some(key) = level.key() else {
+            return Vec::new();
+        };
+        ConfigDB::get_instance().scope(scope).get_all(&key).await
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|entry| RoleId::try_from(entry).ok())
+            .collect()
+    }
+
+    // the roles of exactly this level, everyone has no roles
+    pub fn roles(&self, level: PermissionLevel) -> &[RoleId] {
+        match level {
+            PermissionLevel::Everyone => &[],
+            PermissionLevel::Member   => &self.role_member,
+            PermissionLevel::Trusted  => &self.role_trusted,
+            PermissionLevel::Trial    => &self.role_trial,
+            PermissionLevel::Mod      => &self.role_mod,
+            PermissionLevel::HeadMod  => &self.role_headmod,
+            PermissionLevel::Admin    => &self.role_admin,
+        }
+    }
+
+    // the roles of a level and of all levels above it, e.g. all staff roles,
+    // only tickets and the auto moderator need them
+    #[allow(unused)]
+    pub fn roles_from(&self, level: PermissionLevel) -> Vec<RoleId> {
+        PermissionLevel::iter()
+            .filter(|other| *other >= level)
+            .flat_map(|other| self.roles(other).to_vec())
+            .collect()
+    }
+
+    // The cached configuration of a scope, it is loaded on first access.
+    pub async fn get(scope: impl Into<Scope>) -> DBResult<Arc<BotConfig>> {
+        let scope = scope.into();
+        let cache = ConfigCache::get_instance();
+        let cached = cache.configs.read().expect("Could not get config cache")
+            .get(&scope)
+            .cloned();
+        if let Some(config) = cached {
+            return Ok(config);
+        }
+
+        let config = Arc::new(BotConfig::load(scope).await?);
+        cache.configs.write().expect("Could not get config cache")
+            .insert(scope, Arc::clone(&config));
+        Ok(config)
+    }
+}
+
+// How a command may be used in a guild, configured at runtime. Commands
+// without any of these keys keep the permission they define themselves.
+#[derive(Debug, Clone, Default)]
+pub struct CommandSettings {
+    pub disabled: bool,
+    // channels and categories, the command is allowed everywhere if empty
+    pub channels: Vec<ChannelId>,
+    pub permission: Option<PermissionLevel>,
+}
+
+impl CommandSettings {
+
+    // Keys that are not set for a guild fall back to their global value,
+    // malformed values are ignored like missing ones.
+    async fn load(scope: Scope, trigger: &str) -> DBResult<CommandSettings> {
+
+        let config = ConfigDB::get_instance().scope(scope);
+        let disabled = match config.get(&format!("{}{}", DISABLED_PREFIX, trigger)).await {
+            Ok(value) => value.to_string() == "true",
+            Err(DBError::NotFound(_)) => false,
+            Err(err) => return Err(err),
+        };
+        let channels = config.get_all(&format!("{}{}", CHANNELS_PREFIX, trigger)).await?
+            .into_iter()
+            .filter_map(|entry| ChannelId::try_from(entry).ok())
+            .collect();
+        let permission = match config.get(&format!("{}{}", PERMISSION_PREFIX, trigger)).await {
+            Ok(value) => PermissionLevel::from_str(&value.to_string()).ok(),
+            Err(DBError::NotFound(_)) => None,
+            Err(err) => return Err(err),
+        };
+
+        Ok(CommandSettings { disabled, channels, permission })
+    }
+
+    // the settings of all commands that are configured in a guild or globally
+    async fn load_all(scope: Scope) -> DBResult<HashMap<String, CommandSettings>> {
+        let mut keys = ConfigDB::get_instance().scope(scope).get_keys().await?;
+        if scope != Scope::Global {
+            keys.extend(ConfigDB::get_instance().global().get_keys().await?);
+        }
+        let triggers = keys.iter()
+            .filter_map(|key| [DISABLED_PREFIX, CHANNELS_PREFIX, PERMISSION_PREFIX].iter()
+                .find_map(|prefix| key.strip_prefix(prefix)))
+            .map(|trigger| trigger.to_string())
+            .collect::<HashSet<String>>();
+
+        let mut settings = HashMap::new();
+        for trigger in triggers {
+            let loaded = CommandSettings::load(scope, &trigger).await?;
+            settings.insert(trigger, loaded);
+        }
+        Ok(settings)
+    }
+
+    // The cached settings of a command, the settings of all commands of a
+    // guild are loaded together on first access.
+    pub async fn get(scope: impl Into<Scope>, trigger: &str) -> DBResult<CommandSettings> {
+        let scope = scope.into();
+        let cache = ConfigCache::get_instance();
+        let cached = cache.settings.read().expect("Could not get config cache")
+            .get(&scope)
+            .cloned();
+        let settings = match cached {
+            Some(settings) => settings,
+            None => {
+                let settings = Arc::new(CommandSettings::load_all(scope).await?);
+                cache.settings.write().expect("Could not get config cache")
+                    .insert(scope, Arc::clone(&settings));
+                settings
+            }
+        };
+        Ok(settings.get(trigger).cloned().unwrap_or_default())
+    }
+}
+
+pub struct ConfigCache {
+    configs: RwLock<HashMap<Scope, Arc<BotConfig>>>,
+    settings: RwLock<HashMap<Scope, Arc<HashMap<String, CommandSettings>>>>,
+    prefixes: RwLock<HashMap<Scope, String>>,
+    color_primary: RwLock<Option<Colour>>,
+}
+
+impl_singleton!(ConfigCache);
+
+impl ConfigCache {
+
+    pub fn new() -> Self {
+        ConfigCache {
+            configs: RwLock::new(HashMap::new()),
+            settings: RwLock::new(HashMap::new()),
+            prefixes: RwLock::new(HashMap::new()),
+            color_primary: RwLock::new(None),
+        }
+    }
+
+    // Drops the cached configuration of a guild, so it is loaded again on
+    // next access. Global keys are inherited by all guilds, so changing the
+    // global configuration drops every cached configuration.
+    pub fn invalidate(&self, scope: Scope) {
+        let mut configs = self.configs.write().expect("Could not get config cache");
+        let mut settings = self.settings.write().expect("Could not get config cache");
+        let mut prefixes = self.prefixes.write().expect("Could not get config cache");
+        match scope {
+            Scope::Global => {
+                configs.clear();
+                settings.clear();
+                prefixes.clear();
+                *self.color_primary.write().expect("Could not get config cache") = None;
+            }
+            scope => {
+                configs.remove(&scope);
+                settings.remove(&scope);
+                prefixes.remove(&scope);
+            }
+        }
+    }
+}
+
+// The kind of value a configuration key holds, used to validate changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    Text,
+    User,
+    Colour,
+    Channel,
+    Category,
+    Role,
+    Retention,
+    Boolean,
+    Location,
+    Level,
+    Locale,
+}
+
+const KEYS: [(&str, ConfigKind); 34] = [
+    ("command_prefix",              ConfigKind::Text),
+    ("locale",                      ConfigKind::Locale),
+    ("bot_id",                      ConfigKind::User),
+    ("color_primary",               ConfigKind::Colour),
+    ("web_url",                     ConfigKind::Text),
+    ("channel_suggestions",         ConfigKind::Channel),
+    ("channel_event_suggestions",   ConfigKind::Channel),
+    ("channel_transcripts",         ConfigKind::Channel),
+    ("channel_headmod",             ConfigKind::Channel),
+    ("channel_reviews",             ConfigKind::Channel),
+    ("channel_unbanlogs",           ConfigKind::Channel),
+    ("channel_verify",              ConfigKind::Channel),
+    ("channel_welcome",             ConfigKind::Channel),
+    ("channel_tweets",              ConfigKind::Channel),
+    ("channel_admin",               ConfigKind::Channel),
+    ("channel_member_count",        ConfigKind::Channel),
+    ("channel_protected_log",       ConfigKind::Channel),
+    ("channel_messagelogs",         ConfigKind::Channel),
+    ("channel_muted",               ConfigKind::Channel),
+    ("channel_modlogs",             ConfigKind::Channel),
+    ("channel_tickets",             ConfigKind::Channel),
+    ("category_music",              ConfigKind::Category),
+    ("category_tickets",            ConfigKind::Category),
+    ("category_lockdown",           ConfigKind::Category),
+    ("category_protected_slowmode", ConfigKind::Category),
+    ("category_protected_purge",    ConfigKind::Category),
+    ("role_admin",                  ConfigKind::Role),
+    ("role_headmod",                ConfigKind::Role),
+    ("role_mod",                    ConfigKind::Role),
+    ("role_trial",                  ConfigKind::Role),
+    ("role_trusted",                ConfigKind::Role),
+    ("role_member",                 ConfigKind::Role),
+    ("role_muted",                  ConfigKind::Role),
+    ("role_auto_mute",              ConfigKind::Role),
+];
+
+// keys that hold any number of values
+const LIST_KEYS: [&str; 10] = [
+    "channel_protected_log",
+    "category_lockdown",
+    "category_protected_slowmode",
+    "category_protected_purge",
+    "role_admin",
+    "role_headmod",
+    "role_mod",
+    "role_trial",
+    "role_trusted",
+    "role_member",
+];
+
+// Commands are configured by their first trigger, e.g. `disabled_tweet`,
+// `channels_8ball` with the channels and categories the command is allowed
+// in, or `permission_warn` with the level that may use the command.
+pub const DISABLED_PREFIX: &str = "disabled_";
+pub const CHANNELS_PREFIX: &str = "channels_";
+pub const PERMISSION_PREFIX: &str = "permission_";
+
+// the trigger of the command a key configures, if it configures one
+pub fn configured_command(key: &str) -> Option<&str> {
+    [DISABLED_PREFIX, CHANNELS_PREFIX, PERMISSION_PREFIX].iter()
+        .find_map(|prefix| key.strip_prefix(prefix))
+        .filter(|trigger| !trigger.is_empty())
+}
+
+// all keys known to the bot, in the order they are listed in
+pub fn known_keys() -> Vec<&'static str> {
+    let mut keys = KEYS.iter().map(|(key, _)| *key).collect::<Vec<&str>>();
+    keys.push(retention::ARCHIVE_KEY);
+    keys
+}
+
+// The kind of a key, `None` for unknown keys and keys that are managed by
+// the bot itself, like `token` or `uptime`.
+pub fn kind(key: &str) -> Option<ConfigKind> {
+    if let Some((_, kind)) = KEYS.iter().find(|(known, _)| *known == key) {
+        return Some(*kind);
+    }
+    match key {
+        retention::ARCHIVE_KEY => Some(ConfigKind::Boolean),
+        key if key.starts_with(retention::RETENTION_PREFIX) => Some(ConfigKind::Retention),
+        key if key.starts_with("role_") => Some(ConfigKind::Role),
+        key if key.starts_with(DISABLED_PREFIX) => Some(ConfigKind::Boolean),
+        key if key.starts_with(CHANNELS_PREFIX) => Some(ConfigKind::Location),
+        key if key.starts_with(PERMISSION_PREFIX) => Some(ConfigKind::Level),
+        _ => None,
+    }
+}
+
+pub fn is_list(key: &str) -> bool {
+    LIST_KEYS.contains(&key) || key.starts_with(CHANNELS_PREFIX)
+}
+
+// Checks the format of a value. Whether channels and roles exist in the
+// guild can only be checked by the caller.
+pub fn check_format(kind: ConfigKind, value: &str) -> std::result::Result<(), Text> {
+    let valid = match kind {
+        ConfigKind::Text => !value.is_empty(),
+        ConfigKind::User => UserId::try_from(DBEntry::from(value)).is_ok(),
+        ConfigKind::Colour => Colour::try_from(DBEntry::from(value)).is_ok(),
+        ConfigKind::Channel | ConfigKind::Category | ConfigKind::Location | ConfigKind::Role => value.parse::<u64>().is_ok_and(|id| id > 0),
+        ConfigKind::Retention => value == "never" || parse_time(value).is_ok_and(|seconds| seconds > 0),
+        ConfigKind::Boolean => value == "true" || value == "false",
+        ConfigKind::Level => PermissionLevel::from_str(value).is_ok(),
+        ConfigKind::Locale => Locale::from_str(value).is_ok(),
+    };
+    match valid {
+        true  => Ok(()),
+        false => Err(text!("config.invalid-value", value = value, kind = kind.to_string().to_lowercase())),
+    }
+}
+
+impl fmt::Display for ConfigKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigKind::Text      => write!(f, "Text"),
+            ConfigKind::User      => write!(f, "User ID"),
+            ConfigKind::Colour    => write!(f, "Hex colour"),
+            ConfigKind::Channel   => write!(f, "Channel ID"),
+            ConfigKind::Category  => write!(f, "Category ID"),
+            ConfigKind::Role      => write!(f, "Role ID"),
+            ConfigKind::Retention => write!(f, "Duration"),
+            ConfigKind::Boolean   => write!(f, "Boolean"),
+            ConfigKind::Location  => write!(f, "Channel or category ID"),
+            ConfigKind::Level     => write!(f, "Permission level"),
+            ConfigKind::Locale    => write!(f, "Locale"),
+        }
+    }
+}
+
+// the values that are set for a key in exactly this scope, without fallback
+pub async fn values(scope: Scope, key: &str) -> DBResult<Vec<String>> {
+    get_storage(&DB::Config)
+        .query_rows(&Query::new().key(key).scope(scope)).await?
+        .into_iter()
+        .map(|row| DBEntry::from_row(row).map(|entry| entry.value))
+        .collect::<Result<Vec<String>>>()
+        .map_err(DBError::Corrupt)
+}
+
+// Replaces the values of a key and records the change in the history, no
+// values unset the key. The cached configuration is dropped afterwards.
+pub async fn change(scope: Scope, key: &str, new_values: Vec<String>, staff_id: &str) -> DBResult<ConfigChange> {
+
+    let old_values = values(scope, key).await?;
+    let change = ConfigChange::new(old_values.join(" "), new_values.join(" "), staff_id.to_string());
+    let rows = new_values.iter()
+        .map(|value| DBEntry::from(value).into_values())
+        .collect();
+
+    // the values are never changed without their history
+    get_storage(&DB::Config)
+        .change_values(scope, key, rows, LogRow::new(get_storage(&DB::ConfigHistory), change.clone())).await?;
+    ConfigCache::get_instance().invalidate(scope);
+    Ok(change)
+}
+
+// Validates the configuration of every guild that has its own keys, or the
+// global configuration if no guild has any. Returns the problems found.
+pub async fn validate() -> DBResult<Vec<ConfigReport>> {
+
+    let mut scopes = get_storage(&DB::Config).query_rows(&Query::new()).await?
+        .into_iter()
+        .map(|row| Scope::from_id(&row.guild_id))
+        .filter(|scope| *scope != Scope::Global)
+        .collect::<Vec<Scope>>();
+    scopes.sort_by_key(|scope| scope.id());
+    scopes.dedup();
+    if scopes.is_empty() {
+        scopes.push(Scope::Global);
+    }
+
+    let mut reports = Vec::new();
+    for scope in scopes {
+        match BotConfig::load(scope).await {
+            Ok(_) => {},
+            Err(ConfigError::Invalid(report)) => reports.push(report),
+            Err(ConfigError::Database(err)) => return Err(err),
+        }
+    }
+    Ok(reports)
+}
@@ -0,0 +1,151 @@
+use rusqlite::{params, Connection};
+use once_cell::sync::Lazy;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::databases::database::DB;
+use crate::databases::schema::*;
+use crate::databases::migrations::{migrate, claim_rows};
+use crate::databases::search::prepare_index;
+use crate::databases::error::*;
+use crate::utility::*;
+
+
+pub const DEFAULT_PATH: &str = "src/databases/kalopsian.db";
+pub const PATH_VARIABLE: &str = "KALOPSIAN_DATABASE";
+pub const PATH_FLAG: &str = "--database";
+
+// Before all tables were moved into a single file, every table was stored
+// in its own file in this directory.
+const LEGACY_DIRECTORY: &str = "src/databases";
+
+// The location of the database file is taken from the `--database <path>`
+// flag, then from the `KALOPSIAN_DATABASE` environment variable.
+pub fn database_path() -> String {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == PATH_FLAG {
+            if let Some(path) = args.next() {
+                return path;
+            }
+        }
+        if let Some(path) = arg.strip_prefix(&format!("{}=", PATH_FLAG)) {
+            return path.to_string();
+        }
+    }
+    std::env::var(PATH_VARIABLE).unwrap_or(DEFAULT_PATH.to_string())
+}
+
+pub fn open_connection(path: &str) -> Connection {
+    let connection = Connection::open(path).expect("Failed to open database");
+    connection.pragma_update(None, "journal_mode", "WAL")
+        .expect("Failed to enable WAL");
+    connection.busy_timeout(Duration::from_secs(5))
+        .expect("Failed to set busy timeout");
+    connection
+}
+
+// All tables share one connection for writing and one for reading.
+// With WAL enabled readers are not blocked by a concurrent writer.
+pub struct Connections {
+    pub reader: Arc<Mutex<Connection>>,
+    pub writer: Arc<Mutex<Connection>>,
+}
+
+pub static CONNECTIONS: Lazy<Connections> = Lazy::new(|| {
+    let path = database_path();
+    Logger::info_long("Opening database", &path);
+    if let Some(directory) = Path::new(&path).parent() {
+        if !directory.as_os_str().is_empty() {
+            std::fs::create_dir_all(directory).expect("Failed to create database directory");
+        }
+    }
+    let writer = open_connection(&path);
+    let reader = open_connection(&path);
+    Connections {
+        reader: Arc::new(Mutex::new(reader)),
+        writer: Arc::new(Mutex::new(writer)),
+    }
+});
+
+// One connection to a database in memory for reading and writing, so tests
+// can use SQLite without touching the database file.
+#[cfg(test)]
+pub fn in_memory() -> Connections {
+    let connection = Arc::new(Mutex::new(Connection::open_in_memory()
+        .expect("Failed to open database in memory")));
+    Connections {
+        reader: connection.clone(),
+        writer: connection,
+    }
+}
+
+// Migrates the table of the given database, merges its legacy file
+// into it, if one is still present, and prepares its search index.
+pub fn prepare_table(identifier: &DB, columns: &[Column]) {
+    let mut writer = CONNECTIONS.writer.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let table = identifier.to_string();
+    migrate(&mut writer, &table, columns)
+        .expect("Failed to migrate database");
+    import_legacy_file(&writer, &table, columns)
+        .expect("Failed to import legacy database");
+    prepare_index(&writer, &table)
+        .expect("Failed to prepare search index");
+}
+
+// One-shot importer for the per-table files used by older versions. The
+// legacy file is migrated to the current schema on its own, its rows are
+// copied into the consolidated file and the legacy file is then renamed to
+// `<name>.db.imported`, so that it is never imported twice.
+fn import_legacy_file(connection: &Connection, table: &str, columns: &[Column]) -> DBResult<()> {
+
+    let legacy_path = format!("{}/{}.db", LEGACY_DIRECTORY, table);
+    if !Path::new(&legacy_path).is_file() || same_file(&legacy_path, &database_path()) {
+        return Ok(());
+    }
+
+    // bring the legacy table up to date before copying it
+    {
+        let mut legacy = open_connection(&legacy_path);
+        migrate(&mut legacy, table, columns)?;
+        legacy.pragma_update(None, "journal_mode", "DELETE")?;
+    }
+
+    connection.execute("ATTACH DATABASE ? AS legacy", params![legacy_path])?;
+
+    // ids are kept if possible, since users refer to records by their id
+    let is_empty = connection.query_row(
+        &format!("SELECT COUNT(*) FROM main.{}", table),
+        [],
+        |row| row.get::<_, i64>(0)
+    )? == 0;
+    let names = match is_empty {
+        true  => column_list(columns),
+        false => column_list(columns).replacen("id, ", "", 1),
+    };
+    let copied = connection.execute(&format!(
+        "INSERT INTO main.{table} ({names}) SELECT {names} FROM legacy.{table}"), []);
+
+    connection.execute("DETACH DATABASE legacy", [])?;
+    let copied = copied?;
+
+    // legacy files of tables other than the configuration cannot know the main guild
+    claim_rows(connection, table)?;
+
+    std::fs::rename(&legacy_path, format!("{}.imported", legacy_path))
+        .map_err(|err| DBError::Io(err.to_string()))?;
+    Logger::info_long(
+        &format!("Imported {} rows into {}", copied, table),
+        &legacy_path);
+    Ok(())
+}
+
+fn same_file(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
@@ -1,15 +1,25 @@
 
-use serenity::all::{ChannelId, UserId};
+use serenity::all::{ChannelId, RoleId, UserId};
 use serenity::model::colour::Colour;
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection};
+use rusqlite::types::Value;
 use strum_macros::EnumIter;
 
 use std::str::FromStr;
 use std::sync::Arc;
-use std::collections::HashSet;
 use std::fmt;
-use std::sync::RwLock;
-
+use std::sync::Mutex;
+
+use crate::databases::schema::*;
+use crate::databases::connection::{CONNECTIONS, prepare_table};
+#[cfg(test)]
+use crate::databases::connection::Connections;
+#[cfg(test)]
+use crate::databases::{migrations::migrate, search::prepare_index};
+use crate::databases::query::*;
+use crate::databases::scope::Scope;
+use crate::databases::storage::{Storage, LogRow};
+use crate::databases::error::*;
 use crate::utility::*;
 
 
@@ -26,8 +36,12 @@ pub enum DB {
     TicketReviews,
     Notes,
     Reminders,
-    Tweets,
-    Deadchat
+    Archive,
+    Removals,
+    ConfigHistory,
+    Cooldowns,
+    CommandUses,
+    Locales
 }
 
 impl fmt::Display for DB {
@@ -44,8 +58,12 @@ impl fmt::Display for DB {
             DB::TicketReviews => write!(f, "ticket_reviews"),
             DB::Notes => write!(f, "notes"),
             DB::Reminders => write!(f, "reminders"),
-            DB::Tweets => write!(f, "tweets"),
-            DB::Deadchat => write!(f, "deadchat"),
+            DB::Archive => write!(f, "archive"),
+            DB::Removals => write!(f, "removals"),
+            DB::ConfigHistory => write!(f, "config_history"),
+            DB::Cooldowns => write!(f, "cooldowns"),
+            DB::CommandUses => write!(f, "command_uses"),
+            DB::Locales => write!(f, "locales"),
         }
     }
 }
@@ -54,6 +72,7 @@ impl fmt::Display for DB {
 pub struct DBEntry {
     pub id: i64,
     pub key: String,
+    pub guild_id: String,
     pub value: String,
     pub timestamp: i64,
 }
@@ -70,159 +89,385 @@ impl fmt::Display for DBEntry {
     }
 }
 
-impl From<DBEntry> for Colour {
-    fn from(entry: DBEntry) -> Colour {
+impl From<&str> for DBEntry {
+    fn from(value: &str) -> DBEntry {
+        DBEntry {
+            id: 0,
+            key: "".to_string(),
+            guild_id: "".to_string(),
+            value: value.to_string(),
+            timestamp: 0,
+        }
+    }
+}
+
+impl From<&String> for DBEntry {
+    fn from(value: &String) -> DBEntry {
+        DBEntry::from(value.as_str())
+    }
+}
+
+impl From<String> for DBEntry {
+    fn from(value: String) -> DBEntry {
+        DBEntry::from(value.as_str())
+    }
+}
+
+impl Record for DBEntry {
+
+    fn columns() -> Vec<Column> {
+        vec![Column::new("value", ColumnType::Text)]
+    }
+
+    fn from_row(row: DBRow) -> Result<Self> {
+        Ok(DBEntry {
+            id: row.id,
+            key: row.key,
+            guild_id: row.guild_id,
+            value: String::from_value(row.values.into_iter().next())?,
+            timestamp: row.timestamp,
+        })
+    }
+
+    fn into_values(self) -> Vec<Value> {
+        vec![self.value.into_value()]
+    }
+}
+
+impl TryFrom<DBEntry> for Colour {
+    type Error = DBError;
+    fn try_from(entry: DBEntry) -> DBResult<Colour> {
         let value = entry.value.trim_start_matches("#");
-        u64::from_str_radix(value, 16).unwrap().into()
+        u64::from_str_radix(value, 16)
+            .map(|colour| colour.into())
+            .map_err(|_| DBError::Corrupt(format!("'{}' is not a colour", entry.value)))
+    }
+}
+
+impl TryFrom<DBEntry> for ChannelId {
+    type Error = DBError;
+    fn try_from(entry: DBEntry) -> DBResult<ChannelId> {
+        ChannelId::from_str(&entry.value)
+            .map_err(|_| DBError::Corrupt(format!("'{}' is not a channel id", entry.value)))
+    }
+}
+
+impl TryFrom<DBEntry> for UserId {
+    type Error = DBError;
+    fn try_from(entry: DBEntry) -> DBResult<UserId> {
+        UserId::from_str(&entry.value)
+            .map_err(|_| DBError::Corrupt(format!("'{}' is not a user id", entry.value)))
     }
 }
 
-impl From<DBEntry> for ChannelId {
-    fn from(entry: DBEntry) -> ChannelId {
-        ChannelId::from_str(&entry.value).unwrap()
+impl TryFrom<DBEntry> for Locale {
+    type Error = DBError;
+    fn try_from(entry: DBEntry) -> DBResult<Locale> {
+        Locale::from_str(&entry.value)
+            .map_err(DBError::Corrupt)
     }
 }
 
-impl From<DBEntry> for UserId {
-    fn from(entry: DBEntry) -> UserId {
-        UserId::from_str(&entry.value).unwrap()
+impl TryFrom<DBEntry> for RoleId {
+    type Error = DBError;
+    fn try_from(entry: DBEntry) -> DBResult<RoleId> {
+        RoleId::from_str(&entry.value)
+            .map_err(|_| DBError::Corrupt(format!("'{}' is not a role id", entry.value)))
     }
 }
 
+// All databases are tables in the same file and share its connections,
+// every rusqlite call runs on tokio's blocking pool instead of the event tasks.
 pub struct Database {
-    connection: RwLock<Connection>,
+    reader: Arc<Mutex<Connection>>,
+    writer: Arc<Mutex<Connection>>,
     pub identifier: DB,
+    columns: Arc<Vec<Column>>,
+}
+
+// inserts a row with a fresh id into the table
+fn insert_statement(table: &DB, columns: &[Column]) -> String {
+    let names = columns.iter()
+        .map(|column| column.name)
+        .collect::<Vec<_>>();
+    let placeholders = vec!["?"; names.len() + 3].join(", ");
+    let names = match names.is_empty() {
+        true  => String::new(),
+        false => format!(", {}", names.join(", ")),
+    };
+    format!("INSERT INTO {} (key, guild_id, timestamp{}) VALUES ({})",
+        table,
+        names,
+        placeholders)
+}
+
+fn read_row(row: &rusqlite::Row, column_count: usize) -> rusqlite::Result<DBRow> {
+    let values = (0..column_count)
+        .map(|i| row.get::<_, Value>(i + 4))
+        .collect::<rusqlite::Result<Vec<Value>>>()?;
+    Ok(DBRow {
+        id: row.get(0)?,
+        key: row.get(1)?,
+        guild_id: row.get(2)?,
+        timestamp: row.get(3)?,
+        values,
+    })
 }
 
 impl Database {
 
-    pub fn new(identifier: DB) -> Self {
-        let path = format!("src/databases/{}.db", identifier.to_string());
-        let connection = Connection::open(path).expect("Failed to open database");
-        connection.execute(&format!(
-            "CREATE TABLE IF NOT EXISTS {} (
-                id        INTEGER PRIMARY KEY,
-                key       TEXT    NOT NULL,
-                value     TEXT    NOT NULL,
-                timestamp INTEGER
-            )", identifier.to_string()),
-            [],
-        ).expect("Failed to create table");
-        Database { connection: RwLock::new(connection), identifier: identifier }
-    }
-
-    pub async fn get_keys(&self) -> Vec<String> {
-        let connection = self.connection.read().expect("Failed to get connection");
-        let mut keys = HashSet::new();
-        let mut statement = connection
-            .prepare(&format!("SELECT key FROM {}", self.identifier.to_string()))
-            .expect("Failed to prepare statement");
-        let rows = statement.query_map(
-            [],
-            |row| row.get(0)
-        ).expect("Failed to query map");
-        for key in rows {
-            keys.insert(key.unwrap());
+    pub fn new(identifier: DB, columns: Vec<Column>) -> Self {
+        prepare_table(&identifier, &columns);
+        Database {
+            reader: CONNECTIONS.reader.clone(),
+            writer: CONNECTIONS.writer.clone(),
+            identifier,
+            columns: Arc::new(columns),
+        }
+    }
+
+    // A table on other connections than the ones of the database file,
+    // legacy files are not imported into it.
+    #[cfg(test)]
+    pub fn with_connections(identifier: DB, columns: Vec<Column>, connections: &Connections) -> Self {
+        {
+            let mut writer = connections.writer.lock().unwrap();
+            let table = identifier.to_string();
+            migrate(&mut writer, &table, &columns).expect("Failed to migrate database");
+            prepare_index(&writer, &table).expect("Failed to prepare search index");
+        }
+        Database {
+            reader: connections.reader.clone(),
+            writer: connections.writer.clone(),
+            identifier,
+            columns: Arc::new(columns),
         }
-        keys.into_iter().collect()
     }
 
-    pub async fn query(&self, key: &str, query_string: &str) -> Result<Vec<DBEntry>> {
-        let connection = self.connection.read().expect("Failed to get connection");
-        let mut statement = connection.prepare(&format!(
-            "SELECT id, key, value, timestamp FROM {} WHERE key = ? {}",
-            self.identifier.to_string(),
-            query_string
-        )).expect("Failed to prepare statement");
-        let entry_iter = statement.query_map([key], |entry| {
-            Ok(DBEntry {
-                id: entry.get(0)?,
-                key: entry.get(1)?,
-                value: entry.get(2)?,
-                timestamp: entry.get(3)?,
-            })
-        }).expect("Failed to query map");
-        Ok(entry_iter.map(|entry| entry.unwrap()).collect::<Vec<DBEntry>>())
+    // runs the closure with the given connection on the blocking pool
+    async fn run<R, F>(connection: &Arc<Mutex<Connection>>, f: F) -> DBResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&Connection) -> DBResult<R> + Send + 'static,
+    {
+        let connection = connection.clone();
+        tokio::task::spawn_blocking(move || {
+            // a panic while holding the lock leaves the connection usable
+            let connection = connection.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&connection)
+        }).await?
     }
 
-    pub async fn get(&self, key: &str) -> Result<DBEntry> {
-        self.query(key, "ORDER BY timestamp ASC LIMIT 1").await?
-            .pop()
-            .ok_or(format!("Failed to get value for '{}'", key))
+    async fn read<R, F>(&self, f: F) -> DBResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&Connection) -> DBResult<R> + Send + 'static,
+    {
+        Database::run(&self.reader, f).await
     }
 
-    pub async fn get_all(&self, key: &str) -> Result<Vec<DBEntry>> {
-        self.query(key, "").await
+    async fn write<R, F>(&self, f: F) -> DBResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&Connection) -> DBResult<R> + Send + 'static,
+    {
+        Database::run(&self.writer, f).await
     }
 
-    pub async fn get_last(&self, key: &str, limit: u8) -> Result<Vec<DBEntry>> {
-        self.query(key, &format!("ORDER BY timestamp DESC LIMIT {}", limit)).await
+    fn insert_statement(&self) -> String {
+        insert_statement(&self.identifier, &self.columns)
     }
 
-    pub async fn get_multiple(&self, keys: impl ToList<&str>) -> Result<Vec<DBEntry>> {
-        let mut values = Vec::new();
-        for key in keys.to_list() {
-            let value = self.get(key).await?;
-            values.push(value);
-        }
-        Ok(values)
+    fn insert_parameters(scope: Scope, key: &str, values: Vec<Value>) -> Vec<Value> {
+        let mut parameters = vec![
+            Value::Text(key.to_string()),
+            Value::Text(scope.id()),
+            Value::Integer(chrono::Utc::now().timestamp())];
+        parameters.extend(values);
+        parameters
+    }
+}
+
+impl Storage for Database {
+
+    fn get_keys(&self, scope: Scope) -> BoxedFuture<'_, DBResult<Vec<String>>> {
+        let statement = format!("SELECT DISTINCT key FROM {} WHERE guild_id = ?", self.identifier);
+        Box::pin(self.read(move |connection| {
+            let mut statement = connection.prepare(&statement)?;
+            let keys = statement.query_map([scope.id()], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            Ok(keys)
+        }))
+    }
+
+    fn query_rows<'a>(&'a self, query: &'a Query) -> BoxedFuture<'a, DBResult<Vec<DBRow>>> {
+        Box::pin(async move {
+            let (clause, parameters) = query.build(&self.identifier.to_string(), &self.columns)?;
+            let statement = format!(
+                "SELECT {} FROM {}{}",
+                column_list(&self.columns),
+                self.identifier,
+                clause
+            );
+            let column_count = self.columns.len();
+            self.read(move |connection| {
+                let mut statement = connection.prepare(&statement)?;
+                let rows = statement.query_map(params_from_iter(parameters), |row| read_row(row, column_count))?
+                    .collect::<rusqlite::Result<Vec<DBRow>>>()?;
+                Ok(rows)
+            }).await
+        })
     }
 
-    pub async fn set(&self, key: &str, value: impl ToList<&str>) {
+    fn set_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>> {
+        let delete = format!("DELETE FROM {} WHERE key = ? AND guild_id = ?", self.identifier);
+        let insert = self.insert_statement();
+        let parameters = Database::insert_parameters(scope, key, values);
+        let key = key.to_string();
+        Box::pin(self.write(move |connection| {
+
+            // replace old values in a single transaction
+            let transaction = connection.unchecked_transaction()?;
+            transaction.execute(&delete, params![key, scope.id()])?;
+            transaction.execute(&insert, params_from_iter(parameters))?;
+            transaction.commit()?;
+            Ok(())
+        }))
+    }
 
-        let connection = self.connection.write().expect("Failed to get connection");
-        for value in value.to_list() {
+    fn append_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>> {
+        let insert = self.insert_statement();
+        let parameters = Database::insert_parameters(scope, key, values);
+        Box::pin(self.write(move |connection| {
+            connection.execute(&insert, params_from_iter(parameters))?;
+            Ok(())
+        }))
+    }
 
-            // Delete old values
-            connection.execute(
-                &format!("DELETE FROM {} WHERE key = ?", self.identifier.to_string()),
-                params![key],
-            ).expect("Failed to delete value");
+    fn append_limited<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>, after: i64, limit: usize) -> BoxedFuture<'a, DBResult<bool>> {
+        let count = format!("SELECT COUNT(*) FROM {} WHERE key = ? AND guild_id = ? AND timestamp > ?", self.identifier);
+        let insert = self.insert_statement();
+        let parameters = Database::insert_parameters(scope, key, values);
+        let key = key.to_string();
+        Box::pin(self.write(move |connection| {
+
+            // the rows are counted on the writer, so no append can come in between
+            let transaction = connection.unchecked_transaction()?;
+            let count = transaction.query_row(&count, params![key, scope.id(), after], |row| row.get::<_, i64>(0))?;
+            if count as usize >= limit {
+                return Ok(false);
+            }
+            transaction.execute(&insert, params_from_iter(parameters))?;
+            transaction.commit()?;
+            Ok(true)
+        }))
+    }
 
-            connection.execute(
-                &format!("INSERT INTO {} (key, value, timestamp) VALUES (?, ?, ?)", self.identifier.to_string()),
-                params![key, value, chrono::Utc::now().timestamp()],
-            ).expect("Failed to set value");
+    fn change_values<'a>(&'a self, scope: Scope, key: &'a str, rows: Vec<Vec<Value>>, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>> {
+        let delete = format!("DELETE FROM {} WHERE key = ? AND guild_id = ?", self.identifier);
+        let insert = self.insert_statement();
+        let insert_log = insert_statement(log.storage.identifier(), log.storage.columns());
+        let rows = rows.into_iter()
+            .map(|values| Database::insert_parameters(scope, key, values))
+            .collect::<Vec<Vec<Value>>>();
+        let log = Database::insert_parameters(scope, key, log.values);
+        let key = key.to_string();
+        Box::pin(self.write(move |connection| {
+
+            // all tables share the connection, so the log is part of the transaction
+            let transaction = connection.unchecked_transaction()?;
+            transaction.execute(&delete, params![key, scope.id()])?;
+            for parameters in rows {
+                transaction.execute(&insert, params_from_iter(parameters))?;
+            }
+            transaction.execute(&insert_log, params_from_iter(log))?;
+            transaction.commit()?;
+            Ok(())
+        }))
+    }
 
-        }
+    fn has<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<bool>> {
+        let statement = format!("SELECT EXISTS(SELECT 1 FROM {} WHERE key = ? AND guild_id = ?)", self.identifier);
+        let key = key.to_string();
+        Box::pin(self.read(move |connection| {
+            Ok(connection.query_row(&statement, [key, scope.id()], |row| row.get(0))?)
+        }))
     }
 
-    pub async fn has(&self, key: &str) -> bool {
+    fn delete<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<()>> {
+        let statement = format!("DELETE FROM {} WHERE key = ? AND guild_id = ?", self.identifier);
+        let key = key.to_string();
+        Box::pin(self.write(move |connection| {
+            connection.execute(&statement, params![key, scope.id()])?;
+            Ok(())
+        }))
+    }
 
-        let connection = self.connection.read().expect("Failed to get connection");
-        let mut statement = connection.prepare(&format!(
-            "SELECT id FROM {} WHERE key = ?",
-            self.identifier.to_string()
-        )).expect("Failed to prepare statement");
+    fn delete_by_id(&self, id: i64) -> BoxedFuture<'_, DBResult<()>> {
+        let statement = format!("DELETE FROM {} WHERE id = ?", self.identifier);
+        Box::pin(self.write(move |connection| {
+            connection.execute(&statement, params![id])?;
+            Ok(())
+        }))
+    }
 
-        let entry_iter = statement.query_map([key], |_| Ok(())).expect("Failed to query map");
-        entry_iter.count() > 0
+    fn delete_logged<'a>(&'a self, id: i64, scope: Scope, key: &'a str, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>> {
+        let delete = format!("DELETE FROM {} WHERE id = ?", self.identifier);
+        let insert_log = insert_statement(log.storage.identifier(), log.storage.columns());
+        let log = Database::insert_parameters(scope, key, log.values);
+        Box::pin(self.write(move |connection| {
+            let transaction = connection.unchecked_transaction()?;
+            transaction.execute(&insert_log, params_from_iter(log))?;
+            transaction.execute(&delete, params![id])?;
+            transaction.commit()?;
+            Ok(())
+        }))
     }
 
-    pub async fn append(&self, key: &str, value: &str) {
-        let connection = self.connection.write().expect("Failed to get connection");
-        connection.execute(
-            &format!("INSERT INTO {} (key, value, timestamp) VALUES (?, ?, ?)", self.identifier.to_string()),
-            params![key, value, chrono::Utc::now().timestamp()],
-        ).expect("Failed to append value");
+    fn identifier(&self) -> &DB {
+        &self.identifier
     }
 
-    pub async fn delete(&self, key: &str) {
-        let connection = self.connection.write().expect("Failed to get connection");
-        connection.execute(
-            &format!("DELETE FROM {} WHERE key = ?", self.identifier.to_string()),
-            params![key],
-        ).expect("Failed to delete value");
+    fn columns(&self) -> &[Column] {
+        &self.columns
     }
 
-    pub async fn delete_by_id(&self, id: i64) {
-        let connection = self.connection.write().expect("Failed to get connection");
-        connection.execute(
-            &format!("DELETE FROM {} WHERE id = ?", self.identifier.to_string()),
-            params![id],
-        ).expect("Failed to delete value");
+    fn restore_rows(&self, scope: Option<Scope>, rows: Vec<DBRow>) -> BoxedFuture<'_, DBResult<()>> {
+        let (delete, scope_parameters) = match scope {
+            Some(scope) => (format!("DELETE FROM {} WHERE guild_id = ?", self.identifier), vec![scope.id()]),
+            None => (format!("DELETE FROM {}", self.identifier), Vec::new()),
+        };
+        let exists = format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", self.identifier);
+        let insert = format!("INSERT INTO {} ({}) VALUES ({})",
+            self.identifier,
+            column_list(&self.columns),
+            vec!["?"; self.columns.len() + 4].join(", "));
+        Box::pin(self.write(move |connection| {
+
+            // either the whole scope is restored or nothing changes
+            let transaction = connection.unchecked_transaction()?;
+            transaction.execute(&delete, params_from_iter(scope_parameters))?;
+            {
+                let mut exists = transaction.prepare(&exists)?;
+                let mut statement = transaction.prepare(&insert)?;
+                for row in rows {
+                    // SQLite assigns a fresh id if the id is already taken
+                    let id = match exists.query_row([row.id], |result| result.get::<_, bool>(0))? {
+                        true  => Value::Null,
+                        false => Value::Integer(row.id),
+                    };
+                    let mut parameters = vec![
+                        id,
+                        Value::Text(row.key),
+                        Value::Text(row.guild_id),
+                        Value::Integer(row.timestamp)];
+                    parameters.extend(row.values);
+                    statement.execute(params_from_iter(parameters))?;
+                }
+            }
+            transaction.commit()?;
+            Ok(())
+        }))
     }
 }
-
-unsafe impl Send for Database {}
-unsafe impl Sync for Database {}
@@ -0,0 +1,52 @@
+use rusqlite::ErrorCode;
+
+use std::fmt;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DBError {
+    NotFound(String),
+    Corrupt(String),
+    Io(String),
+    // a query the table can not answer, e.g. a search of a column without an index
+    InvalidQuery(String),
+    Locked,
+}
+
+pub type DBResult<T> = std::result::Result<T, DBError>;
+
+impl fmt::Display for DBError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DBError::NotFound(key)   => write!(f, "Key '{}' not found", key),
+            DBError::Corrupt(reason) => write!(f, "Corrupt database value: {}", reason),
+            DBError::Io(reason)      => write!(f, "Database error: {}", reason),
+            DBError::InvalidQuery(reason) => write!(f, "Invalid query: {}", reason),
+            DBError::Locked          => write!(f, "Database is locked, try again later"),
+        }
+    }
+}
+
+impl std::error::Error for DBError {}
+
+impl From<rusqlite::Error> for DBError {
+    fn from(err: rusqlite::Error) -> DBError {
+        match err {
+            rusqlite::Error::SqliteFailure(ref failure, _) => match failure.code {
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => DBError::Locked,
+                _ => DBError::Io(err.to_string()),
+            },
+            rusqlite::Error::QueryReturnedNoRows => DBError::NotFound(String::new()),
+            rusqlite::Error::FromSqlConversionFailure(..)
+            | rusqlite::Error::InvalidColumnType(..)
+            | rusqlite::Error::IntegralValueOutOfRange(..) => DBError::Corrupt(err.to_string()),
+            _ => DBError::Io(err.to_string()),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for DBError {
+    fn from(err: tokio::task::JoinError) -> DBError {
+        DBError::Io(err.to_string())
+    }
+}
@@ -0,0 +1,351 @@
+use rusqlite::types::Value;
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use crate::databases::database::DB;
+use crate::databases::schema::*;
+use crate::databases::query::*;
+use crate::databases::scope::Scope;
+use crate::databases::error::*;
+use crate::databases::storage::{Storage, LogRow};
+use crate::utility::*;
+
+
+struct Table {
+    rows: Vec<DBRow>,
+    next_id: i64,
+}
+
+// A storage backend that keeps all rows in memory and forgets them once
+// the process exits. Ids and timestamps are assigned like in SQLite.
+pub struct MemoryDatabase {
+    identifier: DB,
+    columns: Vec<Column>,
+    table: Mutex<Table>,
+    changing: tokio::sync::Mutex<()>,
+}
+
+impl MemoryDatabase {
+
+    pub fn new(identifier: DB, columns: Vec<Column>) -> Self {
+        MemoryDatabase {
+            identifier,
+            columns,
+            table: Mutex::new(Table { rows: Vec::new(), next_id: 1 }),
+            changing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn with_table<R>(&self, f: impl FnOnce(&mut Table) -> R) -> R {
+        let mut table = self.table.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut table)
+    }
+
+    fn insert(table: &mut Table, scope: Scope, key: &str, values: Vec<Value>) {
+        table.rows.push(DBRow {
+            id: table.next_id,
+            key: key.to_string(),
+            guild_id: scope.id(),
+            timestamp: chrono::Utc::now().timestamp(),
+            values,
+        });
+        table.next_id += 1;
+    }
+}
+
+impl Storage for MemoryDatabase {
+
+    fn get_keys(&self, scope: Scope) -> BoxedFuture<'_, DBResult<Vec<String>>> {
+        Box::pin(async move {
+            Ok(self.with_table(|table| table.rows.iter()
+                .filter(|row| row.guild_id == scope.id())
+                .map(|row| row.key.clone())
+                .collect::<HashSet<String>>()
+                .into_iter()
+                .collect()))
+        })
+    }
+
+    fn query_rows<'a>(&'a self, query: &'a Query) -> BoxedFuture<'a, DBResult<Vec<DBRow>>> {
+        Box::pin(async move {
+            let rows = self.with_table(|table| table.rows.clone());
+            query.apply(&self.identifier.to_string(), rows, &self.columns)
+        })
+    }
+
+    fn set_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>> {
+        Box::pin(async move {
+            self.with_table(|table| {
+                table.rows.retain(|row| row.key != key || row.guild_id != scope.id());
+                MemoryDatabase::insert(table, scope, key, values);
+            });
+            Ok(())
+        })
+    }
+
+    fn append_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>> {
+        Box::pin(async move {
+            self.with_table(|table| MemoryDatabase::insert(table, scope, key, values));
+            Ok(())
+        })
+    }
+
+    fn append_limited<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>, after: i64, limit: usize) -> BoxedFuture<'a, DBResult<bool>> {
+        Box::pin(async move {
+            Ok(self.with_table(|table| {
+                let count = table.rows.iter()
+                    .filter(|row| row.key == key && row.guild_id == scope.id() && row.timestamp > after)
+                    .count();
+                if count >= limit {
+                    return false;
+                }
+                MemoryDatabase::insert(table, scope, key, values);
+                true
+            }))
+        })
+    }
+
+    // Writing to memory can not fail, so the rows are always replaced once
+    // the log was appended. Changes wait for each other, so the last log
+    // belongs to the current rows.
+    fn change_values<'a>(&'a self, scope: Scope, key: &'a str, rows: Vec<Vec<Value>>, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>> {
+        Box::pin(async move {
+            let _changing = self.changing.lock().await;
+            log.storage.append_values(scope, key, log.values).await?;
+            self.with_table(|table| {
+                table.rows.retain(|row| row.key != key || row.guild_id != scope.id());
+                for values in rows {
+                    MemoryDatabase::insert(table, scope, key, values);
+                }
+            });
+            Ok(())
+        })
+    }
+
+    fn has<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<bool>> {
+        Box::pin(async move {
+            Ok(self.with_table(|table| table.rows.iter()
+                .any(|row| row.key == key && row.guild_id == scope.id())))
+        })
+    }
+
+    fn delete<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<()>> {
+        Box::pin(async move {
+            self.with_table(|table| table.rows.retain(|row| row.key != key || row.guild_id != scope.id()));
+            Ok(())
+        })
+    }
+
+    fn delete_by_id(&self, id: i64) -> BoxedFuture<'_, DBResult<()>> {
+        Box::pin(async move {
+            self.with_table(|table| table.rows.retain(|row| row.id != id));
+            Ok(())
+        })
+    }
+
+    // like changes, the row is only deleted once the log was appended
+    fn delete_logged<'a>(&'a self, id: i64, scope: Scope, key: &'a str, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>> {
+        Box::pin(async move {
+            let _changing = self.changing.lock().await;
+            log.storage.append_values(scope, key, log.values).await?;
+            self.with_table(|table| table.rows.retain(|row| row.id != id));
+            Ok(())
+        })
+    }
+
+    fn identifier(&self) -> &DB {
+        &self.identifier
+    }
+
+    fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    fn restore_rows(&self, scope: Option<Scope>, rows: Vec<DBRow>) -> BoxedFuture<'_, DBResult<()>> {
+        Box::pin(async move {
+            self.with_table(|table| {
+                table.rows.retain(|row| scope.is_some_and(|scope| row.guild_id != scope.id()));
+                for mut row in rows {
+                    if table.rows.iter().any(|other| other.id == row.id) {
+                        row.id = table.next_id;
+                    }
+                    table.next_id = table.next_id.max(row.id + 1);
+                    table.rows.push(row);
+                }
+            });
+            Ok(())
+        })
+    }
+}
+
+// The same calls are made against SQLite and the memory backend, which have
+// to behave alike.
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::databases::*;
+    use crate::databases::database::Database;
+    use crate::databases::connection::in_memory;
+    use crate::databases::testing::guild;
+
+    // the warnings and the table their changes are logged to
+    fn backends() -> Vec<(Box<dyn Storage>, &'static dyn Storage)> {
+        let connections = in_memory();
+        let history = Database::with_connections(DB::ConfigHistory, ConfigChange::columns(), &connections);
+        vec![
+            (Box::new(Database::with_connections(DB::Warnings, ModLog::columns(), &connections)),
+             Box::leak(Box::new(history))),
+            (Box::new(MemoryDatabase::new(DB::Warnings, ModLog::columns())),
+             Box::leak(Box::new(MemoryDatabase::new(DB::ConfigHistory, ConfigChange::columns())))),
+        ]
+    }
+
+    fn warning(staff_id: &str, reason: &str) -> Vec<Value> {
+        ModLog::new(staff_id.to_string(), reason.to_string()).into_values()
+    }
+
+    fn row(id: i64, key: &str, scope: Scope, timestamp: i64, reason: &str) -> DBRow {
+        DBRow { id, key: key.to_string(), guild_id: scope.id(), timestamp, values: warning("1", reason) }
+    }
+
+    async fn reasons(storage: &dyn Storage, query: Query) -> Vec<String> {
+        storage.query_rows(&query).await.unwrap()
+            .into_iter()
+            .map(|row| ModLog::from_row(row).unwrap().reason)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn set_replaces_and_append_adds() {
+        for (storage, _) in backends() {
+            let scope = guild();
+            storage.set_values(scope, "10", warning("1", "first")).await.unwrap();
+            storage.set_values(scope, "10", warning("1", "second")).await.unwrap();
+            storage.append_values(scope, "10", warning("1", "third")).await.unwrap();
+            storage.append_values(scope, "20", warning("1", "other")).await.unwrap();
+
+            let query = Query::new().key("10").order(Order::Ascending);
+            assert_eq!(reasons(storage.as_ref(), query).await, vec!["second", "third"]);
+            let mut keys = storage.get_keys(scope).await.unwrap();
+            keys.sort();
+            assert_eq!(keys, vec!["10", "20"]);
+            assert!(storage.has(scope, "20").await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn limited_appends_stop_at_the_limit() {
+        for (storage, _) in backends() {
+            let scope = guild();
+            storage.restore_rows(Some(scope), vec![row(1, "10", scope, 100, "expired")]).await.unwrap();
+
+            let appends = (0..4).map(|_| storage.append_limited(scope, "10", warning("1", "new"), 100, 2));
+            let appended = futures::future::join_all(appends).await;
+            assert_eq!(appended.into_iter().filter(|appended| *appended.as_ref().unwrap()).count(), 2);
+            assert!(storage.append_limited(scope, "20", warning("1", "other"), 100, 2).await.unwrap());
+            assert_eq!(reasons(storage.as_ref(), Query::new().key("10")).await.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn rows_are_removed_by_key_and_id() {
+        for (storage, _) in backends() {
+            let scope = guild();
+            storage.append_values(scope, "10", warning("1", "first")).await.unwrap();
+            storage.append_values(scope, "10", warning("1", "second")).await.unwrap();
+            storage.append_values(scope, "20", warning("1", "other")).await.unwrap();
+
+            let first = storage.query_rows(&Query::new().key("10").order(Order::Ascending)).await.unwrap()[0].id;
+            storage.delete_by_id(first).await.unwrap();
+            assert_eq!(reasons(storage.as_ref(), Query::new().key("10")).await, vec!["second"]);
+
+            storage.delete(scope, "20").await.unwrap();
+            assert!(!storage.has(scope, "20").await.unwrap());
+            assert!(storage.has(scope, "10").await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn queries_filter_order_and_limit() {
+        for (storage, _) in backends() {
+            let scope = guild();
+            storage.restore_rows(Some(scope), vec![
+                row(1, "10", scope, 100, "spam links"),
+                row(2, "10", scope, 300, "flooding"),
+                row(3, "20", scope, 200, "more spam"),
+            ]).await.unwrap();
+            storage.append_values(scope, "10", warning("2", "by someone else")).await.unwrap();
+
+            let reasons_of = |query: Query| reasons(storage.as_ref(), query.scope(scope).order(Order::Ascending));
+            assert_eq!(reasons_of(Query::new().after(100).before(300)).await, vec!["more spam"]);
+            assert_eq!(reasons_of(Query::new().id(2)).await, vec!["flooding"]);
+            assert_eq!(reasons_of(Query::new().staff("2")).await, vec!["by someone else"]);
+            assert_eq!(reasons_of(Query::new().matching("reason", "spam")).await, vec!["spam links", "more spam"]);
+            assert_eq!(reasons_of(Query::new().limit(2)).await, vec!["spam links", "more spam"]);
+
+            let query = Query::new().key("10").scope(scope).order(Order::Descending).limit(2);
+            assert_eq!(reasons(storage.as_ref(), query).await, vec!["by someone else", "flooding"]);
+            let result = storage.query_rows(&Query::new().matching("staff_id", "1")).await;
+            assert!(matches!(result, Err(DBError::InvalidQuery(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn guilds_only_see_their_own_rows() {
+        for (storage, _) in backends() {
+            let (first, second) = (guild(), guild());
+            storage.set_values(first, "10", warning("1", "first")).await.unwrap();
+            storage.set_values(second, "10", warning("1", "second")).await.unwrap();
+            storage.set_values(Scope::Global, "10", warning("1", "global")).await.unwrap();
+
+            assert_eq!(reasons(storage.as_ref(), Query::new().scope(first)).await, vec!["first"]);
+            storage.delete(first, "10").await.unwrap();
+            assert!(!storage.has(first, "10").await.unwrap());
+            assert_eq!(reasons(storage.as_ref(), Query::new().scope(second)).await, vec!["second"]);
+            assert_eq!(reasons(storage.as_ref(), Query::new().scope(Scope::Global)).await, vec!["global"]);
+
+            // restoring a guild keeps the ids of other guilds
+            let taken = storage.query_rows(&Query::new().scope(second)).await.unwrap()[0].id;
+            storage.restore_rows(Some(first), vec![row(taken, "10", first, 0, "restored")]).await.unwrap();
+            assert_eq!(storage.query_rows(&Query::new().id(taken)).await.unwrap()[0].guild_id, second.id());
+            assert_eq!(reasons(storage.as_ref(), Query::new().scope(first)).await, vec!["restored"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_are_written_with_their_log() {
+        for (storage, history) in backends() {
+            let scope = guild();
+            storage.append_values(scope, "10", warning("1", "old")).await.unwrap();
+            let change = ConfigChange::new("old".to_string(), "new newer".to_string(), "1".to_string());
+            let rows = vec![warning("1", "new"), warning("1", "newer")];
+            storage.change_values(scope, "10", rows, LogRow::new(history, change)).await.unwrap();
+
+            let query = Query::new().key("10").scope(scope).order(Order::Ascending);
+            assert_eq!(reasons(storage.as_ref(), query.clone()).await, vec!["new", "newer"]);
+            let logged = history.query_rows(&query).await.unwrap();
+            assert_eq!(logged.len(), 1);
+            assert_eq!(ConfigChange::from_row(logged[0].clone()).unwrap().new_value, "new newer");
+        }
+    }
+
+    #[tokio::test]
+    async fn rows_are_deleted_with_their_log() {
+        for (storage, history) in backends() {
+            let scope = guild();
+            storage.append_values(scope, "10", warning("1", "removed")).await.unwrap();
+            storage.append_values(scope, "10", warning("1", "kept")).await.unwrap();
+            let removed = storage.query_rows(&Query::new().key("10").order(Order::Ascending)).await.unwrap()[0].id;
+            let change = ConfigChange::new("removed".to_string(), String::new(), "1".to_string());
+            storage.delete_logged(removed, scope, "20", LogRow::new(history, change)).await.unwrap();
+
+            assert_eq!(reasons(storage.as_ref(), Query::new().key("10")).await, vec!["kept"]);
+            let logged = history.query_rows(&Query::new().key("20").scope(scope)).await.unwrap();
+            assert_eq!(logged.len(), 1);
+            assert_eq!(ConfigChange::from_row(logged[0].clone()).unwrap().old_value, "removed");
+        }
+    }
+}
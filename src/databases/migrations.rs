@@ -0,0 +1,322 @@
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use rusqlite::types::Value;
+
+use crate::databases::schema::*;
+use crate::databases::scope::GLOBAL_KEYS;
+use crate::utility::*;
+
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    apply: fn(&Connection, &str, &[Column]) -> rusqlite::Result<()>,
+}
+
+// Migrations are applied in order to every table whose recorded schema
+// version is lower than the version of the migration. Append new
+// migrations to the end of this list and never alter existing ones.
+pub const MIGRATIONS: [Migration; 2] = [
+    Migration {
+        version: 1,
+        description: "move JSON array values into typed columns",
+        apply: typed_columns,
+    },
+    Migration {
+        version: 2,
+        description: "scope all rows by guild",
+        apply: guild_scope,
+    },
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+fn table_exists(connection: &Connection, table: &str) -> rusqlite::Result<bool> {
+    connection.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [table],
+        |row| row.get::<_, i64>(0)
+    ).map(|count| count > 0)
+}
+
+fn get_version(connection: &Connection, table: &str) -> rusqlite::Result<Option<i64>> {
+    connection.query_row(
+        "SELECT version FROM schema_versions WHERE name = ?",
+        [table],
+        |row| row.get(0)
+    ).optional()
+}
+
+fn set_version(connection: &Connection, table: &str, version: i64) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO schema_versions (name, version, timestamp) VALUES (?, ?, ?)",
+        params![table, version, chrono::Utc::now().timestamp()],
+    ).map(|_| ())
+}
+
+pub fn migrate(connection: &mut Connection, table: &str, columns: &[Column]) -> rusqlite::Result<()> {
+
+    connection.execute(
+        "CREATE TABLE IF NOT EXISTS schema_versions (
+            name      TEXT    PRIMARY KEY,
+            version   INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // tables without a recorded version predate the migration runner
+    let version = match get_version(connection, table)? {
+        Some(version) => version,
+        None => match table_exists(connection, table)? {
+            true  => 0,
+            false => {
+                connection.execute(&create_table_statement(table, columns), [])?;
+                set_version(connection, table, latest_version())?;
+                return Ok(());
+            }
+        }
+    };
+
+    // apply pending migrations, each in its own transaction
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > version) {
+        let transaction = connection.transaction()?;
+        (migration.apply)(&transaction, table, columns)?;
+        set_version(&transaction, table, migration.version)?;
+        transaction.commit()?;
+        Logger::info_long(
+            &format!("Migrated {} to version {}", table, migration.version),
+            migration.description);
+    }
+
+    connection.execute(&create_table_statement(table, columns), [])?;
+    add_missing_columns(connection, table, columns)
+}
+
+// Columns that were added to a record after its table has been created
+// are appended with their default value, so that old rows stay readable.
+fn add_missing_columns(connection: &Connection, table: &str, columns: &[Column]) -> rusqlite::Result<()> {
+
+    let mut statement = connection.prepare(&format!("PRAGMA table_info({})", table))?;
+    let existing = statement
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+
+    for column in columns.iter().filter(|column| !existing.iter().any(|name| name == column.name)) {
+        connection.execute(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table,
+            column.name,
+            column.column_type.definition()), [])?;
+        Logger::info_long(&format!("Added column to {}", table), column.name);
+    }
+    Ok(())
+}
+
+fn legacy_values(table: &str, value: &str, columns: &[Column]) -> Vec<Value> {
+
+    // key/value tables already store their payload as plain text
+    if columns.len() == 1 && columns[0].name == "value" {
+        return vec![Value::Text(value.to_string())];
+    }
+
+    let fields: Vec<String> = serde_json::from_str(value).unwrap_or_else(|_| {
+        Logger::warn_long(&format!("Malformed legacy row in {}", table), value);
+        Vec::new()
+    });
+
+    // missing fields are filled with the default of their column
+    columns.iter()
+        .enumerate()
+        .map(|(i, column)| match fields.get(i) {
+            Some(field) => column.column_type.parse_legacy(field),
+            None        => column.column_type.default_value(),
+        })
+        .collect()
+}
+
+fn typed_columns(connection: &Connection, table: &str, columns: &[Column]) -> rusqlite::Result<()> {
+
+    let legacy = format!("{}_legacy", table);
+    connection.execute(&format!("ALTER TABLE {} RENAME TO {}", table, legacy), [])?;
+    connection.execute(&create_table_statement(table, columns), [])?;
+
+    // read all legacy rows
+    let mut statement = connection.prepare(&format!("SELECT id, key, value, timestamp FROM {}", legacy))?;
+    let rows = statement
+        .query_map([], |row| Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        )))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // insert them into the typed table, keeping their ids
+    let mut names = vec!["id", "key", "timestamp"];
+    names.extend(columns.iter().map(|column| column.name));
+    let placeholders = vec!["?"; names.len()].join(", ");
+    let insert = format!("INSERT INTO {} ({}) VALUES ({})", table, names.join(", "), placeholders);
+    for (id, key, value, timestamp) in rows {
+        let mut values = vec![Value::Integer(id), Value::Text(key), Value::Integer(timestamp.unwrap_or(0))];
+        values.extend(legacy_values(table, &value, columns));
+        connection.execute(&insert, params_from_iter(values))?;
+    }
+
+    drop(statement);
+    connection.execute(&format!("DROP TABLE {}", legacy), [])?;
+    Ok(())
+}
+
+fn has_column(connection: &Connection, table: &str, column: &str) -> rusqlite::Result<bool> {
+    let mut statement = connection.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = statement
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(names.iter().any(|name| name == column))
+}
+
+// the guild that was configured before data was scoped by guild
+fn main_guild(connection: &Connection) -> rusqlite::Result<Option<String>> {
+    if !table_exists(connection, "config")? || !has_column(connection, "config", "value")? {
+        return Ok(None);
+    }
+    connection.query_row(
+        "SELECT value FROM config WHERE key = 'guild_main' ORDER BY id LIMIT 1",
+        [],
+        |row| row.get(0)
+    ).optional()
+}
+
+// Moves all rows without a guild to the main guild. Global configuration
+// keys stay global. Files without a configuration keep their rows global
+// until they are claimed after being imported.
+pub fn claim_rows(connection: &Connection, table: &str) -> rusqlite::Result<()> {
+    let Some(guild) = main_guild(connection)? else {
+        return Ok(());
+    };
+    let mut statement = format!("UPDATE {} SET guild_id = ? WHERE guild_id = ''", table);
+    let mut parameters = vec![Value::Text(guild)];
+    if table == "config" {
+        statement.push_str(&format!(" AND key NOT IN ({})", vec!["?"; GLOBAL_KEYS.len()].join(", ")));
+        parameters.extend(GLOBAL_KEYS.iter().map(|key| Value::Text(key.to_string())));
+    }
+    connection.execute(&statement, params_from_iter(parameters))?;
+    Ok(())
+}
+
+fn guild_scope(connection: &Connection, table: &str, _columns: &[Column]) -> rusqlite::Result<()> {
+
+    // tables created by the first migration already have the column
+    if !has_column(connection, table, "guild_id")? {
+        connection.execute(&format!(
+            "ALTER TABLE {} ADD COLUMN guild_id TEXT NOT NULL DEFAULT ''", table), [])?;
+    }
+    claim_rows(connection, table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning_columns() -> Vec<Column> {
+        vec![
+            Column::new("staff_id", ColumnType::Text),
+            Column::new("reason", ColumnType::Text),
+            Column::new("active", ColumnType::Boolean),
+            Column::new("points", ColumnType::Integer),
+        ]
+    }
+
+    // a file as it was written before the migration runner existed
+    fn legacy_connection() -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        for table in ["config", "warnings"] {
+            connection.execute(&format!(
+                "CREATE TABLE {} (id INTEGER PRIMARY KEY, key TEXT NOT NULL, value TEXT NOT NULL, timestamp INTEGER)",
+                table), []).unwrap();
+        }
+        connection.execute_batch(
+            "INSERT INTO config (id, key, value, timestamp) VALUES
+                (1, 'guild_main', '123', 10),
+                (2, 'channel_modlogs', '456', 20);
+            INSERT INTO warnings (id, key, value, timestamp) VALUES
+                (7, '789', '[\"42\",\"spamming\",\"true\"]', 30),
+                (8, '789', 'not json', NULL);").unwrap();
+        connection
+    }
+
+    fn rows(connection: &Connection, statement: &str) -> Vec<Vec<Value>> {
+        let mut statement = connection.prepare(statement).unwrap();
+        let count = statement.column_count();
+        statement.query_map([], |row| (0..count).map(|i| row.get::<_, Value>(i)).collect())
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    #[test]
+    fn legacy_tables_are_migrated() {
+        let mut connection = legacy_connection();
+        migrate(&mut connection, "config", &[Column::new("value", ColumnType::Text)]).unwrap();
+        migrate(&mut connection, "warnings", &warning_columns()).unwrap();
+
+        // global keys stay global, everything else belongs to the main guild
+        assert_eq!(rows(&connection, "SELECT id, key, guild_id, timestamp, value FROM config ORDER BY id"), vec![
+            vec![Value::Integer(1), text("guild_main"), text(""), Value::Integer(10), text("123")],
+            vec![Value::Integer(2), text("channel_modlogs"), text("123"), Value::Integer(20), text("456")],
+        ]);
+
+        // missing and malformed fields get the default of their column
+        assert_eq!(rows(&connection, "SELECT id, key, guild_id, timestamp, staff_id, reason, active, points FROM warnings ORDER BY id"), vec![
+            vec![Value::Integer(7), text("789"), text("123"), Value::Integer(30), text("42"), text("spamming"), Value::Integer(1), Value::Integer(0)],
+            vec![Value::Integer(8), text("789"), text("123"), Value::Integer(0), text(""), text(""), Value::Integer(0), Value::Integer(0)],
+        ]);
+
+        assert_eq!(rows(&connection, "SELECT name, version FROM schema_versions ORDER BY name"), vec![
+            vec![text("config"), Value::Integer(latest_version())],
+            vec![text("warnings"), Value::Integer(latest_version())],
+        ]);
+        assert!(!table_exists(&connection, "warnings_legacy").unwrap());
+    }
+
+    #[test]
+    fn migrated_tables_are_left_alone() {
+        let mut connection = legacy_connection();
+        migrate(&mut connection, "config", &[Column::new("value", ColumnType::Text)]).unwrap();
+        migrate(&mut connection, "warnings", &warning_columns()).unwrap();
+        let warnings = rows(&connection, "SELECT * FROM warnings ORDER BY id");
+        let versions = rows(&connection, "SELECT * FROM schema_versions ORDER BY name");
+
+        migrate(&mut connection, "warnings", &warning_columns()).unwrap();
+        assert_eq!(rows(&connection, "SELECT * FROM warnings ORDER BY id"), warnings);
+        assert_eq!(rows(&connection, "SELECT * FROM schema_versions ORDER BY name"), versions);
+    }
+
+    #[test]
+    fn new_columns_are_added_to_existing_tables() {
+        let mut connection = legacy_connection();
+        migrate(&mut connection, "warnings", &warning_columns()).unwrap();
+
+        let mut columns = warning_columns();
+        columns.push(Column::new("note", ColumnType::Text));
+        migrate(&mut connection, "warnings", &columns).unwrap();
+        assert_eq!(rows(&connection, "SELECT id, note FROM warnings ORDER BY id"), vec![
+            vec![Value::Integer(7), text("")],
+            vec![Value::Integer(8), text("")],
+        ]);
+    }
+
+    #[test]
+    fn new_tables_start_at_the_latest_version() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection, "warnings", &warning_columns()).unwrap();
+        assert!(has_column(&connection, "warnings", "guild_id").unwrap());
+        assert_eq!(get_version(&connection, "warnings").unwrap(), Some(latest_version()));
+    }
+}
@@ -4,6 +4,47 @@ pub mod database;
 pub use database::DB;
 pub use database::DBEntry;
 
+pub mod error;
+
+pub use error::{DBError, DBResult};
+
+pub mod schema;
+
+pub mod scope;
+
+pub use scope::Scope;
+
+pub use schema::{Record, Column, ColumnType, ColumnValue, DBRow};
+
+pub mod migrations;
+
+pub mod connection;
+
+pub mod storage;
+
+pub use storage::{Storage, LogRow, Backend, use_backend};
+
+pub mod memory;
+
+pub use memory::MemoryDatabase;
+
+pub mod query;
+
+pub use query::{Query, Order};
+
+pub mod archive;
+
+pub mod retention;
+
+pub mod search;
+
+#[cfg(test)]
+pub mod testing;
+
+pub mod config;
+
+pub use config::{BotConfig, CommandSettings};
+
 pub mod wrappers;
 
 pub use wrappers::DatabaseWrapper;
@@ -12,6 +53,10 @@ pub use wrappers::FlagLog;
 pub use wrappers::ScheduleLog;
 pub use wrappers::TicketReviewLog;
 pub use wrappers::Note;
+pub use wrappers::ArchiveLog;
+pub use wrappers::RemovalLog;
+pub use wrappers::ConfigChange;
+pub use wrappers::{CommandUse, CommandOutcome};
 
 pub use wrappers::ConfigDB;
 pub use wrappers::WarningsDB;
@@ -24,5 +69,13 @@ pub use wrappers::ScheduleDB;
 pub use wrappers::TicketReviewsDB;
 pub use wrappers::NotesDB;
 pub use wrappers::RemindersDB;
-pub use wrappers::TweetsDB;
-pub use wrappers::DeadchatDB;
+pub use wrappers::ArchiveDB;
+pub use wrappers::RemovalsDB;
+pub use wrappers::ConfigHistoryDB;
+pub use wrappers::CooldownsDB;
+pub use wrappers::CommandUsesDB;
+pub use wrappers::LocalesDB;
+
+pub use wrappers::initialize_databases;
+pub use wrappers::get_storage;
+pub use wrappers::remove_records;
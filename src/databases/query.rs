@@ -0,0 +1,204 @@
+use rusqlite::types::Value;
+
+use crate::databases::schema::*;
+use crate::databases::scope::Scope;
+use crate::databases::search::{indexed_column, index_name, match_expression, matches};
+use crate::databases::error::*;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+impl Order {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Order::Ascending  => "ASC",
+            Order::Descending => "DESC",
+        }
+    }
+}
+
+// A typed filter over the rows of a table. Every condition is turned into a
+// bound parameter, so user supplied text never becomes part of the SQL itself.
+// Conditions are combined with AND, rows are ordered by their timestamp.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    key: Option<String>,
+    scope: Option<Scope>,
+    id: Option<i64>,
+    staff_id: Option<String>,
+    matching: Option<(&'static str, String)>,
+    after: Option<i64>,
+    before: Option<i64>,
+    order: Option<Order>,
+    limit: Option<u32>,
+}
+
+impl Query {
+
+    pub fn new() -> Self {
+        Query::default()
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn scope(mut self, scope: impl Into<Scope>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    pub fn id(mut self, id: i64) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    // only applies to tables whose records have a `staff_id` column
+    pub fn staff(mut self, staff_id: impl Into<String>) -> Self {
+        self.staff_id = Some(staff_id.into());
+        self
+    }
+
+    // rows whose column contains all words of the text, only applies to
+    // the columns that have a search index, see `search.rs`
+    pub fn matching(mut self, column: &'static str, text: impl Into<String>) -> Self {
+        self.matching = Some((column, text.into()));
+        self
+    }
+
+    // rows with a timestamp strictly greater than the given one
+    pub fn after(mut self, timestamp: i64) -> Self {
+        self.after = Some(timestamp);
+        self
+    }
+
+    // rows with a timestamp strictly less than the given one
+    pub fn before(mut self, timestamp: i64) -> Self {
+        self.before = Some(timestamp);
+        self
+    }
+
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    // conditions that the table can not answer
+    fn check(&self, table: &str, columns: &[Column]) -> DBResult<()> {
+        if self.staff_id.is_some() && !columns.iter().any(|column| column.name == "staff_id") {
+            return Err(DBError::InvalidQuery("Table has no staff column".to_string()));
+        }
+        if let Some((column, text)) = &self.matching {
+            if indexed_column(table) != Some(column) {
+                return Err(DBError::InvalidQuery(format!("Column {} has no search index", column)));
+            }
+            if text.split_whitespace().next().is_none() {
+                return Err(DBError::InvalidQuery("Nothing to search for".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    // Builds everything after `FROM table` together with the parameters
+    // that have to be bound to the placeholders, in order.
+    pub fn build(&self, table: &str, columns: &[Column]) -> DBResult<(String, Vec<Value>)> {
+
+        self.check(table, columns)?;
+        let mut conditions = Vec::new();
+        let mut parameters = Vec::new();
+
+        if let Some(key) = &self.key {
+            conditions.push("key = ?");
+            parameters.push(Value::Text(key.clone()));
+        }
+        if let Some(scope) = &self.scope {
+            conditions.push("guild_id = ?");
+            parameters.push(Value::Text(scope.id()));
+        }
+        if let Some(id) = self.id {
+            conditions.push("id = ?");
+            parameters.push(Value::Integer(id));
+        }
+        if let Some(staff_id) = &self.staff_id {
+            conditions.push("staff_id = ?");
+            parameters.push(Value::Text(staff_id.clone()));
+        }
+        let search;
+        if let Some((_, text)) = &self.matching {
+            search = format!("id IN (SELECT rowid FROM {0} WHERE {0} MATCH ?)", index_name(table));
+            conditions.push(&search);
+            parameters.push(Value::Text(match_expression(text)));
+        }
+        if let Some(after) = self.after {
+            conditions.push("timestamp > ?");
+            parameters.push(Value::Integer(after));
+        }
+        if let Some(before) = self.before {
+            conditions.push("timestamp < ?");
+            parameters.push(Value::Integer(before));
+        }
+
+        let mut clause = String::new();
+        if !conditions.is_empty() {
+            clause.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        if let Some(order) = self.order {
+            // ties are broken by insertion order
+            clause.push_str(&format!(" ORDER BY timestamp {0}, id {0}", order.keyword()));
+        }
+        if let Some(limit) = self.limit {
+            clause.push_str(" LIMIT ?");
+            parameters.push(Value::Integer(limit as i64));
+        }
+
+        Ok((clause, parameters))
+    }
+
+    // Applies the query to rows that are kept in memory, with the same
+    // semantics as the clause produced by `build`.
+    pub fn apply(&self, table: &str, rows: impl IntoIterator<Item = DBRow>, columns: &[Column]) -> DBResult<Vec<DBRow>> {
+
+        self.check(table, columns)?;
+        let staff_index = columns.iter().position(|column| column.name == "staff_id");
+        let search_index = self.matching.as_ref()
+            .and_then(|(name, _)| columns.iter().position(|column| column.name == *name));
+
+        let mut rows = rows.into_iter()
+            .filter(|row| self.key.as_ref().is_none_or(|key| &row.key == key))
+            .filter(|row| self.scope.is_none_or(|scope| row.guild_id == scope.id()))
+            .filter(|row| self.id.is_none_or(|id| row.id == id))
+            .filter(|row| match (&self.staff_id, staff_index) {
+                (Some(staff_id), Some(index)) => row.values.get(index) == Some(&Value::Text(staff_id.clone())),
+                _ => true,
+            })
+            .filter(|row| match (&self.matching, search_index) {
+                (Some((_, text)), Some(index)) => matches(row.values.get(index), text),
+                _ => true,
+            })
+            .filter(|row| self.after.is_none_or(|after| row.timestamp > after))
+            .filter(|row| self.before.is_none_or(|before| row.timestamp < before))
+            .collect::<Vec<DBRow>>();
+
+        if let Some(order) = self.order {
+            rows.sort_by_key(|row| (row.timestamp, row.id));
+            if order == Order::Descending {
+                rows.reverse();
+            }
+        }
+        if let Some(limit) = self.limit {
+            rows.truncate(limit as usize);
+        }
+
+        Ok(rows)
+    }
+}
@@ -0,0 +1,218 @@
+use strum::IntoEnumIterator;
+
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::databases::database::DB;
+use crate::databases::query::*;
+use crate::databases::scope::Scope;
+use crate::databases::wrappers::*;
+use crate::databases::error::*;
+use crate::utility::*;
+
+
+const DAY: i64 = 24 * 60 * 60;
+
+// The retention of a table is configured with the `retention_<table>` key,
+// e.g. `365d`, or `never` to keep all records. Pruned records are moved to
+// the archive table unless `archive_pruned` is set to `false`.
+pub const RETENTION_PREFIX: &str = "retention_";
+pub const ARCHIVE_KEY: &str = "archive_pruned";
+
+// Prunes never overlap, otherwise both would archive the same records.
+static PRUNING: Mutex<()> = Mutex::const_new(());
+
+// Tables without a default keep their records until they are removed by
+// the bot itself, e.g. reminders once they have fired.
+fn default_retention(identifier: &DB) -> Option<i64> {
+    match identifier {
+        DB::Warnings | DB::Mutes | DB::Unmutes | DB::Flags => Some(365 * DAY),
+        DB::CommandUses => Some(90 * DAY),
+        _ => None,
+    }
+}
+
+fn retention_key(identifier: &DB) -> String {
+    format!("{}{}", RETENTION_PREFIX, identifier)
+}
+
+// The maximum age of the records of a table in a guild in seconds, if they
+// expire at all. Guilds without their own retention use the global one.
+pub async fn retention(scope: Scope, identifier: &DB) -> DBResult<Option<i64>> {
+    let value = match ConfigDB::get_instance().scope(scope).get(&retention_key(identifier)).await {
+        Ok(value) => value.to_string(),
+        Err(DBError::NotFound(_)) => return Ok(default_retention(identifier)),
+        Err(err) => return Err(err),
+    };
+    match value.as_str() {
+        "never" => Ok(None),
+        value => parse_time(value).ok()
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| Some(seconds as i64))
+            .ok_or_else(|| DBError::Corrupt(format!("'{}' is not a valid retention for {}", value, identifier))),
+    }
+}
+
+// A malformed retention keeps the records of its guild, so it does not stop
+// the other guilds from being pruned.
+async fn retention_or_never(scope: Scope, identifier: &DB) -> DBResult<Option<i64>> {
+    match retention(scope, identifier).await {
+        Err(DBError::Corrupt(err)) => {
+            Logger::err_long("Skipped pruning a guild", &format!("{} in {}", err, scope));
+            Ok(None)
+        }
+        result => result,
+    }
+}
+
+async fn archive_enabled(scope: Scope) -> DBResult<bool> {
+    match ConfigDB::get_instance().scope(scope).get(ARCHIVE_KEY).await {
+        Ok(value) => Ok(value.to_string() != "false"),
+        Err(DBError::NotFound(_)) => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
+// the shortest retention of a table in any guild, if its records expire at all
+async fn shortest_retention(identifier: &DB) -> DBResult<Option<i64>> {
+    let mut scopes = get_storage(&DB::Config)
+        .query_rows(&Query::new().key(retention_key(identifier))).await?
+        .into_iter()
+        .map(|row| Scope::from_id(&row.guild_id))
+        .collect::<Vec<Scope>>();
+    scopes.push(Scope::Global);
+    let mut shortest: Option<i64> = None;
+    for scope in scopes {
+        if let Some(retention) = retention_or_never(scope, identifier).await? {
+            shortest = Some(shortest.map_or(retention, |shortest| shortest.min(retention)));
+        }
+    }
+    Ok(shortest)
+}
+
+// Removes all records that are older than the retention of their table in
+// their guild and returns how many were removed. The configuration is never
+// pruned and archived records are not archived a second time.
+pub async fn prune() -> DBResult<usize> {
+
+    let _pruning = PRUNING.lock().await;
+    let now = chrono::Utc::now().timestamp();
+    let mut count = 0;
+
+    for identifier in DB::iter().filter(|identifier| *identifier != DB::Config) {
+
+        // only records that expire in at least one guild are read
+        let Some(shortest) = shortest_retention(&identifier).await? else {
+            continue;
+        };
+        let storage = get_storage(&identifier);
+        let rows = storage.query_rows(&Query::new().before(now - shortest)).await?;
+
+        let mut settings = HashMap::new();
+        for row in rows {
+            let scope = Scope::from_id(&row.guild_id);
+            let (retention, archive) = match settings.get(&scope) {
+                Some(setting) => *setting,
+                None => {
+                    let setting = (retention_or_never(scope, &identifier).await?, archive_enabled(scope).await?);
+                    settings.insert(scope, setting);
+                    setting
+                }
+            };
+            if retention.is_none_or(|retention| row.timestamp >= now - retention) {
+                continue;
+            }
+            let id = row.id;
+
+            // the record is archived first, so it is never lost
+            if archive && identifier != DB::Archive {
+                let log = ArchiveLog::archive(&identifier, row, storage.columns());
+                ArchiveDB::get_instance()
+                    .scope(scope)
+                    .append(&log.key.clone(), log).await?;
+            }
+            storage.delete_by_id(id).await?;
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::databases::*;
+    use crate::databases::testing::guild;
+
+    // Other tests prune in between, which is why every test configures
+    // its guilds before adding their records and only checks those guilds.
+
+    // a warning of the given age in days
+    fn warning(scope: Scope, id: i64, days: i64) -> DBRow {
+        DBRow {
+            id,
+            key: "10".to_string(),
+            guild_id: scope.id(),
+            timestamp: chrono::Utc::now().timestamp() - days * DAY,
+            values: ModLog::new("1".to_string(), format!("{} days", days)).into_values(),
+        }
+    }
+
+    async fn remaining(scope: Scope) -> Vec<String> {
+        WarningsDB::get_instance().scope(scope).get_all("10").await.unwrap()
+            .into_iter()
+            .map(|warning| warning.reason)
+            .collect()
+    }
+
+    async fn archived(scope: Scope) -> usize {
+        ArchiveDB::get_instance().scope(scope).get_all("10").await.unwrap().len()
+    }
+
+    #[tokio::test]
+    async fn guilds_keep_records_as_long_as_they_configured() {
+        let (short, long) = (guild(), guild());
+        ConfigDB::get_instance().scope(short).set("retention_warnings", "30d").await.unwrap();
+        let storage = get_storage(&DB::Warnings);
+        storage.restore_rows(Some(short), vec![warning(short, 0, 60), warning(short, 0, 10)]).await.unwrap();
+        storage.restore_rows(Some(long), vec![warning(long, 0, 60)]).await.unwrap();
+
+        prune().await.unwrap();
+        assert_eq!(remaining(short).await, vec!["10 days"]);
+        assert_eq!(archived(short).await, 1);
+        assert_eq!(remaining(long).await, vec!["60 days"]);
+        assert_eq!(archived(long).await, 0);
+    }
+
+    #[tokio::test]
+    async fn guilds_may_delete_pruned_records() {
+        let (deleting, archiving) = (guild(), guild());
+        ConfigDB::get_instance().scope(deleting).set(ARCHIVE_KEY, "false").await.unwrap();
+        for scope in [deleting, archiving] {
+            ConfigDB::get_instance().scope(scope).set("retention_warnings", "30d").await.unwrap();
+            get_storage(&DB::Warnings).restore_rows(Some(scope), vec![warning(scope, 0, 60)]).await.unwrap();
+        }
+
+        prune().await.unwrap();
+        assert!(remaining(deleting).await.is_empty());
+        assert_eq!(archived(deleting).await, 0);
+        assert!(remaining(archiving).await.is_empty());
+        assert_eq!(archived(archiving).await, 1);
+    }
+
+    #[tokio::test]
+    async fn malformed_retentions_only_skip_their_guild() {
+        let (malformed, valid) = (guild(), guild());
+        ConfigDB::get_instance().scope(malformed).set("retention_warnings", "soon").await.unwrap();
+        ConfigDB::get_instance().scope(valid).set("retention_warnings", "30d").await.unwrap();
+        for scope in [malformed, valid] {
+            get_storage(&DB::Warnings).restore_rows(Some(scope), vec![warning(scope, 0, 60)]).await.unwrap();
+        }
+
+        prune().await.unwrap();
+        assert_eq!(remaining(malformed).await, vec!["60 days"]);
+        assert!(remaining(valid).await.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use rusqlite::types::Value;
+
+use crate::utility::*;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Boolean,
+}
+
+impl ColumnType {
+
+    pub fn definition(&self) -> &'static str {
+        match self {
+            ColumnType::Text    => "TEXT    NOT NULL DEFAULT ''",
+            ColumnType::Integer => "INTEGER NOT NULL DEFAULT 0",
+            ColumnType::Boolean => "INTEGER NOT NULL DEFAULT 0",
+        }
+    }
+
+    pub fn default_value(&self) -> Value {
+        match self {
+            ColumnType::Text => Value::Text(String::new()),
+            _                => Value::Integer(0),
+        }
+    }
+
+    // legacy rows stored every field as a string inside a JSON array,
+    // this converts such a string into the value of a typed column
+    pub fn parse_legacy(&self, raw: &str) -> Value {
+        match self {
+            ColumnType::Text    => Value::Text(raw.to_string()),
+            ColumnType::Integer => Value::Integer(raw.parse::<i64>().unwrap_or(0)),
+            ColumnType::Boolean => Value::Integer((raw == "true" || raw == "1") as i64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: &'static str,
+    pub column_type: ColumnType,
+}
+
+impl Column {
+    pub fn new(name: &'static str, column_type: ColumnType) -> Self {
+        Column { name, column_type }
+    }
+}
+
+// A raw row as it is stored in a table, the values are ordered like the
+// columns of the record that is stored in the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DBRow {
+    pub id: i64,
+    pub key: String,
+    pub guild_id: String,
+    pub timestamp: i64,
+    pub values: Vec<Value>,
+}
+
+pub trait ColumnValue: Sized {
+
+    const COLUMN_TYPE: ColumnType;
+
+    fn from_value(value: Option<Value>) -> Result<Self>;
+
+    fn into_value(self) -> Value;
+
+}
+
+impl ColumnValue for String {
+
+    const COLUMN_TYPE: ColumnType = ColumnType::Text;
+
+    fn from_value(value: Option<Value>) -> Result<Self> {
+        match value {
+            Some(Value::Text(text))     => Ok(text),
+            Some(Value::Integer(value)) => Ok(value.to_string()),
+            _ => Err("Expected a text column".to_string()),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Text(self)
+    }
+}
+
+impl ColumnValue for i64 {
+
+    const COLUMN_TYPE: ColumnType = ColumnType::Integer;
+
+    fn from_value(value: Option<Value>) -> Result<Self> {
+        match value {
+            Some(Value::Integer(value)) => Ok(value),
+            Some(Value::Text(text)) => text.parse::<i64>()
+                .map_err(|_| format!("Expected an integer column, found '{}'", text)),
+            _ => Err("Expected an integer column".to_string()),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Integer(self)
+    }
+}
+
+impl ColumnValue for bool {
+
+    const COLUMN_TYPE: ColumnType = ColumnType::Boolean;
+
+    fn from_value(value: Option<Value>) -> Result<Self> {
+        match value {
+            Some(Value::Integer(value)) => Ok(value != 0),
+            Some(Value::Text(text)) => match text.as_str() {
+                "true"  | "1" => Ok(true),
+                "false" | "0" => Ok(false),
+                _ => Err(format!("Expected a boolean column, found '{}'", text)),
+            },
+            _ => Err("Expected a boolean column".to_string()),
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Integer(self as i64)
+    }
+}
+
+// Every type stored in a database implements this trait. Besides the
+// columns `id`, `key`, `guild_id` and `timestamp` which every table has,
+// a record defines its own columns and how to convert from and into them.
+pub trait Record: Sized + Clone + Send + Sync + 'static {
+
+    fn columns() -> Vec<Column>;
+
+    fn from_row(row: DBRow) -> Result<Self>;
+
+    fn into_values(self) -> Vec<Value>;
+
+}
+
+pub fn create_table_statement(table: &str, columns: &[Column]) -> String {
+    let mut definitions = vec![
+        "id        INTEGER PRIMARY KEY".to_string(),
+        "key       TEXT    NOT NULL".to_string(),
+        "guild_id  TEXT    NOT NULL DEFAULT ''".to_string(),
+        "timestamp INTEGER NOT NULL DEFAULT 0".to_string(),
+    ];
+    for column in columns {
+        definitions.push(format!("{} {}", column.name, column.column_type.definition()));
+    }
+    format!("CREATE TABLE IF NOT EXISTS {} (\n    {}\n)", table, definitions.join(",\n    "))
+}
+
+pub fn column_list(columns: &[Column]) -> String {
+    let mut names = vec!["id", "key", "guild_id", "timestamp"];
+    names.extend(columns.iter().map(|column| column.name));
+    names.join(", ")
+}
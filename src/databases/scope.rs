@@ -0,0 +1,80 @@
+use serenity::all::GuildId;
+
+use std::str::FromStr;
+use std::fmt;
+
+use crate::utility::*;
+
+
+// Configuration keys that are shared by all guilds. The migration to
+// per-guild data keeps them global, all other rows are moved to the
+// guild that was configured as `guild_main` before.
+pub const GLOBAL_KEYS: [&str; 7] = [
+    "token",
+    "bot_id",
+    "guild_main",
+    "uptime",
+    "executed_commands",
+    "color_primary",
+    "web_url",
+];
+
+// The guild a row belongs to. Every row is stored with the ID of its guild,
+// global rows are stored with an empty guild ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Global,
+    Guild(GuildId),
+}
+
+impl Scope {
+
+    // the value of the `guild_id` column
+    pub fn id(&self) -> String {
+        match self {
+            Scope::Global => String::new(),
+            Scope::Guild(guild_id) => guild_id.to_string(),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Scope {
+        match GuildId::from_str(id) {
+            Ok(guild_id) => Scope::Guild(guild_id),
+            Err(_) => Scope::Global,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scope::Global => write!(f, "global"),
+            Scope::Guild(guild_id) => write!(f, "{}", guild_id),
+        }
+    }
+}
+
+impl From<GuildId> for Scope {
+    fn from(guild_id: GuildId) -> Scope {
+        Scope::Guild(guild_id)
+    }
+}
+
+// messages outside of a guild, e.g. direct messages, are global
+impl From<Option<GuildId>> for Scope {
+    fn from(guild_id: Option<GuildId>) -> Scope {
+        guild_id.map_or(Scope::Global, Scope::Guild)
+    }
+}
+
+impl From<&MessageManager> for Scope {
+    fn from(message: &MessageManager) -> Scope {
+        message.get_guild().into()
+    }
+}
+
+impl From<&Resolver> for Scope {
+    fn from(resolver: &Resolver) -> Scope {
+        resolver.guild_id().into()
+    }
+}
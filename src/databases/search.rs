@@ -0,0 +1,155 @@
+use rusqlite::Connection;
+use rusqlite::types::Value;
+
+use crate::databases::database::DB;
+use crate::databases::schema::*;
+use crate::databases::query::*;
+use crate::databases::scope::Scope;
+use crate::databases::wrappers::*;
+use crate::databases::error::*;
+
+
+// The tables that can be searched and the column of each table that is
+// indexed. Every table gets its own FTS5 index named `<table>_search`, which
+// refers to the rows of the table itself and is kept up to date by triggers.
+pub const SEARCHABLE: [(DB, &str); 5] = [
+    (DB::Warnings, "reason"),
+    (DB::Mutes,    "reason"),
+    (DB::Bans,     "reason"),
+    (DB::Flags,    "reason"),
+    (DB::Notes,    "content"),
+];
+
+pub fn indexed_column(table: &str) -> Option<&'static str> {
+    SEARCHABLE.iter()
+        .find(|(identifier, _)| identifier.to_string() == table)
+        .map(|(_, column)| *column)
+}
+
+pub fn index_name(table: &str) -> String {
+    format!("{}_search", table)
+}
+
+fn trigger_exists(connection: &Connection, trigger: &str) -> rusqlite::Result<bool> {
+    connection.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'trigger' AND name = ?",
+        [trigger],
+        |row| row.get::<_, i64>(0)
+    ).map(|count| count > 0)
+}
+
+// Creates the search index of a table and the triggers that keep it in sync.
+// Migrations that rebuild a table drop its triggers, whenever they are
+// missing the index is therefore rebuilt from the rows of the table.
+pub fn prepare_index(connection: &Connection, table: &str) -> DBResult<()> {
+
+    let Some(column) = indexed_column(table) else {
+        return Ok(());
+    };
+    let index = index_name(table);
+    if trigger_exists(connection, &format!("{}_insert", index))? {
+        return Ok(());
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+    transaction.execute_batch(&format!("
+        CREATE VIRTUAL TABLE IF NOT EXISTS {index} USING fts5({column}, content='{table}', content_rowid='id');
+
+        CREATE TRIGGER IF NOT EXISTS {index}_insert AFTER INSERT ON {table} BEGIN
+            INSERT INTO {index} (rowid, {column}) VALUES (new.id, new.{column});
+        END;
+        CREATE TRIGGER IF NOT EXISTS {index}_delete AFTER DELETE ON {table} BEGIN
+            INSERT INTO {index} ({index}, rowid, {column}) VALUES ('delete', old.id, old.{column});
+        END;
+        CREATE TRIGGER IF NOT EXISTS {index}_update AFTER UPDATE ON {table} BEGIN
+            INSERT INTO {index} ({index}, rowid, {column}) VALUES ('delete', old.id, old.{column});
+            INSERT INTO {index} (rowid, {column}) VALUES (new.id, new.{column});
+        END;
+
+        INSERT INTO {index} ({index}) VALUES ('rebuild');
+    "))?;
+    transaction.commit()?;
+    Ok(())
+}
+
+// Turns the text entered by a user into an FTS5 query. Every word has to
+// occur in the indexed column, words also match longer words they start with.
+// Words are quoted, so the syntax of FTS5 can not be used to break the query.
+pub fn match_expression(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// The same matching for rows that are kept in memory: the words of the
+// column are compared case insensitively, like the default FTS5 tokenizer.
+pub fn matches(value: Option<&Value>, text: &str) -> bool {
+    let Some(Value::Text(value)) = value else {
+        return false;
+    };
+    let value = value.to_lowercase();
+    let words = value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>();
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .all(|term| words.iter().any(|word| word.starts_with(&term)))
+}
+
+// A record of any searchable table that matched a search.
+#[derive(Clone)]
+pub struct SearchHit {
+    pub table: DB,
+    pub id: i64,
+    pub key: String,
+    pub timestamp: i64,
+    pub staff_id: Option<String>,
+    pub text: String,
+}
+
+// Searches all searchable tables of a guild, the newest matches first.
+pub async fn search(scope: impl Into<Scope>, text: &str) -> DBResult<Vec<SearchHit>> {
+
+    let scope = scope.into();
+    let mut hits = Vec::new();
+
+    for (identifier, column) in SEARCHABLE {
+        let query = Query::new()
+            .scope(scope)
+            .matching(column, text);
+        let rows = get_storage(&identifier).query_rows(&query).await?;
+        for row in rows {
+            let hit = match identifier {
+                DB::Notes => {
+                    let note = Note::from_row(row).map_err(DBError::Corrupt)?;
+                    SearchHit {
+                        table: identifier.clone(),
+                        id: note.id,
+                        key: Note::deescape(note.key),
+                        timestamp: note.timestamp,
+                        staff_id: None,
+                        text: note.content,
+                    }
+                }
+                _ => {
+                    let log = ModLog::from_row(row).map_err(DBError::Corrupt)?;
+                    SearchHit {
+                        table: identifier.clone(),
+                        id: log.id,
+                        key: log.key,
+                        timestamp: log.timestamp,
+                        staff_id: Some(log.staff_id),
+                        text: log.reason,
+                    }
+                }
+            };
+            hits.push(hit);
+        }
+    }
+
+    hits.sort_by_key(|hit| std::cmp::Reverse((hit.timestamp, hit.id)));
+    Ok(hits)
+}
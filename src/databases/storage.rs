@@ -0,0 +1,131 @@
+use rusqlite::types::Value;
+use once_cell::sync::OnceCell;
+
+use crate::databases::database::{Database, DB};
+use crate::databases::memory::MemoryDatabase;
+use crate::databases::schema::*;
+use crate::databases::query::*;
+use crate::databases::scope::Scope;
+use crate::databases::error::*;
+use crate::utility::*;
+
+
+// A storage backend holds the rows of a single table. Backends only deal
+// with raw rows, the conversion from and into records is done on top of
+// them, see the implementation of `dyn Storage` below. Every key exists
+// once per scope, rows of other scopes are never touched.
+pub trait Storage: Send + Sync {
+
+    fn get_keys(&self, scope: Scope) -> BoxedFuture<'_, DBResult<Vec<String>>>;
+
+    fn query_rows<'a>(&'a self, query: &'a Query) -> BoxedFuture<'a, DBResult<Vec<DBRow>>>;
+
+    // replaces all rows of the key with a single new row
+    fn set_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>>;
+
+    fn append_values<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>) -> BoxedFuture<'a, DBResult<()>>;
+
+    // Appends the values unless the key already has `limit` rows newer than
+    // `after`. Counting and appending happen at once, so concurrent appends
+    // never exceed the limit. Returns whether the values were appended.
+    fn append_limited<'a>(&'a self, scope: Scope, key: &'a str, values: Vec<Value>, after: i64, limit: usize) -> BoxedFuture<'a, DBResult<bool>>;
+
+    // Replaces all rows of the key with the given ones, none delete the key,
+    // and appends the log to its table with the same scope and key. Either
+    // everything is written or nothing is.
+    fn change_values<'a>(&'a self, scope: Scope, key: &'a str, rows: Vec<Vec<Value>>, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>>;
+
+    fn has<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<bool>>;
+
+    fn delete<'a>(&'a self, scope: Scope, key: &'a str) -> BoxedFuture<'a, DBResult<()>>;
+
+    fn delete_by_id(&self, id: i64) -> BoxedFuture<'_, DBResult<()>>;
+
+    // Deletes the row with the id and appends the log to its table with the
+    // scope and key, e.g. a tombstone of the row. Either both are written or
+    // nothing is.
+    fn delete_logged<'a>(&'a self, id: i64, scope: Scope, key: &'a str, log: LogRow<'a>) -> BoxedFuture<'a, DBResult<()>>;
+
+    fn identifier(&self) -> &DB;
+
+    fn columns(&self) -> &[Column];
+
+    // Replaces every row of the scope, or of the whole table without one.
+    // The given rows keep their ids unless a remaining row already has the
+    // same id, then they get a fresh one.
+    fn restore_rows(&self, scope: Option<Scope>, rows: Vec<DBRow>) -> BoxedFuture<'_, DBResult<()>>;
+
+}
+
+impl dyn Storage + '_ {
+
+    pub async fn query<T: Record>(&self, query: &Query) -> DBResult<Vec<T>> {
+        self.query_rows(query).await?
+            .into_iter()
+            .map(|row| T::from_row(row).map_err(DBError::Corrupt))
+            .collect()
+    }
+
+    pub async fn get<T: Record>(&self, scope: Scope, key: &str) -> DBResult<T> {
+        self.query(&Query::new().scope(scope).key(key).order(Order::Ascending).limit(1)).await?
+            .pop()
+            .ok_or(DBError::NotFound(key.to_string()))
+    }
+
+    pub async fn get_all<T: Record>(&self, scope: Scope, key: &str) -> DBResult<Vec<T>> {
+        self.query(&Query::new().scope(scope).key(key)).await
+    }
+
+    pub async fn get_last<T: Record>(&self, scope: Scope, key: &str, limit: u8) -> DBResult<Vec<T>> {
+        self.query(&Query::new().scope(scope).key(key).order(Order::Descending).limit(limit as u32)).await
+    }
+
+    pub async fn set<T: Record>(&self, scope: Scope, key: &str, value: T) -> DBResult<()> {
+        self.set_values(scope, key, value.into_values()).await
+    }
+
+    pub async fn append<T: Record>(&self, scope: Scope, key: &str, value: T) -> DBResult<()> {
+        self.append_values(scope, key, value.into_values()).await
+    }
+}
+
+// A record of another table that is written together with a change, e.g.
+// the history entry of a configuration key.
+pub struct LogRow<'a> {
+    pub storage: &'a dyn Storage,
+    pub values: Vec<Value>,
+}
+
+impl<'a> LogRow<'a> {
+    pub fn new<T: Record>(storage: &'a dyn Storage, record: T) -> LogRow<'a> {
+        LogRow { storage, values: record.into_values() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    Sqlite,
+    Memory,
+}
+
+static BACKEND: OnceCell<Backend> = OnceCell::new();
+
+// Selects the backend of all databases. This has to happen before the first
+// database is accessed, afterwards the backend can no longer be changed.
+// Tests use the in-memory backend by default, so they never touch disk.
+#[allow(unused)]
+pub fn use_backend(backend: Backend) -> Result<()> {
+    BACKEND.set(backend)
+        .map_err(|_| "The storage backend has already been selected".to_string())
+}
+
+pub fn open_storage(identifier: DB, columns: Vec<Column>) -> Box<dyn Storage> {
+    let default = match cfg!(test) {
+        true  => Backend::Memory,
+        false => Backend::Sqlite,
+    };
+    match BACKEND.get_or_init(|| default) {
+        Backend::Sqlite => Box::new(Database::new(identifier, columns)),
+        Backend::Memory => Box::new(MemoryDatabase::new(identifier, columns)),
+    }
+}
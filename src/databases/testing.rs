@@ -0,0 +1,9 @@
+use crate::databases::scope::Scope;
+use crate::utility::FakeGuild;
+
+
+// A guild of its own for every call, so tests that run at the same time
+// never see each other's rows.
+pub fn guild() -> Scope {
+    Scope::from(FakeGuild::new("Database").guild_id)
+}
@@ -1,15 +1,24 @@
 
 use serde::{Serialize, Deserialize};
+use rusqlite::types::Value;
 use tokio::sync::RwLock;
 use once_cell::sync::Lazy;
 use nonempty::{NonEmpty, nonempty};
+use strum_macros::EnumIter;
 
 use std::sync::Arc;
 use std::convert::From;
+use std::marker::PhantomData;
+use std::fmt;
 
-use crate::databases::database::Database;
+use crate::databases::storage::{Storage, LogRow, open_storage};
 use crate::databases::database::DBEntry;
 use crate::databases::database::DB;
+use crate::databases::schema::*;
+use crate::databases::scope::Scope;
+use crate::databases::query::*;
+use crate::databases::error::*;
+use crate::databases::archive::{json_value, from_json_value};
 use crate::utility::*;
 use crate::impl_singleton;
 
@@ -21,6 +30,7 @@ macro_rules! as_db_entry {
         pub struct $name {
             pub id: i64,
             pub key: String,
+            pub guild_id: String,
             pub timestamp: i64,
             $(pub $field_name: $field_type),*
         }
@@ -30,29 +40,32 @@ macro_rules! as_db_entry {
                 $name {
                     id: 0,
                     key: "".to_string(),
+                    guild_id: "".to_string(),
                     timestamp: 0,
                     $($field_name),*
                 }
             }
-            pub fn into(self) -> String {
-                let mut relevant_fields = Vec::<String>::new();
-                for field in vec![$(self.$field_name.to_string()),*] {
-                    relevant_fields.push(field);
-                }
-                serde_json::to_string(&relevant_fields).unwrap()
-            }
         }
 
-        impl From<DBEntry> for $name {
-            fn from(entry: DBEntry) -> $name {
-                let mut relevant: Vec<String> = serde_json::from_str(&entry.value).unwrap();
-                relevant.reverse();
-                $name {
-                    id: entry.id,
-                    key: entry.key,
-                    timestamp: entry.timestamp,
-                    $($field_name: relevant.pop().unwrap().parse().unwrap()),*
-                }
+        impl Record for $name {
+
+            fn columns() -> Vec<Column> {
+                vec![$(Column::new(stringify!($field_name), <$field_type as ColumnValue>::COLUMN_TYPE)),*]
+            }
+
+            fn from_row(row: DBRow) -> Result<Self> {
+                let mut values = row.values.into_iter();
+                Ok($name {
+                    id: row.id,
+                    key: row.key,
+                    guild_id: row.guild_id,
+                    timestamp: row.timestamp,
+                    $($field_name: <$field_type as ColumnValue>::from_value(values.next())?),*
+                })
+            }
+
+            fn into_values(self) -> Vec<Value> {
+                vec![$(self.$field_name.into_value()),*]
             }
         }
     }
@@ -78,6 +91,7 @@ impl From<FlagLog> for ModLog {
         ModLog {
             id: flag_log.id,
             key: flag_log.key,
+            guild_id: flag_log.guild_id,
             timestamp: flag_log.timestamp,
             staff_id: flag_log.staff_id,
             reason: flag_log.reason
@@ -104,148 +118,263 @@ impl Note {
     }
 }
 
+// The values of a record that is moved to another table, as a JSON array.
+fn encode_values(values: Vec<Value>, columns: &[Column]) -> String {
+    let values = values.into_iter()
+        .zip(columns)
+        .map(|(value, column)| json_value(value, column.column_type))
+        .collect::<Vec<serde_json::Value>>();
+    serde_json::Value::Array(values).to_string()
+}
+
+// Rebuilds a record from values that were encoded with `encode_values`.
+fn decode_record<T: Record>(id: i64, key: String, guild_id: String, timestamp: i64, values: &str) -> DBResult<T> {
+    let columns = T::columns();
+    let values = serde_json::from_str::<Vec<serde_json::Value>>(values)
+        .map_err(|err| DBError::Corrupt(err.to_string()))?;
+    let values = columns.iter()
+        .enumerate()
+        .map(|(i, column)| match values.get(i) {
+            Some(value) => from_json_value(value, column),
+            None => Ok(column.column_type.default_value()),
+        })
+        .collect::<DBResult<Vec<Value>>>()?;
+    T::from_row(DBRow { id, key, guild_id, timestamp, values })
+        .map_err(DBError::Corrupt)
+}
+
+// A record that was moved out of its table by the retention job. The key
+// and the values of the original record are kept as they were.
+as_db_entry!(ArchiveLog, table: String, record_id: i64, created: i64, values: String);
+
+impl ArchiveLog {
+
+    pub fn archive(table: &DB, row: DBRow, columns: &[Column]) -> Self {
+        let mut log = ArchiveLog::new(
+            table.to_string(),
+            row.id,
+            row.timestamp,
+            encode_values(row.values, columns));
+        log.key = row.key;
+        log.guild_id = row.guild_id;
+        log
+    }
+
+    // converts the archived values back into the original record
+    pub fn restore<T: Record>(self) -> DBResult<T> {
+        decode_record(self.record_id, self.key, self.guild_id, self.created, &self.values)
+    }
+}
+
+// A record that was removed by a staff member. Like archived records it is
+// kept with its original key and values, together with who removed it and why.
+// The timestamp of the log is the time of the removal.
+as_db_entry!(RemovalLog, table: String, record_id: i64, created: i64, values: String, staff_id: String, reason: String);
+
+impl RemovalLog {
+
+    pub fn remove(table: &DB, row: DBRow, columns: &[Column], staff_id: &str, reason: &str) -> Self {
+        let mut log = RemovalLog::new(
+            table.to_string(),
+            row.id,
+            row.timestamp,
+            encode_values(row.values, columns),
+            staff_id.to_string(),
+            reason.to_string());
+        log.key = row.key;
+        log.guild_id = row.guild_id;
+        log
+    }
+
+    // converts the stored values back into the removed record
+    pub fn restore<T: Record>(&self) -> DBResult<T> {
+        decode_record(self.record_id, self.key.clone(), self.guild_id.clone(), self.created, &self.values)
+    }
+}
+
+// A change of a configuration key, the key of the change is the changed key.
+// Lists are stored separated by spaces, an empty value means the key was unset.
+as_db_entry!(ConfigChange, old_value: String, new_value: String, staff_id: String);
+
+// A single use of a command, the key is the first trigger of the command and
+// the duration is measured in milliseconds.
+as_db_entry!(CommandUse, user_id: String, channel_id: String, duration: i64, outcome: String);
+
+// How a use of a command ended, stored as text in the outcome of a use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum CommandOutcome {
+    Success,
+    // the command replied with a failure, e.g. a database error
+    Failed,
+    // the arguments did not fit the usage
+    Invalid,
+    // a middleware stopped the command, e.g. a cooldown or a confirmation
+    Cancelled,
+    // the command is disabled, not allowed in the channel or not permitted
+    Denied,
+}
+
+impl fmt::Display for CommandOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandOutcome::Success => write!(f, "success"),
+            CommandOutcome::Failed  => write!(f, "failed"),
+            CommandOutcome::Invalid => write!(f, "invalid"),
+            CommandOutcome::Cancelled => write!(f, "cancelled"),
+            CommandOutcome::Denied  => write!(f, "denied"),
+        }
+    }
+}
+
 impl Triggerable for Note {
     fn get_triggers(&self) -> NonEmpty<String> {
         nonempty![Note::deescape(self.key.clone())]
     }
 }
 
-pub trait DatabaseWrapper<T: From<DBEntry>>: Send + Sync {
+pub trait DatabaseWrapper<T: Record>: Send + Sync {
 
-    fn get_database(&self) -> &Database;
+    fn get_database(&self) -> &dyn Storage;
 
-    fn get_keys<'a>(&'a self) -> BoxedFuture<'a, Vec<String>> {
-        Box::pin(async move {
-            self.get_database().get_keys().await
-        })
-    }
+    fn identifier(&self) -> DB;
 
-    fn get<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<T>> {
-        Box::pin(async move {
-            let entry = self.get_database().get(key).await;
-            match entry {
-                Ok(entry) => Ok(T::from(entry)),
-                Err(_)    => Err(format!("Key '{}' not found", key))
-            }
-        })
+    // All records are read and written through the guild they belong to,
+    // e.g. `WarningsDB::get_instance().scope(message).get_all(&user_id)`.
+    fn scope(&self, scope: impl Into<Scope>) -> Scoped<'_, T> where Self: Sized {
+        Scoped {
+            database: self.get_database(),
+            scope: scope.into(),
+            // guilds inherit the global configuration unless they override it
+            fallback: self.identifier() == DB::Config,
+            record: PhantomData,
+        }
     }
 
-    fn query<'a>(&'a self, key: &'a str, query_string: &'a str) -> BoxedFuture<'a, Result<Vec<T>>> {
-        Box::pin(async move {
-            let entries = self.get_database().query(key, query_string).await;
-            match entries {
-                Ok(entries) => Ok(entries.into_iter().map(|entry| T::from(entry)).collect()),
-                Err(_)      => Err("Query failed".to_string())
-            }
-        })
+    fn global(&self) -> Scoped<'_, T> where Self: Sized {
+        self.scope(Scope::Global)
     }
+}
 
-    fn get_all<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Result<Vec<T>>> {
-        Box::pin(async move {
-            let entries = self.get_database().get_all(key).await;
-            match entries {
-                Ok(entries) => Ok(entries.into_iter().map(|entry| T::from(entry)).collect()),
-                Err(_)      => Err(format!("Key '{}' not found", key))
-            }
-        })
+// The records of a database that belong to a single guild.
+pub struct Scoped<'a, T: Record> {
+    database: &'a dyn Storage,
+    scope: Scope,
+    fallback: bool,
+    record: PhantomData<T>,
+}
+
+impl<T: Record> Scoped<'_, T> {
+
+    fn falls_back(&self) -> bool {
+        self.fallback && self.scope != Scope::Global
     }
 
-    fn get_last<'a>(&'a self, key: &'a str, limit: u8) -> BoxedFuture<'a, Result<Vec<T>>> {
-        Box::pin(async move {
-            let entries = self.get_database().get_last(key, limit).await;
-            match entries {
-                Ok(entries) => Ok(entries.into_iter().map(|entry| T::from(entry)).collect()),
-                Err(_)      => Err(format!("Key '{}' not found", key))
-            }
-        })
+    pub async fn get_keys(&self) -> DBResult<Vec<String>> {
+        self.database.get_keys(self.scope).await
     }
 
-    fn get_multiple<'a>(&'a self, keys: Vec<&'a str>) -> BoxedFuture<'a, Result<Vec<T>>> {
-        Box::pin(async move {
-            let entries = self.get_database().get_multiple(keys.clone()).await;
-            match entries {
-                Ok(entries) => Ok(entries.into_iter().map(|entry| T::from(entry)).collect()),
-                Err(_)      => Err(format!("Keys '{:?}' not found", keys))
-            }
-        })
+    pub async fn get(&self, key: &str) -> DBResult<T> {
+        match self.database.get(self.scope, key).await {
+            Err(DBError::NotFound(_)) if self.falls_back() => self.database.get(Scope::Global, key).await,
+            result => result,
+        }
     }
 
-    fn set<'a>(&'a self, key: &'a str, value: &'a str) -> BoxedFuture<'a, ()> {
-        Box::pin(async move {
-            self.get_database().set(key, vec![value]).await
-        })
+    // gets the first value of a key and converts it, e.g. into a ChannelId
+    pub async fn get_as<V: TryFrom<T, Error = DBError>>(&self, key: &str) -> DBResult<V> {
+        V::try_from(self.get(key).await?)
     }
 
-    fn has<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, bool> {
-        Box::pin(async move {
-            self.get_database().has(key).await
-        })
+    pub async fn query(&self, query: Query) -> DBResult<Vec<T>> {
+        self.database.query(&query.scope(self.scope)).await
     }
 
-    fn append<'a>(&'a self, key: &'a str, value: &'a str) -> BoxedFuture<'a, ()> {
-        Box::pin(async move {
-            self.get_database().append(key, value).await
-        })
+    pub async fn get_all(&self, key: &str) -> DBResult<Vec<T>> {
+        match self.database.get_all(self.scope, key).await {
+            Ok(values) if values.is_empty() && self.falls_back() => self.database.get_all(Scope::Global, key).await,
+            result => result,
+        }
     }
 
-    fn delete<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, ()> {
-        Box::pin(async move {
-            self.get_database().delete(key).await
-        })
+    pub async fn get_last(&self, key: &str, limit: u8) -> DBResult<Vec<T>> {
+        self.database.get_last(self.scope, key, limit).await
     }
 
-    fn delete_by_id<'a>(&'a self, id: i64) -> BoxedFuture<'a, ()> {
-        Box::pin(async move {
-            self.get_database().delete_by_id(id).await
-        })
+    pub async fn get_multiple(&self, keys: Vec<&str>) -> DBResult<Vec<T>> {
+        let mut values = Vec::new();
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
     }
-}
 
-macro_rules! impl_database_wrapper {
+    pub async fn set(&self, key: &str, value: impl Into<T>) -> DBResult<()> {
+        self.database.set(self.scope, key, value.into()).await
+    }
 
-    ($name:ident, $db_type:expr, ModLog) => {
-        pub struct $name {
-            database: Database
+    pub async fn has(&self, key: &str) -> DBResult<bool> {
+        match self.database.has(self.scope, key).await? {
+            false if self.falls_back() => self.database.has(Scope::Global, key).await,
+            has => Ok(has),
         }
+    }
 
-        impl DatabaseWrapper<ModLog> for $name {
-            fn get_database(&self) -> &Database {
-                &self.database
-            }
-        }
+    pub async fn append(&self, key: &str, value: impl Into<T>) -> DBResult<()> {
+        self.database.append(self.scope, key, value.into()).await
+    }
 
-        impl $name {
-            pub fn new() -> Self {
-                $name { database: Database::new($db_type) }
-            }
+    // see `Storage::append_limited`
+    pub async fn append_limited(&self, key: &str, value: impl Into<T>, after: i64, limit: usize) -> DBResult<bool> {
+        self.database.append_limited(self.scope, key, value.into().into_values(), after, limit).await
+    }
 
-            // this function is an optional convenience function
-            // but does not need to be called necessarily
-            #[allow(unused)]
-            pub fn get_by_staff<'a>(&'a self, staff_id: &'a str) -> BoxedFuture<'a, Vec<ModLog>> {
-                Box::pin(async move {
-                    self.query("", &format!("OR value LIKE '[\"{}%'", staff_id)).await.unwrap()
-                })
-            }
+    pub async fn delete(&self, key: &str) -> DBResult<()> {
+        self.database.delete(self.scope, key).await
+    }
+
+    // ids are unique across guilds, records of other guilds are left alone
+    pub async fn delete_by_id(&self, id: i64) -> DBResult<()> {
+        if self.database.query_rows(&Query::new().scope(self.scope).id(id)).await?.is_empty() {
+            return Ok(());
         }
+        self.database.delete_by_id(id).await
+    }
+}
 
-        impl_singleton!($name);
-    };
+impl Scoped<'_, ModLog> {
+
+    // this function is an optional convenience function
+    // but does not need to be called necessarily
+    pub async fn get_by_staff(&self, staff_id: &str) -> DBResult<Vec<ModLog>> {
+        self.query(Query::new().staff(staff_id)).await
+    }
+}
+
+macro_rules! impl_database_wrapper {
 
     ($name:ident, $db_type:expr, $log_type:ty) => {
         pub struct $name {
-            database: Database
+            database: Box<dyn Storage>
         }
 
         impl $name {
             pub fn new() -> Self {
-                $name { database: Database::new($db_type) }
+                $name { database: open_storage($db_type, <$log_type>::columns()) }
+            }
+
+            #[allow(unused)]
+            pub fn with_storage(database: Box<dyn Storage>) -> Self {
+                $name { database }
             }
         }
 
         impl DatabaseWrapper<$log_type> for $name {
-            fn get_database(&self) -> &Database {
-                &self.database
+            fn get_database(&self) -> &dyn Storage {
+                self.database.as_ref()
+            }
+
+            fn identifier(&self) -> DB {
+                $db_type
             }
         }
 
@@ -268,5 +397,74 @@ impl_database_wrapper!(ScheduleDB, DB::Schedule, ScheduleLog);
 impl_database_wrapper!(RemindersDB, DB::Reminders, ScheduleLog);
 impl_database_wrapper!(TicketReviewsDB, DB::TicketReviews, TicketReviewLog);
 impl_database_wrapper!(NotesDB, DB::Notes, Note);
-impl_database_wrapper!(TweetsDB, DB::Tweets);
-impl_database_wrapper!(DeadchatDB, DB::Deadchat);
+impl_database_wrapper!(ArchiveDB, DB::Archive, ArchiveLog);
+impl_database_wrapper!(RemovalsDB, DB::Removals, RemovalLog);
+impl_database_wrapper!(ConfigHistoryDB, DB::ConfigHistory, ConfigChange);
+impl_database_wrapper!(CooldownsDB, DB::Cooldowns);
+impl_database_wrapper!(CommandUsesDB, DB::CommandUses, CommandUse);
+// the language each user prefers, kept globally with the user ID as the key
+impl_database_wrapper!(LocalesDB, DB::Locales);
+
+// Opening a database applies its pending migrations, so every database is
+// touched once at startup to migrate all tables before the bot connects.
+pub fn initialize_databases() {
+    let _ = ConfigDB::get_instance();
+    let _ = WarningsDB::get_instance();
+    let _ = MutesDB::get_instance();
+    let _ = UnmutesDB::get_instance();
+    let _ = BansDB::get_instance();
+    let _ = FlagsDB::get_instance();
+    let _ = AfkDB::get_instance();
+    let _ = ScheduleDB::get_instance();
+    let _ = RemindersDB::get_instance();
+    let _ = TicketReviewsDB::get_instance();
+    let _ = NotesDB::get_instance();
+    let _ = ArchiveDB::get_instance();
+    let _ = RemovalsDB::get_instance();
+    let _ = ConfigHistoryDB::get_instance();
+    let _ = CooldownsDB::get_instance();
+    let _ = CommandUsesDB::get_instance();
+    let _ = LocalesDB::get_instance();
+}
+
+// The storage behind the wrapper of the given table, for code that works on
+// every table alike, like backups.
+pub fn get_storage(identifier: &DB) -> &'static dyn Storage {
+    match identifier {
+        DB::Config        => ConfigDB::get_instance().get_database(),
+        DB::Warnings      => WarningsDB::get_instance().get_database(),
+        DB::Mutes         => MutesDB::get_instance().get_database(),
+        DB::Unmutes       => UnmutesDB::get_instance().get_database(),
+        DB::Flags         => FlagsDB::get_instance().get_database(),
+        DB::Bans          => BansDB::get_instance().get_database(),
+        DB::Afk           => AfkDB::get_instance().get_database(),
+        DB::Schedule      => ScheduleDB::get_instance().get_database(),
+        DB::TicketReviews => TicketReviewsDB::get_instance().get_database(),
+        DB::Notes         => NotesDB::get_instance().get_database(),
+        DB::Reminders     => RemindersDB::get_instance().get_database(),
+        DB::Archive       => ArchiveDB::get_instance().get_database(),
+        DB::Removals      => RemovalsDB::get_instance().get_database(),
+        DB::ConfigHistory => ConfigHistoryDB::get_instance().get_database(),
+        DB::Cooldowns     => CooldownsDB::get_instance().get_database(),
+        DB::CommandUses   => CommandUsesDB::get_instance().get_database(),
+        DB::Locales       => LocalesDB::get_instance().get_database(),
+    }
+}
+
+// Soft deletes the records of a table that match the query within the guild.
+// They are moved to the removals table, so they no longer count towards
+// anything but can still be looked up. Returns the removed records.
+pub async fn remove_records(table: &DB, scope: impl Into<Scope>, query: &Query, staff_id: &str, reason: &str) -> DBResult<Vec<RemovalLog>> {
+    let scope = scope.into();
+    let storage = get_storage(table);
+    let mut removed = Vec::new();
+    for row in storage.query_rows(&query.clone().scope(scope)).await? {
+        let id = row.id;
+        let log = RemovalLog::remove(table, row, storage.columns(), staff_id, reason);
+
+        // the record is never lost, it is only deleted with its tombstone
+        storage.delete_logged(id, scope, &log.key, LogRow::new(get_storage(&DB::Removals), log.clone())).await?;
+        removed.push(log);
+    }
+    Ok(removed)
+}
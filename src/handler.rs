@@ -3,7 +3,7 @@ use serenity::async_trait;
 use serenity::model::channel::Message;
 use serenity::model::user::User;
 use serenity::model::guild::Member;
-use serenity::all::{ChannelId, MessageId, GuildId, RoleId, MessageUpdateEvent, CreateEmbedFooter, EditChannel};
+use serenity::all::{ChannelId, MessageId, GuildId, RoleId, MessageUpdateEvent, CreateEmbedFooter, EditChannel, Interaction};
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
 use difference::{Difference, Changeset};
@@ -31,59 +31,52 @@ impl Handler {
         }
     }
 
-}
-
-#[async_trait]
-impl EventHandler for Handler {
-
-    async fn ready(&self, ctx: Context, _ready: Ready) {
-
-        #[cfg(feature = "debug")]
-        Logger::info("Bot is ready!");
-
-        let main_guild = ConfigDB::get_instance()
-            .get("guild_main").await.unwrap().to_string();
-        let guild_id = GuildId::from_str(&main_guild).unwrap();
-        let resolver = Resolver::new(ctx, Some(guild_id));
-
-        #[cfg(feature = "tickets")]
-        TicketHandler::get_instance()
-            .init(&resolver).await;
-
-        spawn(periodic_checks(resolver.clone())).await;
-    }
-
-    async fn message(&self, ctx: Context, msg: Message) {
+    // Everything the bot does with a message, no matter where it came from,
+    // e.g. the gateway or the console of the `simulate` feature.
+    pub async fn handle_message(&self, resolver: Resolver, msg: Message) {
 
         // parse message
-        let resolver = Resolver::new(ctx, msg.guild_id);
         let mut message = Arc::new(MessageManager::new(resolver, msg).await);
+        let scope = Scope::from(message.as_ref());
+        let config = match BotConfig::get(scope).await {
+            Ok(config) => config,
+
+            // Commands still run in guilds, the ones that need the configuration
+            // report its problems, so it can be repaired with `config`. Direct
+            // messages have no configuration of their own.
+            Err(_) => {
+                #[cfg(feature = "commands")]
+                if scope != Scope::Global && message.is_command() {
+                    self.command_manager.execute(&message).await;
+                }
+                return;
+            }
+        };
 
         // if message pings the bot
-        let bot_id = &ConfigDB::get_instance().get("bot_id").await.unwrap().to_string();
-        let bot_pings = vec![format!("<@!{}>", bot_id), format!("<@{}>",  bot_id)];
+        let bot_pings = [format!("<@!{}>", config.bot_id), format!("<@{}>",  config.bot_id)];
         if bot_pings.contains(&message.payload(None, None)) {
-            let prefix = ConfigDB::get_instance().get("command_prefix").await.unwrap().to_string();
-            message = message.spoof(format!("{}about", prefix)).await.into();
+            message = message.spoof(format!("{}about", config.command_prefix)).await.into();
         }
 
         // directly delete messages in the verify channel
-        let channel_verify: ChannelId = ConfigDB::get_instance()
-            .get("channel_verify").await.unwrap().into();
-        if message.get_channel() == channel_verify {
+        if message.get_channel() == config.channel_verify {
             message.delete().await;
         }
 
         // check if author is afk
         let author = &message.get_author();
         let author_id = &author.id.to_string();
-        let author_afk = AfkDB::get_instance().get(author_id).await;
+        let author_afk = AfkDB::get_instance().scope(scope).get(author_id).await;
         if author_afk.is_ok() {
+            let description = message.text("afk.removed").await;
             let embed = MessageManager::create_embed(|embed| {
-                embed.description("Removed your afk.")
+                embed.description(description)
             }).await;
             let _ = message.reply_temporary(embed).await;
-            AfkDB::get_instance().delete(&author_id).await;
+            if let Err(err) = AfkDB::get_instance().scope(scope).delete(author_id).await {
+                Logger::err_long("Failed to remove afk", &err.to_string());
+            }
         }
 
         // check if message mentions an afk user
@@ -96,13 +89,13 @@ impl EventHandler for Handler {
             .for_each_concurrent(None, |mention| {
                 let message = Arc::clone(&message);
                 async move {
-                    let mention_afk = AfkDB::get_instance().get(&mention.to_string()).await;
+                    let mention_afk = AfkDB::get_instance().scope(scope).get(&mention.to_string()).await;
                     if let Ok(afk) = mention_afk {
+                        let description = message.text(text!("afk.notice",
+                            user = format!("<@{}>", mention.to_string()),
+                            reason = afk.to_string())).await;
                         let embed = MessageManager::create_embed(|embed| {
-                            embed.description(
-                                &format!("<@{}> is currently afk `>` {}",
-                                    mention.to_string(),
-                                    afk.to_string()))
+                            embed.description(description)
                         }).await;
                         message.reply_temporary(embed).await;
                     }
@@ -111,7 +104,7 @@ impl EventHandler for Handler {
 
         // check guideline violations
         let filter = ChatFilter::get_instance().apply(&message).await;
-        if filter.filter_type == FilterType::Fine || message.is_trial().await || author.bot {
+        if filter.filter_type == FilterType::Fine || message.has_level(PermissionLevel::Trial).await || author.bot {
 
             // react to welcome messages
             if message.payload(None, None).to_lowercase().contains("welcome") {
@@ -132,11 +125,59 @@ impl EventHandler for Handler {
 
                 message.delete().await;
                 AutoModerator::get_instance()
-                    .perform_warn(&message, &author, filter.filter_type.to_string(), filter.context).await;
+                    .perform_warn(&message, author, filter.filter_type.to_string(), filter.context).await;
             }
         }
     }
 
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
+
+        #[cfg(feature = "debug")]
+        Logger::info("Bot is ready!");
+
+        Logger::info_long("Connected to guilds", &ready.guilds.len().to_string());
+
+        // slash commands are registered per guild, so they are available right away
+        #[cfg(feature = "commands")]
+        {
+            let commands = self.command_manager.application_commands();
+            for guild in &ready.guilds {
+                if let Err(err) = guild.id.set_commands(&ctx.http, commands.clone()).await {
+                    Logger::err_long("Failed to register slash commands", &err.to_string());
+                }
+            }
+        }
+
+        // every guild has its own tickets and configuration
+        #[cfg(feature = "tickets")]
+        for guild in &ready.guilds {
+            let resolver = Resolver::new(ctx.clone(), Some(guild.id));
+            TicketHandler::get_instance()
+                .init(&resolver).await;
+        }
+
+        spawn(periodic_checks(ctx)).await;
+    }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        let resolver = Resolver::new(ctx, msg.guild_id);
+        self.handle_message(resolver, msg).await;
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+
+        // components are handled by whoever awaits them
+        #[cfg(feature = "commands")]
+        if let Interaction::Command(command) = interaction {
+            self.command_manager.execute_interaction(ctx, command).await;
+        }
+    }
+
     async fn guild_member_addition(&self,
                                    ctx: Context,
                                    new_member: Member
@@ -147,8 +188,13 @@ impl EventHandler for Handler {
         let guild = resolver.resolve_guild(None).await;
 
         // get member count channel
-        let channel: ChannelId = ConfigDB::get_instance()
-            .get("channel_member_count").await.unwrap().into();
+        let channel: ChannelId = match BotConfig::get(guild_id).await {
+            Ok(config) => config.channel_member_count,
+            Err(err) => {
+                Logger::err_long("Failed to get member count channel", &err.to_string());
+                return;
+            }
+        };
 
         // update channel name
         if guild.is_some() {
@@ -166,11 +212,14 @@ impl EventHandler for Handler {
                                   member_data_if_available: Option<Member>,
     ) {
         let resolver = Resolver::new(ctx, Some(guild_id));
-        let role_muted = &resolver.resolve_role("Muted").await.unwrap()[0];
+        let role_muted = match BotConfig::get(guild_id).await {
+            Ok(config) => config.role_muted,
+            Err(err) => return Logger::err_long("Failed to get muted role", &err.to_string()),
+        };
 
         // determine if user left while being muted
         let left_while_muted = match member_data_if_available {
-            Some(member) => member.roles.contains(&role_muted.id),
+            Some(member) => role_muted.is_some_and(|role| member.roles.contains(&role)),
             None => {
 
                 // wait 5 seconds to allow database to update
@@ -178,9 +227,9 @@ impl EventHandler for Handler {
 
                 // get last mute, unmute, and ban
                 let id = user.id.to_string();
-                let last_mute = MutesDB::get_instance().get_last(&id, 1).await.unwrap();
-                let last_unmute = UnmutesDB::get_instance().get_last(&id, 1).await.unwrap();
-                let last_ban = BansDB::get_instance().get_last(&id, 1).await.unwrap();
+                let last_mute = MutesDB::get_instance().scope(guild_id).get_last(&id, 1).await.unwrap_or_default();
+                let last_unmute = UnmutesDB::get_instance().scope(guild_id).get_last(&id, 1).await.unwrap_or_default();
+                let last_ban = BansDB::get_instance().scope(guild_id).get_last(&id, 1).await.unwrap_or_default();
 
                 let mut left_while_muted = false;
 
@@ -204,8 +253,10 @@ impl EventHandler for Handler {
 
         // if user left while being muted, ban user
         if left_while_muted {
+            let reason = Text::new("auto-moderation.left-while-muted")
+                .localize(Locale::of_guild(&resolver).await);
             AutoModerator::get_instance()
-                .perform_ban(&resolver, &user, "Left while being muted.".to_string()).await;
+                .perform_ban(&resolver, &user, reason).await;
         }
     }
 
@@ -219,14 +270,17 @@ impl EventHandler for Handler {
     ) {
 
         // get all excluded channels
-        let channel_protected_log: Vec<_> = ConfigDB::get_instance()
-            .get_all("channel_protected_log").await.unwrap()
-            .iter()
-            .map(|entry| entry.value.to_string())
-            .collect();
+        let channel_protected_log = match BotConfig::get(event.guild_id).await {
+            Ok(config) if !config.channel_protected_log.is_empty() => config.channel_protected_log.clone(),
+            Ok(_) => return,
+            Err(err) => {
+                Logger::err_long("Failed to get protected channels", &err.to_string());
+                return;
+            }
+        };
 
         // do not log messages from protected channels
-        if channel_protected_log.contains(&event.channel_id.to_string()) {
+        if channel_protected_log.contains(&event.channel_id) {
             return;
         }
 
@@ -242,9 +296,9 @@ impl EventHandler for Handler {
 
             let name = message.resolve_name();
             let log_builder = message.get_log_builder()
-                .title(&format!("{}'s Message Edited", name))
-                .labeled_timestamp("Sent", message.get_timestamp())
-                .labeled_timestamp("Edited", chrono::Utc::now().timestamp())
+                .title(text!("message-log.edited-title", user = name))
+                .labeled_timestamp("message-log.sent", message.get_timestamp())
+                .labeled_timestamp("message-log.edited", chrono::Utc::now().timestamp())
                 .channel();
 
             let diff_string = match old_if_available {
@@ -265,21 +319,21 @@ impl EventHandler for Handler {
                     diff.push("```".to_string());
                     diff.join("\n")
                 },
-                None => "Content of original message is not available.".to_string(),
+                None => message.guild_text("message-log.original-unavailable").await,
             };
 
             // add additional fields
+            let content_label = message.guild_text("message-log.content").await;
+            let footer = message.guild_text(text!("message-log.user-id", id = message.get_author().id)).await;
             let mut log_message = log_builder.build().await
-                .field("Message Content", diff_string, true)
-                .footer(CreateEmbedFooter::new(
-                    format!("User ID: {}", message.get_author().id)));
+                .field(content_label, diff_string, true)
+                .footer(CreateEmbedFooter::new(footer));
             for attachment in message.get_attachments().await.iter() {
                 log_message = log_message.image(attachment.url.clone());
             }
  
             // send log message
-            let channel = channel_protected_log[0].clone();
-            let channel = ChannelId::from_str(&channel).unwrap();
+            let channel = channel_protected_log[0];
             let _ = channel.send_message(message, log_message.to_message()).await;
         }
 
@@ -294,21 +348,23 @@ impl EventHandler for Handler {
     ) {
 
         // get all excluded channels
-        let channel_protected_log: Vec<_> = ConfigDB::get_instance()
-            .get_all("channel_protected_log").await.unwrap()
-            .iter()
-            .map(|entry| entry.value.to_string())
-            .collect();
+        let channel_protected_log = match BotConfig::get(guild_id).await {
+            Ok(config) if !config.channel_protected_log.is_empty() => config.channel_protected_log.clone(),
+            Ok(_) => return,
+            Err(err) => {
+                Logger::err_long("Failed to get protected channels", &err.to_string());
+                return;
+            }
+        };
 
         // do not log messages from protected channels
-        if channel_protected_log.contains(&channel_id.to_string()) {
+        if channel_protected_log.contains(&channel_id) {
             return;
         }
 
         // obtain Message object
         let resolver = Resolver::new(ctx, guild_id);
-        let channel_messagelogs = channel_protected_log[0].clone();
-        let channel_messagelogs = ChannelId::from_str(&channel_messagelogs).unwrap();
+        let channel_messagelogs = channel_protected_log[0];
         let message = resolver.resolve_message(channel_id, deleted_message_id).await;
 
         // cannot continue if message cannot be resolved
@@ -323,9 +379,9 @@ impl EventHandler for Handler {
 
             let name = resolver.resolve_name(message.get_author());
             let mut log_builder = message.get_log_builder()
-                .title(&format!("{}'s Message Deleted", name))
-                .labeled_timestamp("Sent", message.get_timestamp())
-                .labeled_timestamp("Deleted", chrono::Utc::now().timestamp())
+                .title(text!("message-log.deleted-title", user = name))
+                .labeled_timestamp("message-log.sent", message.get_timestamp())
+                .labeled_timestamp("message-log.deleted", chrono::Utc::now().timestamp())
                 .channel();
 
             // split message content into chunks of 1024 because of Discord embed field limit
@@ -333,13 +389,13 @@ impl EventHandler for Handler {
             let chunks = chars.chunks(1024).collect::<Vec<_>>();
             for chunk in chunks.into_iter() {
                 let content = chunk.into_iter().collect::<String>();
-                log_builder = log_builder.arbitrary("Message Content", &content);
+                log_builder = log_builder.arbitrary("message-log.content", &content);
             }
 
             // add additional fields
+            let footer = message.guild_text(text!("message-log.user-id", id = message.get_author().id)).await;
             let mut log_message = log_builder.build().await
-                .footer(CreateEmbedFooter::new(
-                    format!("User ID: {}", message.get_author().id)));
+                .footer(CreateEmbedFooter::new(footer));
             for attachment in message.get_attachments().await.iter() {
                 log_message = log_message.image(attachment.url.clone());
             }
@@ -18,13 +18,17 @@ mod handler;
 mod utility;
 mod databases;
 mod commands;
+#[cfg(feature = "simulate")]
+mod simulation;
 
 
+#[cfg(not(feature = "simulate"))]
 #[tokio::main]
 async fn main() {
 
     // setup
-    let token = setup().await;
+    setup().await;
+    let token = token().await;
     let command_handler = CommandManager::new();
     let handler = Handler::new(command_handler);
 
@@ -52,20 +56,50 @@ async fn main() {
     let _ = client.start().await;
 }
 
+// rehearse with a guild in memory instead of connecting to Discord
+#[cfg(feature = "simulate")]
+#[tokio::main]
+async fn main() {
+    let guild = simulation::prepare().await;
+    setup().await;
+    let handler = Handler::new(CommandManager::new());
+    simulation::simulation(handler, guild).await;
+}
 
-async fn setup() -> String {
-    let token = fs::read_to_string("token.txt").unwrap();
-    let config = ConfigDB::get_instance();
+
+async fn setup() {
+
+    // migrate all databases before anything reads from them
+    initialize_databases();
+
+    // guilds that do not set a value fall back to the global configuration
+    let config = ConfigDB::get_instance().global();
 
     // initialize executed_commands and command_prefix
-    if !config.has("executed_commands").await {
-        config.set("executed_commands", "0").await;
+    // (the bot cannot run without its config, so failing here is fatal)
+    if !config.has("executed_commands").await.expect("Failed to read config") {
+        config.set("executed_commands", "0").await.expect("Failed to write config");
+    }
+    if !config.has("command_prefix").await.expect("Failed to read config") {
+        config.set("command_prefix", "?").await.expect("Failed to write config");
     }
-    if !config.has("command_prefix").await {
-        config.set("command_prefix", "?").await;
+
+    // refuse to start with a configuration that commands would fail on later
+    let reports = config::validate().await.expect("Failed to read config");
+    if !reports.is_empty() {
+        for report in &reports {
+            Logger::err(&report.to_string());
+        }
+        std::process::exit(1);
     }
 
-    config.set("uptime", &chrono::Utc::now().timestamp().to_string()).await;
-    config.set("token", &token).await;
-    config.get("token").await.unwrap().to_string()
+    config.set("uptime", &chrono::Utc::now().timestamp().to_string()).await.expect("Failed to write config");
+}
+
+#[cfg(not(feature = "simulate"))]
+async fn token() -> String {
+    let token = fs::read_to_string("token.txt").unwrap();
+    let config = ConfigDB::get_instance().global();
+    config.set("token", &token).await.expect("Failed to write config");
+    config.get("token").await.expect("Failed to read config").to_string()
 }
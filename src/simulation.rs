@@ -0,0 +1,319 @@
+use serenity::model::prelude::*;
+use serenity::builder::{CreateChannel, CreateMessage};
+use serenity::all::{Cache, Http, ShardMessenger};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::handler::Handler;
+use crate::utility::*;
+use crate::databases::*;
+
+
+// The guild of the simulation, configured before the configuration is
+// validated. Nothing of the simulation is written to the databases on disk,
+// they start out empty or with the backup given as the first argument, e.g.
+// the export of the running bot. The guild keeps its own channels and roles.
+pub async fn prepare() -> Arc<FakeGuild> {
+    use_backend(Backend::Memory).expect("Failed to select storage");
+    if let Some(path) = std::env::args().nth(1) {
+        let content = std::fs::read_to_string(&path).expect("Failed to read backup");
+        let count = archive::import_json(None, &content).await.expect("Failed to import backup");
+        Logger::info_long("Imported rows", &count.to_string());
+    }
+    FakeGuild::populated("Simulation").await.expect("Failed to configure guild")
+}
+
+// Runs the bot against a guild in memory. The operator writes chat messages
+// as one of the guild's users, which are handled exactly like messages from
+// Discord, and everything the bot does in return is printed.
+pub async fn simulation(handler: Handler, guild: Arc<FakeGuild>) {
+    let console = Arc::new(Console { guild: guild.clone() });
+    let resolver = Resolver::with_discord(console, Some(guild.guild_id));
+    #[cfg(feature = "tickets")]
+    TicketHandler::get_instance().init(&resolver).await;
+
+    let handler = Arc::new(handler);
+    let mut author = guild.user_named("member");
+    let mut channel = guild.channel_named("general");
+    Logger::info_long("Simulating guild", &guild.guild_id.to_string());
+    Logger::info_long("Commands", "/as <user>, /in <channel>, /users, /channels, /db, /quit");
+
+    loop {
+        let channel_name = guild.channel(channel).map(|channel| channel.name).unwrap_or_default();
+        let input = Logger::input(&format!("{} in #{}", author.name, channel_name));
+        let words = input.split_whitespace().collect::<Vec<&str>>();
+
+        match words.first().copied() {
+            None => continue,
+            Some("/as") => match words.get(1) {
+                Some(name) => match guild.users().into_iter().find(|user| user.name == *name) {
+                    Some(user) => author = user,
+                    None => Logger::warn("Invalid user"),
+                },
+                None => Logger::warn("Too few parameters"),
+            },
+            Some("/in") => match words.get(1) {
+                Some(name) => match guild.text_channels().into_iter().find(|channel| channel.name == *name) {
+                    Some(found) => channel = found.id,
+                    None => Logger::warn("Invalid channel"),
+                },
+                None => Logger::warn("Too few parameters"),
+            },
+            Some("/users") => {
+                // mentioned as <@ID>
+                let roles = guild.roles(guild.guild_id).await.unwrap_or_default();
+                let mut users = guild.users();
+                users.sort_by(|a, b| a.name.cmp(&b.name));
+                for user in users.into_iter().filter(|user| !user.bot) {
+                    let names = guild.member_roles(user.id).iter()
+                        .filter_map(|id| roles.iter().find(|role| role.id == *id))
+                        .map(|role| role.name.clone())
+                        .collect::<Vec<String>>();
+                    Logger::info_long(&format!("{} ({})", user.name, user.id), &names.join(", "));
+                }
+            }
+            Some("/channels") => {
+                let mut channels = guild.text_channels().into_iter()
+                    .map(|channel| channel.name)
+                    .collect::<Vec<String>>();
+                channels.sort();
+                Logger::info_long("Channels", &channels.join(", "));
+            }
+            // edit the databases until `exit`, e.g. to try another configuration
+            #[cfg(feature = "db_interface")]
+            Some("/db") => database_interface().await,
+            Some("/quit") => break,
+            Some(command) if command.starts_with('/') => Logger::err("Invalid command"),
+            Some(_) => {
+                let message = guild.send_as(channel, &author, &input);
+                let handler = handler.clone();
+                let resolver = resolver.clone();
+                let handling = tokio::spawn(async move {
+                    handler.handle_message(resolver, message).await;
+                });
+
+                // temporary replies are deleted a few seconds later,
+                // the prompt does not wait for that
+                let _ = tokio::time::timeout(Duration::from_secs(1), handling).await;
+            }
+        }
+    }
+}
+
+// The guild of the simulation, printing every change the bot makes to it.
+struct Console {
+    guild: Arc<FakeGuild>,
+}
+
+impl Console {
+
+    fn inner(&self) -> &dyn Discord {
+        self.guild.as_ref()
+    }
+
+    fn channel_name(&self, channel_id: ChannelId) -> String {
+        self.guild.channel(channel_id)
+            .map(|channel| format!("#{}", channel.name))
+            .unwrap_or(channel_id.to_string())
+    }
+
+    async fn user_name(&self, user_id: UserId) -> String {
+        self.inner().user(user_id).await
+            .map(|user| user.name)
+            .unwrap_or(user_id.to_string())
+    }
+
+    async fn role_name(&self, role_id: RoleId) -> String {
+        self.inner().roles(self.guild.guild_id).await.unwrap_or_default().into_iter()
+            .find(|role| role.id == role_id)
+            .map(|role| role.name)
+            .unwrap_or(role_id.to_string())
+    }
+
+    async fn overwrite_name(&self, kind: PermissionOverwriteType) -> String {
+        match kind {
+            PermissionOverwriteType::Member(user_id) => self.user_name(user_id).await,
+            PermissionOverwriteType::Role(role_id) if role_id.get() == self.guild.guild_id.get() => "@everyone".to_string(),
+            PermissionOverwriteType::Role(role_id) => self.role_name(role_id).await,
+            _ => "unknown".to_string(),
+        }
+    }
+
+    // the content of a message and its embeds, one line each
+    fn print_message(label: &str, message: &Message) {
+        let mut lines = Vec::new();
+        if !message.content.is_empty() {
+            lines.push(message.content.clone());
+        }
+        for embed in &message.embeds {
+            let title = embed.title.clone()
+                .or(embed.author.as_ref().map(|author| author.name.clone()));
+            lines.extend(title.map(|title| format!("| {}", title)));
+            lines.extend(embed.description.iter().map(|description| format!("| {}", description)));
+            lines.extend(embed.fields.iter().map(|field| format!("| {}: {}", field.name, field.value)));
+            lines.extend(embed.footer.as_ref().map(|footer| format!("| {}", footer.text)));
+        }
+        Logger::info_long(label, &lines.join("\n    "));
+    }
+}
+
+impl Discord for Console {
+
+    fn guild(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Guild>> {
+        self.inner().guild(guild_id)
+    }
+
+    fn user(&self, user_id: UserId) -> BoxedFuture<'_, Result<User>> {
+        self.inner().user(user_id)
+    }
+
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<Member>> {
+        self.inner().member(guild_id, user_id)
+    }
+
+    fn roles(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<Role>>> {
+        self.inner().roles(guild_id)
+    }
+
+    fn channels(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<GuildChannel>>> {
+        self.inner().channels(guild_id)
+    }
+
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<Message>> {
+        self.inner().message(channel_id, message_id)
+    }
+
+    fn messages(&self, channel_id: ChannelId, before: MessageId, limit: u8) -> BoxedFuture<'_, Result<Vec<Message>>> {
+        self.inner().messages(channel_id, before, limit)
+    }
+
+    fn send_message(&self, channel_id: ChannelId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            let sent = self.inner().send_message(channel_id, message).await?;
+            Console::print_message(&format!("Bot in {}", self.channel_name(channel_id)), &sent);
+            Ok(sent)
+        })
+    }
+
+    fn direct_message(&self, user_id: UserId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            let sent = self.inner().direct_message(user_id, message).await?;
+            Console::print_message(&format!("Bot to {}", self.user_name(user_id).await), &sent);
+            Ok(sent)
+        })
+    }
+
+    fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<()>> {
+        self.delete_messages(channel_id, vec![message_id])
+    }
+
+    fn delete_messages(&self, channel_id: ChannelId, message_ids: Vec<MessageId>) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+
+            // the bot cleaning up its own replies is not worth mentioning
+            let mut deleted = Vec::new();
+            for message_id in &message_ids {
+                if let Ok(message) = self.inner().message(channel_id, *message_id).await {
+                    if message.author.id != self.guild.bot.id {
+                        deleted.push(message);
+                    }
+                }
+            }
+
+            self.inner().delete_messages(channel_id, message_ids).await?;
+            for message in deleted {
+                let label = format!("Deleted from {} in {}", message.author.name, self.channel_name(channel_id));
+                Console::print_message(&label, &message);
+            }
+            Ok(())
+        })
+    }
+
+    fn add_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.inner().add_role(guild_id, user_id, role_id).await?;
+            Logger::info_long("Added role", &format!("{} to {}", self.role_name(role_id).await, self.user_name(user_id).await));
+            Ok(())
+        })
+    }
+
+    fn remove_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.inner().remove_role(guild_id, user_id, role_id).await?;
+            Logger::info_long("Removed role", &format!("{} from {}", self.role_name(role_id).await, self.user_name(user_id).await));
+            Ok(())
+        })
+    }
+
+    fn create_channel<'a>(&'a self, guild_id: GuildId, channel: CreateChannel<'a>) -> BoxedFuture<'a, Result<GuildChannel>> {
+        Box::pin(async move {
+            let created = self.inner().create_channel(guild_id, channel).await?;
+            Logger::info_long("Created channel", &format!("#{}", created.name));
+            Ok(created)
+        })
+    }
+
+    fn delete_channel(&self, channel_id: ChannelId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let name = self.channel_name(channel_id);
+            self.inner().delete_channel(channel_id).await?;
+            Logger::info_long("Deleted channel", &name);
+            Ok(())
+        })
+    }
+
+    fn create_permission(&self, channel_id: ChannelId, overwrite: PermissionOverwrite) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let permissions = [("allow", overwrite.allow), ("deny", overwrite.deny)].into_iter()
+                .filter(|(_, permissions)| !permissions.is_empty())
+                .map(|(label, permissions)| format!("{} {}", label, permissions.get_permission_names().join(", ")))
+                .collect::<Vec<String>>();
+            let change = format!("{} in {}: {}",
+                self.overwrite_name(overwrite.kind).await,
+                self.channel_name(channel_id),
+                permissions.join("; "));
+            self.inner().create_permission(channel_id, overwrite).await?;
+            Logger::info_long("Changed permissions", &change);
+            Ok(())
+        })
+    }
+
+    fn delete_permission(&self, channel_id: ChannelId, kind: PermissionOverwriteType) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let change = format!("{} in {}", self.overwrite_name(kind).await, self.channel_name(channel_id));
+            self.inner().delete_permission(channel_id, kind).await?;
+            Logger::info_long("Reset permissions", &change);
+            Ok(())
+        })
+    }
+
+    fn ban<'a>(&'a self, guild_id: GuildId, user_id: UserId, reason: &'a str) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let name = self.user_name(user_id).await;
+            self.inner().ban(guild_id, user_id, reason).await?;
+            Logger::info_long("Banned", &format!("{} for {}", name, reason));
+            Ok(())
+        })
+    }
+
+    fn unban(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.inner().unban(guild_id, user_id).await?;
+            Logger::info_long("Unbanned", &self.user_name(user_id).await);
+            Ok(())
+        })
+    }
+
+    fn http(&self) -> &Arc<Http> {
+        self.inner().http()
+    }
+
+    fn cache(&self) -> Option<&Arc<Cache>> {
+        self.inner().cache()
+    }
+
+    fn shard(&self) -> Option<&ShardMessenger> {
+        self.inner().shard()
+    }
+}
@@ -0,0 +1,184 @@
+use serenity::model::id::{ChannelId, RoleId, UserId};
+use regex::Regex;
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use crate::utility::*;
+
+
+// The kind of value a parameter of a usage accepts.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArgumentType {
+    User,
+    // no command takes roles or channels yet
+    #[allow(unused)]
+    Role,
+    #[allow(unused)]
+    Channel,
+    Duration,
+    Integer,
+    Text,
+    Flag,
+}
+
+impl ArgumentType {
+
+    // the message that tells which value was expected instead of a word
+    pub fn expectation(&self) -> &'static str {
+        match self {
+            ArgumentType::User     => "argument.expected-user",
+            ArgumentType::Role     => "argument.expected-role",
+            ArgumentType::Channel  => "argument.expected-channel",
+            ArgumentType::Duration => "argument.expected-duration",
+            ArgumentType::Integer  => "argument.expected-integer",
+            ArgumentType::Text     => "argument.expected-text",
+            ArgumentType::Flag     => "argument.expected-flag",
+        }
+    }
+
+    // Parses a single word of a message, text is taken as it is.
+    pub fn parse(&self, word: &str) -> Option<Argument> {
+        match self {
+            ArgumentType::User     => parse_id(word, &["@!", "@"]).map(|id| Argument::User(UserId::new(id))),
+            ArgumentType::Role     => parse_id(word, &["@&"]).map(|id| Argument::Role(RoleId::new(id))),
+            ArgumentType::Channel  => parse_id(word, &["#"]).map(|id| Argument::Channel(ChannelId::new(id))),
+            ArgumentType::Duration => parse_duration(word).map(Argument::Duration),
+            ArgumentType::Integer  => word.parse::<i64>().ok().map(Argument::Integer),
+            ArgumentType::Text     => Some(Argument::Text(word.to_string())),
+            ArgumentType::Flag     => None,
+        }
+    }
+}
+
+// Either a mention like `<@123>` with one of the given prefixes or a raw ID.
+fn parse_id(word: &str, prefixes: &[&str]) -> Option<u64> {
+    let id = match word.strip_prefix('<').and_then(|word| word.strip_suffix('>')) {
+        Some(mention) => prefixes.iter().find_map(|prefix| mention.strip_prefix(prefix))?,
+        None => word,
+    };
+    if !(17..=20).contains(&id.len()) || !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    id.parse::<u64>().ok().filter(|id| *id != 0)
+}
+
+lazy_static! {
+    // Only words that consist of nothing but durations, parse_time would
+    // also find the `5m` in `abc5m`.
+    static ref DURATION: Regex = Regex::new(r"^(?i)(\d+[dhms])+$").unwrap();
+}
+
+fn parse_duration(word: &str) -> Option<u64> {
+    match DURATION.is_match(word) {
+        true  => parse_time(word).ok(),
+        false => None,
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Argument {
+    User(UserId),
+    Role(RoleId),
+    Channel(ChannelId),
+    Duration(u64),
+    Integer(i64),
+    Text(String),
+    Flag,
+}
+
+// The arguments of a command, by the names of their parameters. Flags are
+// named without their dash, e.g. `-monthly` is found as `monthly`.
+#[derive(Clone, Default, Debug)]
+pub struct Arguments {
+    values: HashMap<String, Argument>,
+}
+
+impl Arguments {
+
+    pub fn insert(&mut self, name: impl Into<String>, argument: Argument) {
+        self.values.insert(name.into(), argument);
+    }
+
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn user(&self, name: &str) -> Option<UserId> {
+        match self.values.get(name) {
+            Some(Argument::User(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn role(&self, name: &str) -> Option<RoleId> {
+        match self.values.get(name) {
+            Some(Argument::Role(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn channel(&self, name: &str) -> Option<ChannelId> {
+        match self.values.get(name) {
+            Some(Argument::Channel(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn duration(&self, name: &str) -> Option<u64> {
+        match self.values.get(name) {
+            Some(Argument::Duration(seconds)) => Some(*seconds),
+            _ => None,
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Option<i64> {
+        match self.values.get(name) {
+            Some(Argument::Integer(number)) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(Argument::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+// Why the words of a command do not fit its usage. The position is the
+// index of the offending word among the words after the trigger, a position
+// past the last word means that something is missing.
+#[derive(Clone, Debug)]
+pub struct ArgumentError {
+    pub position: usize,
+    pub reason: Text,
+}
+
+impl ArgumentError {
+
+    pub fn new(position: usize, reason: impl Into<Text>) -> ArgumentError {
+        ArgumentError { position, reason: reason.into() }
+    }
+
+    // The command as it was written with the reason below the offending
+    // word, the words start with the trigger of the command.
+    pub fn pointer(&self, words: &[String], locale: Locale) -> String {
+        let line = words.join(" ");
+        let column = words.iter()
+            .take(self.position + 1)
+            .map(|word| word.chars().count() + 1)
+            .sum::<usize>();
+        let width = words.get(self.position + 1)
+            .map_or(1, |word| word.chars().count());
+        format!("{}\n{}{} {}", line, " ".repeat(column), "^".repeat(width), self.reason.localize(locale))
+            .replace('`', "'")
+    }
+}
@@ -30,7 +30,7 @@ impl AutoModerator {
     pub async fn check_warnings(&self, message: &MessageManager, target: &User) {
 
         // get timestamp of last mute
-        let last_mute = MutesDB::get_instance()
+        let last_mute = MutesDB::get_instance().scope(message)
             .get_last(&target.id.to_string(), 1).await;
         let mut last_mute_timestamp = 0;
         if let Ok(last_mute) = last_mute {
@@ -40,9 +40,11 @@ impl AutoModerator {
         }
 
         // get all warnings since last mute
-        let recent_warnings = &WarningsDB::get_instance()
-            .query(&target.id.to_string(),
-                   &format!("AND timestamp > {} LIMIT 3", last_mute_timestamp)).await;
+        let recent_warnings = &WarningsDB::get_instance().scope(message)
+            .query(Query::new()
+                .key(target.id.to_string())
+                .after(last_mute_timestamp)
+                .limit(3)).await;
 
         if let Ok(recent_warnings) = recent_warnings {
 
@@ -62,7 +64,7 @@ impl AutoModerator {
         let resolver = message.get_resolver();
 
         // get all flags
-        let all_flags = FlagsDB::get_instance()
+        let all_flags = FlagsDB::get_instance().scope(message)
             .get_all(&target.id.to_string()).await;
 
         if let Ok(all_flags) = all_flags {
@@ -83,24 +85,30 @@ impl AutoModerator {
                 let target_id = target.id.to_string();
 
                 // create embed
+                let locale = Locale::of_guild(message).await;
+                let title = Text::new("auto-moderation.flag-notice-title").localize(locale);
+                let description = text!("auto-moderation.flag-notice",
+                    user = format!("<@{}>", target_id),
+                    reason = flag_reason).localize(locale);
                 let embed = MessageManager::create_embed(|embed| {
                     embed
-                        .title("Flag Notice")
-                        .description(format!("<@{}> is currently flagged `>` {}", target_id, flag_reason))
+                        .title(title)
+                        .description(description)
                         .color(0xFF0000)
                 }).await;
 
-                // distribute responsibility
-                let role_ids = &resolver.resolve_role(vec!["Administrator", "Head Moderator"]).await.unwrap();
-                let responsibility = format!("<@&{}> <@&{}>", role_ids[0].id, role_ids[1].id);
-
-                // get muted channel
-                let channel: ChannelId = ConfigDB::get_instance()
-                    .get("channel_muted").await.unwrap().into();
+                // get muted channel and distribute responsibility
+                let (channel, responsibility): (ChannelId, String) = match BotConfig::get(message).await {
+                    Ok(config) => (config.channel_muted, config.roles_from(PermissionLevel::HeadMod).iter()
+                        .map(|role| format!("<@&{}>", role))
+                        .collect::<Vec<String>>()
+                        .join(" ")),
+                    Err(err) => return Logger::err_long("Failed to get muted channel", &err.to_string()),
+                };
 
                 // send flag notice
-                let _ = channel.send_message(resolver, responsibility.to_message()).await;
-                let _ = channel.send_message(resolver, embed.to_message()).await;
+                let _ = resolver.send_message(channel, responsibility).await;
+                let _ = resolver.send_message(channel, embed).await;
             }
         }
     }
@@ -113,33 +121,39 @@ impl AutoModerator {
         let target_id = target.id.to_string();
 
         // warn user
-        let warn_message = format!("<@{}>, you have been **automatically warned** `>` {}",
-            target_id,
-            reason);
+        let warn_message = text!("auto-moderation.warned",
+            user = format!("<@{}>", target_id),
+            reason = reason).localize(Locale::of_user(message, target.id).await);
         let _ = message.reply(warn_message.to_message()).await;
 
-        let bot_id = ConfigDB::get_instance()
-            .get("bot_id").await.unwrap().to_string();
+        let bot_id = match BotConfig::get(message).await {
+            Ok(config) => config.bot_id.to_string(),
+            Err(err) => return Logger::err_long("Failed to get bot id", &err.to_string()),
+        };
 
         // log to database
         let log = ModLog::new(
             bot_id,
             context.clone()
         );
-        WarningsDB::get_instance().append(&target_id, &log.into()).await;
+        if let Err(err) = WarningsDB::get_instance().scope(message).append(&target_id, log).await {
+            return Logger::err_long("Failed to log warning", &err.to_string());
+        }
 
         // log to mod logs
         let log_message = message.get_log_builder()
-            .title("[AUTOMATIC WARNING]")
+            .title("auto-moderation.warning-log-title")
             .target(&target)
             .color(0xff8200)
             .user(&target)
-            .arbitrary("Reason", &context)
+            .arbitrary("log.reason", &context)
             .timestamp()
             .build().await;
-        let modlogs: ChannelId = ConfigDB::get_instance()
-            .get("channel_modlogs").await.unwrap().into();
-        let _ = modlogs.send_message(resolver, log_message.to_message()).await;
+        let modlogs: ChannelId = match BotConfig::get(message).await {
+            Ok(config) => config.channel_modlogs,
+            Err(err) => return Logger::err_long("Failed to get modlogs channel", &err.to_string()),
+        };
+        let _ = resolver.send_message(modlogs, log_message).await;
 
         // check if the user has been warned too many times
         self.check_warnings(message, &target).await;
@@ -150,97 +164,119 @@ impl AutoModerator {
         let resolver = message.get_resolver();
         let target_id = target.id.to_string();
 
+        let config = match BotConfig::get(message).await {
+            Ok(config) => config,
+            Err(err) => return Logger::err_long("Failed to get configuration", &err.to_string()),
+        };
+
         // mute user
-        let role_muted = &resolver.resolve_role("Muted").await.unwrap()[0];
-        let member = resolver.resolve_member(&target).await.unwrap();
-        member.add_role(&resolver, role_muted.id).await.unwrap();
+        let Some(role_muted) = config.role_muted else {
+            return Logger::err_long("Failed to mute automatically", "No muted role is configured");
+        };
+        resolver.add_role(target.id, role_muted).await.unwrap();
 
         // log mute to database
-        let bot_id = ConfigDB::get_instance().get("bot_id").await.unwrap().to_string();
+        let bot_id = config.bot_id.to_string();
         let log = ModLog::new(
             bot_id.clone(),
             reason.clone(),
         );
-        MutesDB::get_instance().append(&target.id.to_string(), &log.into()).await;
+        if let Err(err) = MutesDB::get_instance().scope(message).append(&target.id.to_string(), log).await {
+            Logger::err_long("Failed to log mute", &err.to_string());
+        }
 
         // log mute to modlogs
         let log_message = message.get_log_builder()
-            .title("[AUTOMATIC MUTE]")
+            .title("auto-moderation.mute-log-title")
             .target(&target)
             .user(&target)
-            .arbitrary("Reason", reason)
+            .arbitrary("log.reason", reason)
             .timestamp()
             .build().await;
-        let modlogs: ChannelId = ConfigDB::get_instance()
-            .get("channel_modlogs").await.unwrap().into();
-        let _ = modlogs.send_message(message.get_resolver(), log_message.to_message()).await;
+        let modlogs: ChannelId = match BotConfig::get(message).await {
+            Ok(config) => config.channel_modlogs,
+            Err(err) => return Logger::err_long("Failed to get modlogs channel", &err.to_string()),
+        };
+        let _ = resolver.send_message(modlogs, log_message).await;
 
         // check for active flags
         self.check_flags(message, target).await;
 
         // create embed
+        let locale = Locale::of_user(message, target.id).await;
+        let title = Text::new("auto-moderation.mute-title").localize(locale);
+        let description = Text::new("auto-moderation.muted").localize(locale);
         let embed = MessageManager::create_embed(|embed| {
             embed
-                .title("Automatic Mute")
-                .description(
-                    "You have been **automatically muted** because you reached **3** warnings. A staff member will shortly open a **ticket** with you to discuss your warnings. The staff member to delete this note should be the one to create the ticket.")
+                .title(title)
+                .description(description)
                 .color(0xFF0000)
         }).await;
 
         // find person responsible for the last warning (to ping them)
-        let last_warning = &WarningsDB::get_instance()
-            .get_last(&target_id, 1).await.unwrap()[0];
-        let role_automute = &resolver.resolve_role("Auto Mute").await.unwrap()[0];
-        let responsibility = match last_warning.staff_id == bot_id {
-            true  => format!("<@{}><@&{}>", target_id, role_automute.id),
-            false => format!("<@{}><@{}>",  target_id, last_warning.staff_id)
+        let last_warning = WarningsDB::get_instance().scope(message)
+            .get_last(&target_id, 1).await.unwrap_or_default();
+        let responsibility = match (last_warning.first(), config.role_auto_mute) {
+            (Some(warning), _) if warning.staff_id != bot_id => format!("<@{}><@{}>",  target_id, warning.staff_id),
+            (_, Some(role_auto_mute)) => format!("<@{}><@&{}>", target_id, role_auto_mute),
+            (_, None) => format!("<@{}>", target_id),
         };
 
         // get muted channel
-        let channel: ChannelId = ConfigDB::get_instance()
-            .get("channel_muted").await.unwrap().into();
+        let channel: ChannelId = match BotConfig::get(message).await {
+            Ok(config) => config.channel_muted,
+            Err(err) => return Logger::err_long("Failed to get muted channel", &err.to_string()),
+        };
 
         // send automute message
-        let _ = channel.send_message(resolver, responsibility.to_message()).await;
-        let _ = channel.send_message(resolver, embed.to_message()).await;
+        let _ = resolver.send_message(channel, responsibility).await;
+        let _ = resolver.send_message(channel, embed).await;
     }
 
     pub async fn perform_ban(&self, resolver: &Resolver, target: &User, reason: String) {
 
         // ban user
-        let guild = resolver.resolve_guild(None).await.unwrap();
-        let success = guild.ban_with_reason(&resolver, target.id, 0, &reason).await;
+        let success = resolver.ban(target.id, &reason).await;
 
         match success {
             Ok(_) => {
 
                 // log ban to database
-                let bot_id = ConfigDB::get_instance().get("bot_id").await.unwrap().to_string();
+                let bot_id = match BotConfig::get(resolver).await {
+                    Ok(config) => config.bot_id.to_string(),
+                    Err(err) => return Logger::err_long("Failed to get bot id", &err.to_string()),
+                };
                 let log = ModLog::new(
                     bot_id.clone(),
                     reason.clone(),
                 );
-                BansDB::get_instance().append(&target.id.to_string(), &log.into()).await;
+                if let Err(err) = BansDB::get_instance().scope(resolver).append(&target.id.to_string(), log).await {
+                    Logger::err_long("Failed to log ban", &err.to_string());
+                }
 
                 // create embed
+                let locale = Locale::of_guild(resolver).await;
+                let title = Text::new("auto-moderation.ban-title").localize(locale);
+                let description = text!("auto-moderation.banned",
+                    user = resolver.resolve_name(target),
+                    reason = &reason).localize(locale);
                 let embed = MessageManager::create_embed(|embed| {
                     embed
-                        .title("Automatic Ban")
-                        .description(&format!(
-                            "{} has been automatically banned for `>` {}",
-                            resolver.resolve_name(target),
-                            reason))
+                        .title(title)
+                        .description(description)
                         .color(0xFF0000)
                 }).await;
 
                 // get modlogs channel
-                let channel: ChannelId = ConfigDB::get_instance()
-                    .get("channel_modlogs").await.unwrap().into();
+                let channel: ChannelId = match BotConfig::get(resolver).await {
+                    Ok(config) => config.channel_modlogs,
+                    Err(err) => return Logger::err_long("Failed to get modlogs channel", &err.to_string()),
+                };
 
                 // send autoban message
-                let _ = channel.send_message(resolver, embed.to_message()).await;
+                let _ = resolver.send_message(channel, embed).await;
             },
-            Err(err) => Logger::err_long("Failed to ban user", &err.to_string())
+            Err(err) => Logger::err_long("Failed to ban user", &err)
         };
     }
 }
@@ -138,14 +138,17 @@ impl ChatFilter {
         }
 
         // fetch additional roles and channels
-        let category_music: ChannelId = ConfigDB::get_instance()
-            .get("category_music").await.unwrap().into();
-        let link_perm_roles = message.resolve_role(vec!["Level 30+", "Booster"]).await;
-
-        // sometimes the role cache of a guild is randomly empty
-        // in this case, we allow all users to post links
-        let has_link_perms = link_perm_roles.is_none()
-            || message.has_role(link_perm_roles.unwrap()).await;
+        // without a configured music category no music links are allowed
+        let config = BotConfig::get(message).await
+            .map_err(|err| Logger::err_long("Failed to get music category", &err.to_string()))
+            .ok();
+        let category_music: Option<ChannelId> = config.as_ref()
+            .and_then(|config| config.category_music);
+
+        // trusted members may post links, without configured trusted
+        // roles all users may post links
+        let has_link_perms = config.is_none_or(|config| config.role_trusted.is_empty())
+            || message.has_level(PermissionLevel::Trusted).await;
 
         if !has_link_perms {
 
@@ -170,7 +173,7 @@ impl ChatFilter {
                     // check music category
                     if !allowed_link {
                         if let Some(category) = channel.parent_id {
-                            if category == category_music {
+                            if Some(category) == category_music {
                                 for whitelisted_music_domain in &self.music_domains {
                                     if link.contains(whitelisted_music_domain) {
                                         allowed_link = true;
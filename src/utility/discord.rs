@@ -0,0 +1,258 @@
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+use serenity::builder::{CreateChannel, CreateMessage, GetMessages};
+use serenity::all::{Cache, Http, ShardMessenger};
+
+use std::sync::Arc;
+
+use crate::utility::*;
+
+
+// The operations on Discord the bot relies on. A connected client implements
+// them with its cache and the API, tests implement them with a guild that only
+// exists in memory, see `FakeGuild`.
+pub trait Discord: Send + Sync {
+
+    fn guild(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Guild>>;
+    fn user(&self, user_id: UserId) -> BoxedFuture<'_, Result<User>>;
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<Member>>;
+    fn roles(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<Role>>>;
+    fn channels(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<GuildChannel>>>;
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<Message>>;
+
+    // up to `limit` messages sent before the given one, the newest first
+    fn messages(&self, channel_id: ChannelId, before: MessageId, limit: u8) -> BoxedFuture<'_, Result<Vec<Message>>>;
+
+    fn send_message(&self, channel_id: ChannelId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>>;
+    fn direct_message(&self, user_id: UserId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>>;
+    fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<()>>;
+    fn delete_messages(&self, channel_id: ChannelId, message_ids: Vec<MessageId>) -> BoxedFuture<'_, Result<()>>;
+
+    fn add_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>>;
+    fn remove_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>>;
+
+    fn create_channel<'a>(&'a self, guild_id: GuildId, channel: CreateChannel<'a>) -> BoxedFuture<'a, Result<GuildChannel>>;
+    fn delete_channel(&self, channel_id: ChannelId) -> BoxedFuture<'_, Result<()>>;
+    fn create_permission(&self, channel_id: ChannelId, overwrite: PermissionOverwrite) -> BoxedFuture<'_, Result<()>>;
+    fn delete_permission(&self, channel_id: ChannelId, kind: PermissionOverwriteType) -> BoxedFuture<'_, Result<()>>;
+
+    fn ban<'a>(&'a self, guild_id: GuildId, user_id: UserId, reason: &'a str) -> BoxedFuture<'a, Result<()>>;
+    fn unban(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<()>>;
+
+    // Everything that is not covered above still talks to serenity directly,
+    // e.g. interactions, which need the shard of a connected client.
+    fn http(&self) -> &Arc<Http>;
+    fn cache(&self) -> Option<&Arc<Cache>>;
+    fn shard(&self) -> Option<&ShardMessenger>;
+}
+
+// Discord as seen by a connected client. Reads are served from the cache
+// when possible and fall back to the API.
+pub struct LiveDiscord {
+    ctx: Context,
+}
+
+impl LiveDiscord {
+
+    pub const ATTEMPTS: usize = 3;
+
+    pub fn new(ctx: Context) -> LiveDiscord {
+        LiveDiscord { ctx }
+    }
+
+    async fn attempt<T, F>(&self, f: impl Fn() -> F) -> Result<T>
+    where F: std::future::Future<Output = std::result::Result<T, serenity::Error>>
+    {
+        for _ in 0..Self::ATTEMPTS {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(_) => continue
+            }
+        }
+        Err("Attempt limit reached".into())
+    }
+}
+
+impl Discord for LiveDiscord {
+
+    fn guild(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Guild>> {
+        Box::pin(async move {
+            guild_id.to_guild_cached(&self.ctx.cache)
+                .map(|guild| guild.clone())
+                .ok_or("Guild is not cached".to_string())
+        })
+    }
+
+    fn user(&self, user_id: UserId) -> BoxedFuture<'_, Result<User>> {
+        Box::pin(async move {
+
+            // first, attempt to get user from cache
+            if let Some(user) = user_id.to_user_cached(&self.ctx.cache) {
+                return Ok(user.clone());
+            }
+
+            // if cache fails, attempt to get user over discord api
+            self.attempt(|| async { self.ctx.http.get_user(user_id).await }).await
+        })
+    }
+
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<Member>> {
+        Box::pin(async move {
+
+            // first, attempt to get member from cache
+            if let Some(guild) = self.ctx.cache.guild(guild_id) {
+                if let Some(member) = guild.members.get(&user_id) {
+                    return Ok(member.clone());
+                }
+            }
+
+            // if cache fails, attempt to get member over discord api
+            self.attempt(|| async { guild_id.member(&self.ctx, user_id).await }).await
+        })
+    }
+
+    fn roles(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<Role>>> {
+        Box::pin(async move {
+
+            // first, attempt to get roles from cache
+            if let Some(guild) = self.ctx.cache.guild(guild_id) {
+                return Ok(guild.roles.values().cloned().collect());
+            }
+
+            // if cache fails, attempt to get roles over discord api
+            self.attempt(|| async { guild_id.roles(&self.ctx).await }).await
+                .map(|roles| roles.into_values().collect())
+        })
+    }
+
+    fn channels(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<GuildChannel>>> {
+        Box::pin(async move {
+
+            // first, attempt to get channels from cache
+            if let Some(guild) = self.ctx.cache.guild(guild_id) {
+                return Ok(guild.channels.values().cloned().collect());
+            }
+
+            // if cache fails, attempt to get channels over discord api
+            self.attempt(|| async { guild_id.channels(&self.ctx).await }).await
+                .map(|channels| channels.into_values().collect())
+        })
+    }
+
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            let message = self.attempt(|| async { self.ctx.http.get_message(channel_id, message_id).await }).await;
+            match message {
+                Ok(message) => Ok(message),
+                Err(err) => self.ctx.cache.message(channel_id, message_id)
+                    .map(|message| message.clone())
+                    .ok_or(err),
+            }
+        })
+    }
+
+    fn messages(&self, channel_id: ChannelId, before: MessageId, limit: u8) -> BoxedFuture<'_, Result<Vec<Message>>> {
+        Box::pin(async move {
+            let builder = GetMessages::new().before(before).limit(limit);
+            channel_id.messages(&self.ctx, builder).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn send_message(&self, channel_id: ChannelId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            channel_id.send_message(&self.ctx, message).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn direct_message(&self, user_id: UserId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            user_id.direct_message(&self.ctx, message).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            channel_id.delete_message(&self.ctx, message_id).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn delete_messages(&self, channel_id: ChannelId, message_ids: Vec<MessageId>) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            channel_id.delete_messages(&self.ctx, message_ids).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn add_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.ctx.http.add_member_role(guild_id, user_id, role_id, None).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn remove_role(&self, guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.ctx.http.remove_member_role(guild_id, user_id, role_id, None).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn create_channel<'a>(&'a self, guild_id: GuildId, channel: CreateChannel<'a>) -> BoxedFuture<'a, Result<GuildChannel>> {
+        Box::pin(async move {
+            guild_id.create_channel(&self.ctx, channel).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn delete_channel(&self, channel_id: ChannelId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            channel_id.delete(&self.ctx).await
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn create_permission(&self, channel_id: ChannelId, overwrite: PermissionOverwrite) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            channel_id.create_permission(&self.ctx, overwrite).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn delete_permission(&self, channel_id: ChannelId, kind: PermissionOverwriteType) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            channel_id.delete_permission(&self.ctx, kind).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn ban<'a>(&'a self, guild_id: GuildId, user_id: UserId, reason: &'a str) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            guild_id.ban_with_reason(&self.ctx, user_id, 0, reason).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn unban(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            guild_id.unban(&self.ctx, user_id).await
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    fn http(&self) -> &Arc<Http> {
+        &self.ctx.http
+    }
+
+    fn cache(&self) -> Option<&Arc<Cache>> {
+        Some(&self.ctx.cache)
+    }
+
+    fn shard(&self) -> Option<&ShardMessenger> {
+        Some(&self.ctx.shard)
+    }
+}
@@ -0,0 +1,495 @@
+use serenity::model::prelude::*;
+use serenity::builder::{CreateChannel, CreateMessage};
+use serenity::all::{Cache, Http, HttpBuilder, ShardMessenger};
+use serde_json::Value;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::utility::*;
+use crate::databases::*;
+
+
+// IDs are unique across all fake guilds, so that every guild has its own
+// configuration, and look and increase like snowflakes, so newer messages
+// sort last.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1_100_000_000_000_000_000);
+
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Default)]
+struct GuildState {
+    users: HashMap<UserId, User>,
+    members: HashMap<UserId, Member>,
+    roles: HashMap<RoleId, Role>,
+    channels: HashMap<ChannelId, GuildChannel>,
+    // all messages that were not deleted, oldest first
+    messages: Vec<Message>,
+    direct_messages: Vec<(UserId, Message)>,
+    bans: HashMap<UserId, String>,
+    owner: Option<UserId>,
+}
+
+// A guild that only exists in memory. Everything the bot does through
+// `Discord` is applied to it, so commands can be run without a connection
+// and their effects inspected afterwards. Requests that still go to the API
+// directly fail right away, since the client points at a closed port.
+pub struct FakeGuild {
+    pub guild_id: GuildId,
+    pub name: String,
+    pub bot: User,
+    http: Arc<Http>,
+    state: Mutex<GuildState>,
+}
+
+impl FakeGuild {
+
+    pub fn new(name: &str) -> Arc<FakeGuild> {
+        let http = HttpBuilder::new("")
+            .proxy("http://127.0.0.1:9")
+            .ratelimiter_disabled(true)
+            .build();
+        let mut bot = User::default();
+        bot.id = UserId::new(next_id());
+        bot.name = "Kalopsian".to_string();
+        bot.bot = true;
+
+        let guild = FakeGuild {
+            guild_id: GuildId::new(next_id()),
+            name: name.to_string(),
+            bot: bot.clone(),
+            http: Arc::new(http),
+            state: Mutex::new(GuildState::default()),
+        };
+        guild.with_state(|state| state.users.insert(bot.id, bot));
+        Arc::new(guild)
+    }
+
+    // A guild with a channel for every configured channel, the staff roles
+    // with one member each and a configuration that refers to all of them.
+    // Channels, roles and users are named after what they are configured as,
+    // e.g. the `modlogs` channel or the `mod` user, categories are capitalized.
+    pub async fn populated(name: &str) -> DBResult<Arc<FakeGuild>> {
+        let guild = FakeGuild::new(name);
+        let config = ConfigDB::get_instance().scope(guild.guild_id);
+
+        // embeds are styled with the global color
+        ConfigDB::get_instance().global().set("color_primary", "#7289da").await?;
+        config.set("command_prefix", "?").await?;
+        config.set("color_primary", "#7289da").await?;
+        config.set("bot_id", &guild.bot.id.to_string()).await?;
+        config.set("web_url", "http://localhost").await?;
+
+        let lockdown = guild.create_category("Lockdown");
+        let tickets = guild.create_category("Tickets");
+        let staff = guild.create_category("Staff");
+        config.append("category_lockdown", &lockdown.to_string()).await?;
+        config.set("category_tickets", &tickets.to_string()).await?;
+        config.append("category_protected_purge", &staff.to_string()).await?;
+        guild.create_channel_in("general", Some(lockdown));
+        guild.create_channel_in("memes", Some(lockdown));
+        guild.create_channel_in("staff-chat", Some(staff));
+
+        for channel in ["suggestions", "event_suggestions", "transcripts", "headmod", "reviews",
+                        "unbanlogs", "verify", "welcome", "tweets", "admin", "member_count",
+                        "messagelogs", "muted", "modlogs", "tickets"] {
+            let id = guild.create_channel_in(channel, None);
+            config.set(&format!("channel_{}", channel), &id.to_string()).await?;
+        }
+
+        for level in [PermissionLevel::Admin, PermissionLevel::HeadMod, PermissionLevel::Mod,
+                      PermissionLevel::Trial, PermissionLevel::Trusted, PermissionLevel::Member] {
+            let role = guild.create_role(&level.to_string());
+            config.append(&level.key().unwrap(), &role.to_string()).await?;
+            guild.create_member(&level.to_string(), &[role]);
+        }
+        let muted = guild.create_role("Muted");
+        let auto_mute = guild.create_role("Auto Mute");
+        config.set("role_muted", &muted.to_string()).await?;
+        config.set("role_auto_mute", &auto_mute.to_string()).await?;
+        guild.create_member("visitor", &[]);
+
+        Ok(guild)
+    }
+
+    #[allow(unused)]
+    pub fn resolver(self: &Arc<Self>) -> Resolver {
+        Resolver::with_discord(self.clone(), Some(self.guild_id))
+    }
+
+    // a message as if the author had sent it, e.g. a command
+    #[allow(unused)]
+    pub async fn message(self: &Arc<Self>, channel_id: ChannelId, author: &User, content: &str) -> MessageManager {
+        let message = self.send_as(channel_id, author, content);
+        MessageManager::new(self.resolver(), message).await
+    }
+
+    pub fn send_as(&self, channel_id: ChannelId, author: &User, content: &str) -> Message {
+        self.post(channel_id, author, content.to_string(), Vec::new())
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut GuildState) -> R) -> R {
+        let mut state = self.state.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut state)
+    }
+
+    fn post(&self, channel_id: ChannelId, author: &User, content: String, embeds: Vec<Embed>) -> Message {
+        let mut message = Message::default();
+        message.id = MessageId::new(next_id());
+        message.channel_id = channel_id;
+        message.guild_id = Some(self.guild_id);
+        message.author = author.clone();
+        message.timestamp = Timestamp::now();
+        message.content = content;
+        message.embeds = embeds;
+        self.with_state(|state| {
+            if let Some(channel) = state.channels.get_mut(&channel_id) {
+                channel.last_message_id = Some(message.id);
+            }
+            state.messages.push(message.clone());
+        });
+        message
+    }
+
+    // the content and embeds of a message the bot sends
+    fn unpack(message: &CreateMessage) -> (String, Vec<Embed>) {
+        let value = serde_json::to_value(message).unwrap_or(Value::Null);
+        let content = value["content"].as_str().unwrap_or_default().to_string();
+        let embeds = serde_json::from_value(value["embeds"].clone()).unwrap_or_default();
+        (content, embeds)
+    }
+
+    // ---- Setup ---- //
+
+    pub fn create_role(&self, name: &str) -> RoleId {
+        let mut role = Role::default();
+        role.id = RoleId::new(next_id());
+        role.guild_id = self.guild_id;
+        role.name = name.to_string();
+        self.with_state(|state| state.roles.insert(role.id, role.clone()));
+        role.id
+    }
+
+    #[allow(unused)]
+    pub fn set_owner(&self, user_id: UserId) {
+        self.with_state(|state| state.owner = Some(user_id));
+    }
+
+    // a user that is not a member of the guild
+    pub fn create_user(&self, name: &str) -> User {
+        let mut user = User::default();
+        user.id = UserId::new(next_id());
+        user.name = name.to_string();
+        self.with_state(|state| state.users.insert(user.id, user.clone()));
+        user
+    }
+
+    pub fn create_member(&self, name: &str, roles: &[RoleId]) -> User {
+        let user = self.create_user(name);
+        let mut member = Member::default();
+        member.user = user.clone();
+        member.guild_id = self.guild_id;
+        member.roles = roles.to_vec();
+        self.with_state(|state| state.members.insert(user.id, member));
+        user
+    }
+
+    pub fn create_category(&self, name: &str) -> ChannelId {
+        let id = self.create_channel_in(name, None);
+        self.with_state(|state| {
+            if let Some(channel) = state.channels.get_mut(&id) {
+                channel.kind = ChannelType::Category;
+            }
+        });
+        id
+    }
+
+    pub fn create_channel_in(&self, name: &str, category: Option<ChannelId>) -> ChannelId {
+        let mut channel = GuildChannel::default();
+        channel.id = ChannelId::new(next_id());
+        channel.guild_id = self.guild_id;
+        channel.name = name.to_string();
+        channel.kind = ChannelType::Text;
+        channel.parent_id = category;
+        self.with_state(|state| state.channels.insert(channel.id, channel.clone()));
+        channel.id
+    }
+
+    // ---- Inspection ---- //
+
+    pub fn user_named(&self, name: &str) -> User {
+        self.with_state(|state| state.users.values()
+            .find(|user| user.name == name)
+            .cloned())
+            .unwrap_or_else(|| panic!("No user named {}", name))
+    }
+
+    #[allow(unused)]
+    pub fn role_named(&self, name: &str) -> RoleId {
+        self.with_state(|state| state.roles.values()
+            .find(|role| role.name == name)
+            .map(|role| role.id))
+            .unwrap_or_else(|| panic!("No role named {}", name))
+    }
+
+    pub fn channel_named(&self, name: &str) -> ChannelId {
+        self.with_state(|state| state.channels.values()
+            .find(|channel| channel.name == name)
+            .map(|channel| channel.id))
+            .unwrap_or_else(|| panic!("No channel named {}", name))
+    }
+
+    pub fn users(&self) -> Vec<User> {
+        self.with_state(|state| state.users.values().cloned().collect())
+    }
+
+    pub fn text_channels(&self) -> Vec<GuildChannel> {
+        self.with_state(|state| state.channels.values()
+            .filter(|channel| channel.kind == ChannelType::Text)
+            .cloned()
+            .collect())
+    }
+
+    pub fn channel(&self, channel_id: ChannelId) -> Option<GuildChannel> {
+        self.with_state(|state| state.channels.get(&channel_id).cloned())
+    }
+
+    #[allow(unused)]
+    pub fn messages_in(&self, channel_id: ChannelId) -> Vec<Message> {
+        self.with_state(|state| state.messages.iter()
+            .filter(|message| message.channel_id == channel_id)
+            .cloned()
+            .collect())
+    }
+
+    #[allow(unused)]
+    pub fn direct_messages_to(&self, user_id: UserId) -> Vec<Message> {
+        self.with_state(|state| state.direct_messages.iter()
+            .filter(|(recipient, _)| *recipient == user_id)
+            .map(|(_, message)| message.clone())
+            .collect())
+    }
+
+    pub fn member_roles(&self, user_id: UserId) -> Vec<RoleId> {
+        self.with_state(|state| state.members.get(&user_id)
+            .map(|member| member.roles.clone())
+            .unwrap_or_default())
+    }
+
+    #[allow(unused)]
+    pub fn overwrites(&self, channel_id: ChannelId) -> Vec<PermissionOverwrite> {
+        self.channel(channel_id)
+            .map(|channel| channel.permission_overwrites)
+            .unwrap_or_default()
+    }
+}
+
+impl Discord for FakeGuild {
+
+    fn guild(&self, guild_id: GuildId) -> BoxedFuture<'_, Result<Guild>> {
+        Box::pin(async move {
+            if guild_id != self.guild_id {
+                return Err("Unknown guild".to_string());
+            }
+            let mut guild = Guild::default();
+            guild.id = self.guild_id;
+            guild.name = self.name.clone();
+            self.with_state(|state| {
+                guild.roles = state.roles.clone();
+                guild.channels = state.channels.clone();
+                guild.members = state.members.clone();
+                if let Some(owner) = state.owner {
+                    guild.owner_id = owner;
+                }
+            });
+            Ok(guild)
+        })
+    }
+
+    fn user(&self, user_id: UserId) -> BoxedFuture<'_, Result<User>> {
+        Box::pin(async move {
+            self.with_state(|state| state.users.get(&user_id).cloned())
+                .ok_or("Unknown user".to_string())
+        })
+    }
+
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<Member>> {
+        Box::pin(async move {
+            self.with_state(|state| state.members.get(&user_id).cloned())
+                .filter(|_| guild_id == self.guild_id)
+                .ok_or("Unknown member".to_string())
+        })
+    }
+
+    fn roles(&self, _guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<Role>>> {
+        Box::pin(async move {
+            Ok(self.with_state(|state| state.roles.values().cloned().collect()))
+        })
+    }
+
+    fn channels(&self, _guild_id: GuildId) -> BoxedFuture<'_, Result<Vec<GuildChannel>>> {
+        Box::pin(async move {
+            Ok(self.with_state(|state| state.channels.values().cloned().collect()))
+        })
+    }
+
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            self.with_state(|state| state.messages.iter()
+                .find(|message| message.channel_id == channel_id && message.id == message_id)
+                .cloned())
+                .ok_or("Unknown message".to_string())
+        })
+    }
+
+    fn messages(&self, channel_id: ChannelId, before: MessageId, limit: u8) -> BoxedFuture<'_, Result<Vec<Message>>> {
+        Box::pin(async move {
+            Ok(self.with_state(|state| state.messages.iter()
+                .rev()
+                .filter(|message| message.channel_id == channel_id && message.id < before)
+                .take(limit as usize)
+                .cloned()
+                .collect()))
+        })
+    }
+
+    fn send_message(&self, channel_id: ChannelId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            if self.channel(channel_id).is_none() {
+                return Err("Unknown channel".to_string());
+            }
+            let (content, embeds) = FakeGuild::unpack(&message);
+            Ok(self.post(channel_id, &self.bot, content, embeds))
+        })
+    }
+
+    fn direct_message(&self, user_id: UserId, message: CreateMessage) -> BoxedFuture<'_, Result<Message>> {
+        Box::pin(async move {
+            let (content, embeds) = FakeGuild::unpack(&message);
+            let mut sent = Message::default();
+            sent.id = MessageId::new(next_id());
+            sent.author = self.bot.clone();
+            sent.timestamp = Timestamp::now();
+            sent.content = content;
+            sent.embeds = embeds;
+            self.with_state(|state| state.direct_messages.push((user_id, sent.clone())));
+            Ok(sent)
+        })
+    }
+
+    fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> BoxedFuture<'_, Result<()>> {
+        self.delete_messages(channel_id, vec![message_id])
+    }
+
+    fn delete_messages(&self, channel_id: ChannelId, message_ids: Vec<MessageId>) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| state.messages
+                .retain(|message| message.channel_id != channel_id || !message_ids.contains(&message.id)));
+            Ok(())
+        })
+    }
+
+    fn add_role(&self, _guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                let member = state.members.get_mut(&user_id).ok_or("Unknown member".to_string())?;
+                if !member.roles.contains(&role_id) {
+                    member.roles.push(role_id);
+                }
+                Ok(())
+            })
+        })
+    }
+
+    fn remove_role(&self, _guild_id: GuildId, user_id: UserId, role_id: RoleId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                let member = state.members.get_mut(&user_id).ok_or("Unknown member".to_string())?;
+                member.roles.retain(|role| *role != role_id);
+                Ok(())
+            })
+        })
+    }
+
+    fn create_channel<'a>(&'a self, _guild_id: GuildId, channel: CreateChannel<'a>) -> BoxedFuture<'a, Result<GuildChannel>> {
+        Box::pin(async move {
+            let value = serde_json::to_value(&channel).unwrap_or(Value::Null);
+            let name = value["name"].as_str().unwrap_or_default();
+            let category = value["parent_id"].as_str()
+                .and_then(|id| id.parse::<u64>().ok())
+                .map(ChannelId::new);
+            let id = self.create_channel_in(name, category);
+            self.with_state(|state| {
+                let channel = state.channels.get_mut(&id).unwrap();
+                channel.topic = value["topic"].as_str().map(|topic| topic.to_string());
+                Ok(channel.clone())
+            })
+        })
+    }
+
+    fn delete_channel(&self, channel_id: ChannelId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                state.messages.retain(|message| message.channel_id != channel_id);
+                state.channels.remove(&channel_id)
+                    .map(|_| ())
+                    .ok_or("Unknown channel".to_string())
+            })
+        })
+    }
+
+    fn create_permission(&self, channel_id: ChannelId, overwrite: PermissionOverwrite) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                let channel = state.channels.get_mut(&channel_id).ok_or("Unknown channel".to_string())?;
+                channel.permission_overwrites.retain(|existing| existing.kind != overwrite.kind);
+                channel.permission_overwrites.push(overwrite);
+                Ok(())
+            })
+        })
+    }
+
+    fn delete_permission(&self, channel_id: ChannelId, kind: PermissionOverwriteType) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                let channel = state.channels.get_mut(&channel_id).ok_or("Unknown channel".to_string())?;
+                channel.permission_overwrites.retain(|existing| existing.kind != kind);
+                Ok(())
+            })
+        })
+    }
+
+    fn ban<'a>(&'a self, _guild_id: GuildId, user_id: UserId, reason: &'a str) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| {
+                state.members.remove(&user_id);
+                state.bans.insert(user_id, reason.to_string());
+            });
+            Ok(())
+        })
+    }
+
+    fn unban(&self, _guild_id: GuildId, user_id: UserId) -> BoxedFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.with_state(|state| state.bans.remove(&user_id))
+                .map(|_| ())
+                .ok_or("Unknown ban".to_string())
+        })
+    }
+
+    fn http(&self) -> &Arc<Http> {
+        &self.http
+    }
+
+    fn cache(&self) -> Option<&Arc<Cache>> {
+        None
+    }
+
+    fn shard(&self) -> Option<&ShardMessenger> {
+        None
+    }
+}
@@ -4,21 +4,29 @@ use serenity::all::ComponentInteractionDataKind::StringSelect;
 use serenity::model::application::ButtonStyle;
 use serenity::builder::{
     CreateEmbed,
+    CreateEmbedFooter,
+    CreateMessage,
+    EditMessage,
     CreateButton,
     CreateActionRow,
     CreateInteractionResponse,
+    CreateInteractionResponseMessage,
     CreateSelectMenu,
     CreateSelectMenuKind,
     CreateSelectMenuOption,
     GetMessages
 };
 
+use std::cmp::min;
 use std::time::Duration;
 use std::collections::HashMap;
 
 use crate::utility::*;
 
 
+// an embed together with the buttons and select menus below it
+pub type NavigationPage = (CreateEmbed, Vec<CreateActionRow>);
+
 pub struct InteractionHelper<'a> {
     channel: ChannelId,
     resolver: &'a Resolver
@@ -30,6 +38,11 @@ impl<'a> InteractionHelper<'_> {
         InteractionHelper { channel, resolver }
     }
 
+    // components are only used by the target, so they are in their language
+    async fn text(&self, target: UserId, text: impl Into<Text>) -> String {
+        text.into().localize(Locale::of_user(self.resolver, target).await)
+    }
+
     pub async fn create_buttons(&self,
                             target: UserId,
                             message: impl ToMessage,
@@ -46,7 +59,7 @@ impl<'a> InteractionHelper<'_> {
 
         // add cancel button
         let cancel_button = CreateButton::new("cancel")
-            .label("Cancel")
+            .label(self.text(target, "interaction.cancel").await)
             .style(ButtonStyle::Danger);
         buttons.push(cancel_button);
 
@@ -62,12 +75,13 @@ impl<'a> InteractionHelper<'_> {
         let message = message.to_message().components(action_rows);
 
         // send message
-        let sent_message = self.channel
-            .send_message(&self.resolver, message).await.unwrap();
+        let sent_message = self.resolver
+            .send_message(self.channel, message).await.unwrap();
 
         // await interaction
+        let shard = self.resolver.shard()?;
         let interaction = &sent_message
-            .await_component_interactions(&self.resolver.ctx().shard)
+            .await_component_interactions(shard)
             .author_id(target)
             .timeout(Duration::from_secs(60)).await;
 
@@ -80,7 +94,7 @@ impl<'a> InteractionHelper<'_> {
             ).await;
 
             // delete message
-            let _ = sent_message.delete(&self.resolver).await;
+            let _ = self.resolver.delete_message(self.channel, sent_message.id).await;
             let id = interaction.data.custom_id.to_string();
 
             match id.as_str() {
@@ -104,16 +118,19 @@ impl<'a> InteractionHelper<'_> {
             CreateSelectMenu::new("select_menu", CreateSelectMenuKind::String {
                 options: options
             })
-            .placeholder("Select an option")
+            .placeholder(self.text(target, "interaction.select-option").await)
         );
 
         // send message
-        let sent_message = self.channel
-            .send_message(&self.resolver, message).await.unwrap();
+        let sent_message = self.resolver
+            .send_message(self.channel, message).await.unwrap();
 
         // await interaction
+        let Some(shard) = self.resolver.shard() else {
+            return;
+        };
         let interaction = &sent_message
-            .await_component_interaction(&self.resolver.ctx().shard)
+            .await_component_interaction(shard)
             .author_id(target)
             .timeout(Duration::from_secs(60)).await;
 
@@ -126,7 +143,7 @@ impl<'a> InteractionHelper<'_> {
             ).await;
 
             // delete message
-            let _ = sent_message.delete(&self.resolver).await;
+            let _ = self.resolver.delete_message(self.channel, sent_message.id).await;
 
             let data = &interaction.data.kind;
             match data {
@@ -152,16 +169,19 @@ impl<'a> InteractionHelper<'_> {
                         .description(&user.id.to_string())
                 }).collect()
             })
-            .placeholder("Select a user")
+            .placeholder(self.text(target, "interaction.select-user").await)
         );
 
         // send message
-        let sent_message = self.channel
-            .send_message(&self.resolver, message).await.unwrap();
+        let sent_message = self.resolver
+            .send_message(self.channel, message).await.unwrap();
 
         // await interaction
+        let Some(shard) = self.resolver.shard() else {
+            return;
+        };
         let interaction = sent_message
-            .await_component_interaction(&self.resolver.ctx().shard)
+            .await_component_interaction(shard)
             .author_id(target)
             .timeout(Duration::from_secs(60)).await;
 
@@ -174,7 +194,7 @@ impl<'a> InteractionHelper<'_> {
             ).await;
 
             // delete message
-            let _ = sent_message.delete(&self.resolver).await;
+            let _ = self.resolver.delete_message(self.channel, sent_message.id).await;
 
             let data = &interaction.data.kind;
             match data {
@@ -190,21 +210,81 @@ impl<'a> InteractionHelper<'_> {
         }
     }
 
+    // Sends the first page and lets the target flip through the others until
+    // no button was pressed for a minute, the buttons are removed afterwards.
+    pub async fn create_pagination(&self, target: UserId, pages: Vec<CreateEmbed>) {
+
+        let Some(first) = pages.first() else {
+            return;
+        };
+        let count = pages.len();
+        let locale = Locale::of_user(self.resolver, target).await;
+        let footer = |index: usize| CreateEmbedFooter::new(
+            text!("interaction.page", page = index + 1, count = count).localize(locale));
+        let previous = Text::new("interaction.previous").localize(locale);
+        let next = Text::new("interaction.next").localize(locale);
+        let buttons = |index: usize| vec![CreateActionRow::Buttons(vec![
+            CreateButton::new("previous")
+                .label(&previous)
+                .style(ButtonStyle::Secondary)
+                .disabled(index == 0),
+            CreateButton::new("next")
+                .label(&next)
+                .style(ButtonStyle::Secondary)
+                .disabled(index + 1 == count),
+        ])];
+
+        // a single page needs no buttons
+        let mut message = CreateMessage::new().embed(first.clone().footer(footer(0)));
+        if count > 1 {
+            message = message.components(buttons(0));
+        }
+        let Ok(mut sent_message) = self.resolver.send_message(self.channel, message).await else {
+            return;
+        };
+        if count == 1 {
+            return;
+        }
+
+        let Some(shard) = self.resolver.shard() else {
+            return;
+        };
+        let mut index: usize = 0;
+        while let Some(interaction) = sent_message
+            .await_component_interaction(shard)
+            .author_id(target)
+            .timeout(Duration::from_secs(60)).await {
+
+            index = match interaction.data.custom_id.as_str() {
+                "previous" => index.saturating_sub(1),
+                _ => min(index + 1, count - 1),
+            };
+            let _ = interaction.create_response(&self.resolver,
+                CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+                    .embed(pages[index].clone().footer(footer(index)))
+                    .components(buttons(index)))
+            ).await;
+        }
+
+        let _ = sent_message.edit(&self.resolver, EditMessage::new().components(Vec::new())).await;
+    }
+
     pub async fn await_reply(&self, user: &User, message: impl ToMessage) -> Option<Message> {
 
         let user_id = user.id.to_string();
 
         // send message
-        let sent_message = self.channel
-            .send_message(&self.resolver, message.to_message()).await.unwrap();
+        let sent_message = self.resolver
+            .send_message(self.channel, message).await.unwrap();
 
         // await interaction
+        let shard = self.resolver.shard()?;
         let interaction = self.channel
-            .await_reply(&self.resolver.ctx().shard)
+            .await_reply(shard)
             .filter(move |reply| reply.author.id.to_string() == user_id)
             .timeout(Duration::from_secs(60)).await;
 
-        let _ = sent_message.delete(&self.resolver).await;
+        let _ = self.resolver.delete_message(self.channel, sent_message.id).await;
 
         // execute callback
         if let Some(interaction) = interaction {
@@ -213,4 +293,41 @@ impl<'a> InteractionHelper<'_> {
         None
     }
 
+    // Sends the first page and shows the one a pressed button or a selected
+    // option leads to, the custom ID of the button or the value of the option
+    // is the key of the page. The components are removed once none was used
+    // for a minute.
+    pub async fn create_navigation(&self, target: UserId, pages: HashMap<String, NavigationPage>, first: &str) {
+
+        let Some((embed, components)) = pages.get(first).cloned() else {
+            return;
+        };
+        let message = CreateMessage::new().embed(embed).components(components);
+        let Ok(mut sent_message) = self.resolver.send_message(self.channel, message).await else {
+            return;
+        };
+
+        let Some(shard) = self.resolver.shard() else {
+            return;
+        };
+        while let Some(interaction) = sent_message
+            .await_component_interaction(shard)
+            .author_id(target)
+            .timeout(Duration::from_secs(60)).await {
+
+            let key = match &interaction.data.kind {
+                StringSelect{values} => values.first().cloned().unwrap_or_default(),
+                _ => interaction.data.custom_id.clone(),
+            };
+            let response = match pages.get(&key) {
+                Some((embed, components)) => CreateInteractionResponse::UpdateMessage(CreateInteractionResponseMessage::new()
+                    .embed(embed.clone())
+                    .components(components.clone())),
+                None => CreateInteractionResponse::Acknowledge,
+            };
+            let _ = interaction.create_response(&self.resolver, response).await;
+        }
+
+        let _ = sent_message.edit(&self.resolver, EditMessage::new().components(Vec::new())).await;
+    }
 }
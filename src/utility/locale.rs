@@ -0,0 +1,321 @@
+use serenity::model::id::UserId;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use once_cell::sync::Lazy;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::utility::*;
+use crate::databases::*;
+use crate::impl_singleton;
+
+
+// The languages the bot replies in. Guilds choose theirs with the `locale`
+// key, members may prefer another one with the `language` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+
+    // the name of the language in the language itself
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German  => "Deutsch",
+        }
+    }
+
+    fn catalog(&self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../../locales/en.toml"),
+            Locale::German  => include_str!("../../locales/de.toml"),
+        }
+    }
+
+    // The language of the guild, English if it did not choose one or its
+    // configuration can not be read.
+    pub async fn of_guild(scope: impl Into<Scope>) -> Locale {
+        match BotConfig::get(scope.into()).await {
+            Ok(config) => config.locale,
+            Err(_) => Locale::default(),
+        }
+    }
+
+    // The language a user prefers in all guilds, otherwise the language of
+    // the guild they are in.
+    pub async fn of_user(scope: impl Into<Scope>, user_id: UserId) -> Locale {
+        let preferred = LocalesDB::get_instance().global()
+            .get(&user_id.to_string()).await;
+        match preferred.map(|entry| Locale::from_str(&entry.value)) {
+            Ok(Ok(locale)) => locale,
+            _ => Locale::of_guild(scope).await,
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(locale: &str) -> std::result::Result<Locale, String> {
+        match locale.to_lowercase().as_str() {
+            "en" | "english" => Ok(Locale::English),
+            "de" | "german" | "deutsch" => Ok(Locale::German),
+            _ => Err(format!("'{}' is not a locale", locale)),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Locale::English => write!(f, "en"),
+            Locale::German  => write!(f, "de"),
+        }
+    }
+}
+
+// A message of the catalog together with its arguments, e.g.
+// `text!("mute.already-muted", user = target.mention())`. It is written in a
+// language only once it is known who reads it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Text {
+    id: String,
+    args: Vec<(String, String)>,
+    // not a message but content that is shown as it is, e.g. a reason
+    plain: bool,
+}
+
+impl Text {
+
+    pub fn new(id: impl Into<String>) -> Text {
+        Text { id: id.into(), args: Vec::new(), plain: false }
+    }
+
+    pub fn plain(content: impl Into<String>) -> Text {
+        Text { id: content.into(), args: Vec::new(), plain: true }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl ToString) -> Text {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn localize(&self, locale: Locale) -> String {
+        match self.plain {
+            true  => self.id.clone(),
+            false => Catalog::get_instance().format(locale, self),
+        }
+    }
+}
+
+impl From<&str> for Text {
+    fn from(id: &str) -> Text {
+        Text::new(id)
+    }
+}
+
+#[macro_export]
+macro_rules! text {
+    ($id:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::utility::Text::new($id)$(.arg(stringify!($name), $value))*
+    };
+}
+
+// The messages of all languages, read from the `locales` directory at
+// compile time. Each file is a TOML table of messages, the ID of a message
+// is its key prefixed with the names of the tables it is in, e.g. `[mute]`
+// and `already-muted` make `mute.already-muted`. Arguments are written as
+// `{name}`. Messages a language lacks are taken from English.
+pub struct Catalog {
+    messages: HashMap<Locale, HashMap<String, String>>,
+}
+
+impl_singleton!(Catalog);
+
+impl Catalog {
+
+    pub fn new() -> Catalog {
+        let messages = Locale::iter()
+            .map(|locale| {
+                let messages = Catalog::parse(locale.catalog()).unwrap_or_else(|err| {
+                    Logger::err_long(&format!("Failed to read the {} catalog", locale.name()), &err);
+                    HashMap::new()
+                });
+                (locale, messages)
+            })
+            .collect();
+        Catalog { messages }
+    }
+
+    fn parse(source: &str) -> Result<HashMap<String, String>> {
+        let table = source.parse::<toml::Table>()
+            .map_err(|err| err.to_string())?;
+        let mut messages = HashMap::new();
+        Catalog::flatten("", table, &mut messages)?;
+        Ok(messages)
+    }
+
+    fn flatten(prefix: &str, table: toml::Table, messages: &mut HashMap<String, String>) -> Result<()> {
+        for (key, value) in table {
+            let id = format!("{}{}", prefix, key);
+            match value {
+                toml::Value::String(message) => {
+                    messages.insert(id, message);
+                }
+                toml::Value::Table(table) => Catalog::flatten(&format!("{}.", id), table, messages)?,
+                _ => return Err(format!("`{}` is not a message", id)),
+            }
+        }
+        Ok(())
+    }
+
+    // the message in the language, or in English if it was not translated
+    pub fn get(&self, locale: Locale, id: &str) -> Option<&str> {
+        [locale, Locale::English].iter()
+            .find_map(|locale| self.messages.get(locale)?.get(id))
+            .map(|message| message.as_str())
+    }
+
+    // Unknown messages are shown as their ID, so a missing message is noticed
+    // without failing the command that uses it. Arguments are inserted in a
+    // single pass, so a `{name}` in a value, e.g. in a reason, stays as it is.
+    pub fn format(&self, locale: Locale, text: &Text) -> String {
+        let Some(mut rest) = self.get(locale, &text.id) else {
+            Logger::warn_long("Unknown message", &text.id);
+            return text.id.clone();
+        };
+        let mut formatted = String::new();
+        while let Some(start) = rest.find('{') {
+            formatted.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let argument = after.find('}')
+                .and_then(|end| text.args.iter().find(|(name, _)| *name == after[..end]));
+            match argument {
+                Some((name, value)) => {
+                    formatted.push_str(value);
+                    rest = &after[name.len() + 1..];
+                }
+                None => {
+                    formatted.push('{');
+                    rest = after;
+                }
+            }
+        }
+        formatted.push_str(rest);
+        formatted
+    }
+
+    // The arguments of content that was formatted from the message in any
+    // language, e.g. the staff in the footer of a review, so messages the bot
+    // sent earlier can be read again after the guild changed its language.
+    pub fn arguments(&self, id: &str, content: &str) -> Option<HashMap<String, String>> {
+        self.messages.values()
+            .filter_map(|messages| messages.get(id))
+            .find_map(|message| Catalog::match_message(message, content))
+    }
+
+    // Literal parts have to match exactly, an argument takes everything up
+    // to the next literal part.
+    fn match_message(message: &str, content: &str) -> Option<HashMap<String, String>> {
+        let mut arguments = HashMap::new();
+        let mut rest = content;
+        let mut pending: Option<&str> = None;
+        let mut message = message;
+        loop {
+            let (literal, argument) = match message.find('{') {
+                Some(start) => {
+                    let end = start + message[start..].find('}')?;
+                    (&message[..start], Some(&message[start + 1..end]))
+                }
+                None => (message, None),
+            };
+            let position = match pending {
+                Some(_) if argument.is_none() && !literal.is_empty() => rest.rfind(literal)?,
+                Some(_) if literal.is_empty() => rest.len(),
+                Some(_) => rest.find(literal)?,
+                None if rest.starts_with(literal) => 0,
+                None => return None,
+            };
+            if let Some(name) = pending.take() {
+                arguments.insert(name.to_string(), rest[..position].to_string());
+            }
+            rest = &rest[position + literal.len()..];
+            match argument {
+                Some(name) => {
+                    pending = Some(name);
+                    message = &message[literal.len() + name.len() + 2..];
+                }
+                None => break,
+            }
+        }
+        match rest.is_empty() {
+            true  => Some(arguments),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn placeholders(message: &str) -> Vec<&str> {
+        let mut names = message.match_indices('{')
+            .filter_map(|(start, _)| message[start + 1..].find('}').map(|end| &message[start + 1..start + 1 + end]))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn catalogs_match_english() {
+        let english = Catalog::parse(Locale::English.catalog()).unwrap();
+        for locale in Locale::iter() {
+            let messages = Catalog::parse(locale.catalog()).unwrap();
+            for (id, message) in messages.iter() {
+                let original = english.get(id).unwrap_or_else(|| panic!("{} has no English message `{}`", locale, id));
+                assert_eq!(placeholders(message), placeholders(original), "{} `{}`", locale, id);
+            }
+        }
+    }
+
+    #[test]
+    fn format_inserts_arguments_once() {
+        let catalog = Catalog::get_instance();
+        let text = text!("mute.already-muted", user = "{user}");
+        assert_eq!(catalog.format(Locale::English, &text), "{user} is already muted.");
+        assert_eq!(catalog.format(Locale::German, &text), "{user} ist bereits stummgeschaltet.");
+
+        // unknown arguments and messages stay visible
+        assert_eq!(catalog.format(Locale::English, &Text::new("mute.already-muted")), "{user} is already muted.");
+        assert_eq!(catalog.format(Locale::German, &Text::new("no.such-message")), "no.such-message");
+        assert_eq!(Text::plain("mute.already-muted").localize(Locale::German), "mute.already-muted");
+    }
+
+    #[test]
+    fn arguments_are_read_back_in_any_language() {
+        let catalog = Catalog::get_instance();
+        for locale in Locale::iter() {
+            let text = text!("review.suggestion-footer", user = "Ann - Bee", staff = "<@1>");
+            let arguments = catalog.arguments("review.suggestion-footer", &text.localize(locale)).unwrap();
+            assert_eq!(arguments["user"], "Ann - Bee");
+            assert_eq!(arguments["staff"], "<@1>");
+
+            let content = text!("ticket.added", user = "<@2>").localize(locale);
+            assert_eq!(catalog.arguments("ticket.added", &content).unwrap()["user"], "<@2>");
+            assert!(catalog.arguments("ticket.removed", &content).is_none());
+        }
+    }
+}
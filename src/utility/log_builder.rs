@@ -1,21 +1,26 @@
-
 use serenity::builder::{CreateEmbed, CreateEmbedAuthor};
 use serenity::model::user::User;
+use serenity::model::id::UserId;
 
 use crate::utility::*;
 use crate::databases::*;
 
 
+// Logs are read by the staff of the guild, so they are written in the
+// language of the guild, unless the embed is meant for a single user, e.g.
+// the help or the notice of a ban.
 #[derive(Clone)]
 pub struct LogBuilder<'a> {
     message: &'a MessageManager,
-    title: String,
-    description: Option<String>,
+    reader: Option<UserId>,
+    title: Text,
+    description: Option<Text>,
     color: Option<u64>,
     image: Option<String>,
     target: Option<&'a User>,
     thumbnail: bool,
-    fields: Vec<(String, String, bool)>,
+    // the lines of a field are joined once they are localized
+    fields: Vec<(Text, Vec<Text>, bool)>,
 }
 
 impl<'a> LogBuilder<'a> {
@@ -23,7 +28,8 @@ impl<'a> LogBuilder<'a> {
     pub fn new(message: &MessageManager) -> LogBuilder<'_> {
         LogBuilder {
             message: message,
-            title: "No title provided".to_string(),
+            reader: None,
+            title: Text::new("log.no-title"),
             description: None,
             color: None,
             image: None,
@@ -34,15 +40,28 @@ impl<'a> LogBuilder<'a> {
     }
 
     pub async fn build(&self) -> CreateEmbed {
+        let locale = match self.reader {
+            Some(reader) => Locale::of_user(self.message, reader).await,
+            None => Locale::of_guild(self.message).await,
+        };
+        let fields = self.fields.iter()
+            .map(|(label, lines, inline)| {
+                let content = lines.iter()
+                    .map(|line| line.localize(locale))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                (label.localize(locale), content, *inline)
+            })
+            .collect::<Vec<(String, String, bool)>>();
         MessageManager::create_embed(|embed| {
             let author = match &self.target {
                 Some(user) => user,
                 None => &self.message.get_author()
             };
             let mut embed = embed
-                .author(CreateEmbedAuthor::new(self.title.clone())
+                .author(CreateEmbedAuthor::new(self.title.localize(locale))
                     .icon_url(author.face()))
-                .fields(self.fields.clone());
+                .fields(fields);
             if self.thumbnail {
                 embed = embed.thumbnail(author.face());
             }
@@ -50,7 +69,7 @@ impl<'a> LogBuilder<'a> {
                 embed = embed.color(color);
             }
             if let Some(description) = &self.description {
-                embed = embed.description(description);
+                embed = embed.description(description.localize(locale));
             }
             if let Some(image) = &self.image {
                 embed = embed.image(image);
@@ -59,12 +78,22 @@ impl<'a> LogBuilder<'a> {
         }).await
     }
 
-    pub fn title(mut self, title: impl Into<String>) -> Self {
+    pub fn for_author(mut self) -> Self {
+        self.reader = Some(self.message.get_author().id);
+        self
+    }
+
+    pub fn for_user(mut self, user_id: UserId) -> Self {
+        self.reader = Some(user_id);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<Text>) -> Self {
         self.title = title.into();
         self
     }
 
-    pub fn description(mut self, description: impl Into<String>) -> Self {
+    pub fn description(mut self, description: impl Into<Text>) -> Self {
         self.description = Some(description.into());
         self
     }
@@ -74,18 +103,18 @@ impl<'a> LogBuilder<'a> {
         self
     }
 
-    fn format_user(&self, user: &User) -> String {
-        format!("<@{}>", user.id.to_string())
+    fn format_user(&self, user: &User) -> Text {
+        Text::plain(format!("<@{}>", user.id))
     }
 
     pub fn user(mut self, user: &User) -> Self {
-        self.fields.push(("User".to_string(), self.format_user(user), true));
+        self.fields.push(("log.user".into(), vec![self.format_user(user)], true));
         self
     }
 
     pub fn staff(mut self) -> Self {
         let staff = self.message.get_author();
-        self.fields.push(("Staff".to_string(), self.format_user(staff), true));
+        self.fields.push(("log.staff".into(), vec![self.format_user(staff)], true));
         self
     }
 
@@ -95,33 +124,33 @@ impl<'a> LogBuilder<'a> {
 
     pub fn timestamp(mut self) -> Self {
         let timestamp = self.message.get_timestamp();
-        self.fields.push(("Timestamp".to_string(),
-            LogBuilder::format_timestamp(timestamp),
+        self.fields.push(("log.timestamp".into(),
+            vec![Text::plain(LogBuilder::format_timestamp(timestamp))],
             true));
         self
     }
 
-    pub fn labeled_timestamp(mut self, label: impl Into<String>, timestamp: i64) -> Self {
-        let label = label.into();
+    pub fn labeled_timestamp(mut self, label: impl Into<Text>, timestamp: i64) -> Self {
         let timestamp = LogBuilder::format_timestamp(timestamp);
-        self.fields.push((label, timestamp, true));
+        self.fields.push((label.into(), vec![Text::plain(timestamp)], true));
         self
     }
 
     pub fn channel(mut self) -> Self {
-        self.fields.push(("Channel".to_string(),
-            format!("<#{}>", self.message.get_channel().get().to_string()),
+        self.fields.push(("log.channel".into(),
+            vec![Text::plain(format!("<#{}>", self.message.get_channel().get()))],
             true));
         self
     }
 
-    pub fn arbitrary(mut self, label: impl Into<String>, content: impl Into<String>) -> Self {
-        self.fields.push((label.into(), content.into(), true));
+    // a field with content that is shown as it is, e.g. a reason
+    pub fn arbitrary(mut self, label: impl Into<Text>, content: impl Into<String>) -> Self {
+        self.fields.push((label.into(), vec![Text::plain(content)], true));
         self
     }
 
-    pub fn arbitrary_block(mut self, label: impl Into<String>, content: impl Into<String>) -> Self {
-        self.fields.push((label.into(), content.into(), false));
+    pub fn arbitrary_block(mut self, label: impl Into<Text>, content: impl Into<String>) -> Self {
+        self.fields.push((label.into(), vec![Text::plain(content)], false));
         self
     }
 
@@ -142,21 +171,36 @@ impl<'a> LogBuilder<'a> {
 
     pub fn mod_log(mut self, log: &ModLog) -> Self {
         let timestamp = LogBuilder::format_timestamp(log.timestamp);
-        let id = format!("**Database ID**: {}", log.id);
+        let id = text!("log.database-id", id = log.id);
+
+        let staff_time = text!("log.staff-line", staff = format!("<@{}>", log.staff_id), time = timestamp);
+        let reason = text!("log.reason-line", reason = &log.reason);
+
+        self.fields.push((id, vec![staff_time, reason], false));
+        self
+    }
+
+    pub fn removed_log(mut self, log: &ModLog, removal: &RemovalLog) -> Self {
+        let timestamp = LogBuilder::format_timestamp(log.timestamp);
+        let id = text!("log.database-id-removed", id = log.id);
 
-        let staff_time = format!("**Staff**: <@{}> `>` {}", log.staff_id, timestamp);
-        let reason = format!("**Reason**: {}", log.reason);
+        let staff_time = text!("log.staff-line", staff = format!("<@{}>", log.staff_id), time = timestamp);
+        let reason = text!("log.reason-line", reason = &log.reason);
+        let removed_by = text!("log.removed-by-line",
+            staff = format!("<@{}>", removal.staff_id),
+            time = LogBuilder::format_timestamp(removal.timestamp));
+        let removal_reason = text!("log.removal-reason-line", reason = &removal.reason);
 
-        self.fields.push((id, format!("{}\n{}", staff_time, reason), false));
+        self.fields.push((id, vec![staff_time, reason, removed_by, removal_reason], false));
         self
     }
 
     pub fn schedule_log(mut self, log: &ScheduleLog) -> Self {
         let timestamp = LogBuilder::format_timestamp(log.expiration_date);
-        let id = format!("**Database ID**: {}", log.id);
+        let id = text!("log.database-id", id = log.id);
 
-        let content = format!("**Content**: {}", log.message);
-        self.fields.push((id, format!("{}\n{}", content, timestamp), false));
+        let content = text!("log.content-line", content = &log.message);
+        self.fields.push((id, vec![content, Text::plain(timestamp)], false));
         self
     }
 
@@ -1,16 +1,13 @@
 
 use serenity::model::prelude::*;
-use serenity::builder::{
-    CreateEmbed,
-    GetMessages
-};
+use serenity::builder::CreateEmbed;
 use serenity::all::{CacheHttp, Cache, Http};
 use nonempty::NonEmpty;
 use regex::Regex;
 
-use std::collections::HashMap;
 use std::time::Duration;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::utility::*;
 use crate::databases::*;
@@ -22,8 +19,12 @@ pub struct MessageManager {
     raw_message: Message,
     prefix: Option<String>,
     command: Option<String>,
-    parameters: HashMap<String, String>,
-    pub words: Vec<String>
+    // slash commands are not sent as messages, see `from_interaction`
+    is_interaction: bool,
+    // set once a failure was replied, shared by all clones of the manager
+    failed: Arc<AtomicBool>,
+    pub words: Vec<String>,
+    pub tokens: Vec<Token>
 }
 
 impl CacheHttp for MessageManager {
@@ -31,7 +32,7 @@ impl CacheHttp for MessageManager {
         self.resolver.http()
     }
     fn cache(&self) -> Option<&Arc<Cache>> {
-        Some(self.resolver.cache())
+        self.resolver.cache()
     }
 }
 
@@ -49,13 +50,30 @@ impl MessageManager {
             raw_message: message,
             prefix: None,
             command: None,
-            parameters: HashMap::new(),
-            words: Vec::new()
+            is_interaction: false,
+            failed: Arc::new(AtomicBool::new(false)),
+            words: Vec::new(),
+            tokens: Vec::new()
         };
         manager.parse_message().await;
         manager
     }
 
+    // Slash commands are run like the text command they stand for, with a
+    // message that is made up from the interaction and the given content.
+    pub async fn from_interaction(resolver: Resolver, interaction: &CommandInteraction, content: String) -> MessageManager {
+        let mut message = Message::default();
+        message.id = MessageId::new(interaction.id.get());
+        message.channel_id = interaction.channel_id;
+        message.guild_id = interaction.guild_id;
+        message.author = interaction.user.clone();
+        message.timestamp = interaction.id.created_at();
+        message.content = content;
+        let mut manager = MessageManager::new(resolver, message).await;
+        manager.is_interaction = true;
+        manager
+    }
+
     pub async fn spoof(&self, content: String) -> MessageManager {
         let mut spoofed_message = self.raw_message.clone();
         spoofed_message.content = content;
@@ -72,11 +90,13 @@ impl MessageManager {
             .split_whitespace()
             .map(|word| word.to_string())
             .collect();
+        self.tokens = self.words.iter()
+            .map(Token::plain)
+            .collect();
 
         // Obtain command
         if self.words.len() > 0 {
-            let prefix = ConfigDB::get_instance()
-                .get("command_prefix").await.unwrap().to_string();
+            let prefix = BotConfig::prefix(self.raw_message.guild_id.into()).await;
             if self.words[0].starts_with(&prefix) && self.words[0].len() > 1 {
                 let command = self.words[0].to_string();
                 self.command = command.strip_prefix(&prefix).map(|s| s.to_string());
@@ -84,30 +104,12 @@ impl MessageManager {
             }
         }
 
-        // Only parse parameters if message is a command
+        // The words of commands may be quoted
         if self.is_command() {
-
-            // Obtain keys
-            let keys = self.words.iter()
-                .filter(|word| word.starts_with("-"))
-                .map(|word| word.to_string())
-                .collect::<Vec<String>>();
-
-            // Iterate over keys
-            if keys.len() > 0 {
-                let split_regex = Regex::new(keys.join("|").as_str());
-
-                if let Ok(regex) = split_regex {
-                    let payload = self.raw_message.content.clone();
-                    let splits = regex.split(&payload).collect::<Vec<&str>>();
-                    splits[1..].into_iter()
-                        .enumerate()
-                        .for_each(|(i, split)| {
-                            let key = keys[i].strip_prefix("-").unwrap().to_string();
-                            self.parameters.insert(key, split.trim().to_string());
-                        });
-                }
-            }
+            self.tokens = tokenize(&self.raw_message.content);
+            self.words = self.tokens.iter()
+                .map(|token| token.text.clone())
+                .collect();
         }
     }
 
@@ -123,14 +125,6 @@ impl MessageManager {
         self.prefix.clone()
     }
 
-    pub fn has_parameter(&self, key: &str) -> bool {
-        self.parameters.contains_key(key)
-    }
-
-    pub fn get_parameter(&self, key: &str) -> String {
-        self.parameters.get(key).unwrap().to_string()
-    }
-
     pub fn payload(&self, starting_from: Option<usize>, excludes: Option<Vec<String>>) -> String {
 
         // calculate starting index
@@ -179,52 +173,87 @@ impl MessageManager {
     }
 
     pub async fn delete(&self) {
-        let _ = self.raw_message.delete(&self.resolver).await;
+        if self.is_interaction {
+            return;
+        }
+        let _ = self.resolver.delete_message(self.get_channel(), self.raw_message.id).await;
     }
 
     pub async fn reply(&self, message: impl ToMessage) -> Result<Message> {
-        let channel = self.get_channel();
-        channel.send_message(&self.resolver, message.to_message()).await.map_err(|_| "Failed to send message".to_string())
+        self.resolver.send_message(self.get_channel(), message).await.map_err(|_| "Failed to send message".to_string())
     }
 
     pub async fn reply_temporary(&self, message: impl ToMessage) {
         let sent_message = self.reply(message).await;
         if let Ok(message) = sent_message {
             let _ = tokio::time::sleep(Duration::from_secs(4)).await;
-            let _ = message.delete(&self.resolver).await;
+            let _ = self.resolver.delete_message(message.channel_id, message.id).await;
         }
     }
 
     pub async fn reply_success(&self) {
-        let embed = MessageManager::create_embed(|embed| {
-            embed
-                .title("✅")
-                .description("Success!")
-        }).await;
+        let embed = self.create_text_embed("reply.success-title", "reply.success").await;
         self.reply_temporary(embed).await;
     }
 
-    pub async fn reply_failure(&self, context: &str) {
-        let embed = MessageManager::create_embed(|embed| {
-            embed
-                .title("❌")
-                .description(context)
-        }).await;
+    pub async fn reply_failure(&self, context: impl Into<Text>) {
+        self.mark_failed();
+        let embed = self.create_text_embed("reply.failure-title", context).await;
         self.reply_temporary(embed).await;
     }
 
+    // for failures that are replied otherwise, e.g. with the usage
+    pub fn mark_failed(&self) {
+        self.failed.store(true, Ordering::Relaxed);
+    }
+
+    // whether a failure was replied to this message, e.g. by its command
+    pub fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    // logs a failed database operation and informs the author about it
+    pub async fn reply_db_error(&self, err: DBError) {
+        Logger::err_long("Database operation failed", &err.to_string());
+        self.reply_failure(Text::plain(err.to_string())).await;
+    }
+
+    // the language replies to the author are written in
+    pub async fn locale(&self) -> Locale {
+        Locale::of_user(self, self.get_author().id).await
+    }
+
+    pub async fn text(&self, text: impl Into<Text>) -> String {
+        text.into().localize(self.locale().await)
+    }
+
+    // for what the whole guild reads, e.g. announcements and logs
+    pub async fn guild_text(&self, text: impl Into<Text>) -> String {
+        text.into().localize(Locale::of_guild(self).await)
+    }
+
+    // an embed with a title and a description in the language of the author
+    pub async fn create_text_embed(&self, title: impl Into<Text>, description: impl Into<Text>) -> CreateEmbed {
+        let locale = self.locale().await;
+        let title = title.into().localize(locale);
+        let description = description.into().localize(locale);
+        MessageManager::create_embed(|embed| {
+            embed
+                .title(title)
+                .description(description)
+        }).await
+    }
+
     pub async fn create_embed(fn_style: impl FnOnce(CreateEmbed) -> CreateEmbed) -> CreateEmbed {
-        let color_primary = ConfigDB::get_instance()
-            .get("color_primary").await.unwrap();
+        let color_primary = BotConfig::color_primary().await;
         let embed = fn_style(CreateEmbed::default());
         let styled_embed = embed.color(color_primary);
         styled_embed
     }
 
     pub async fn last_messages(&self, limit: u8) -> Vec<Message> {
-        let channel = self.get_channel();
-        let builder = GetMessages::new().before(self.raw_message.id).limit(limit);
-        let messages = channel.messages(&self.resolver, builder).await;
+        let messages = self.resolver.discord()
+            .messages(self.get_channel(), self.raw_message.id, limit).await;
         match messages {
             Ok(messages) => messages,
             Err(_) => Vec::new()
@@ -241,6 +270,10 @@ impl MessageManager {
 
     // ---- Basics ---- //
 
+    pub fn get_id(&self) -> MessageId {
+        self.raw_message.id
+    }
+
     pub fn get_channel(&self) -> ChannelId {
         self.raw_message.channel_id
     }
@@ -327,20 +360,12 @@ impl MessageManager {
         self.resolver.has_role(self.get_author(), roles).await
     }
 
-    pub async fn is_admin(&self) -> bool {
-        self.resolver.is_admin(self.get_author()).await
-    }
-
-    pub async fn is_headmod(&self) -> bool {
-        self.resolver.is_headmod(self.get_author()).await
-    }
-
-    pub async fn is_mod(&self) -> bool {
-        self.resolver.is_mod(self.get_author()).await
+    pub async fn level(&self) -> PermissionLevel {
+        self.resolver.level(self.get_author()).await
     }
 
-    pub async fn is_trial(&self) -> bool {
-        self.resolver.is_trial(self.get_author()).await
+    pub async fn has_level(&self, level: PermissionLevel) -> bool {
+        self.resolver.has_level(self.get_author(), level).await
     }
 
 }
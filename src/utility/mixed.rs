@@ -87,6 +87,26 @@ pub fn parse_time(input: impl Into<String>) -> Result<u64> {
     Ok(total_seconds)
 }
 
+// The inverse of parse_time with the two largest units, e.g. `1h 5m` or `4m`.
+pub fn format_duration(seconds: u64) -> String {
+    let parts = [
+        (seconds / 86_400, "d"),
+        (seconds / 3_600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts = parts.iter()
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<String>>();
+    match parts.is_empty() {
+        true  => "0s".to_string(),
+        false => parts.join(" "),
+    }
+}
+
 // Returns the index of the last element in `array` that is less than or equal to `target`
 pub fn binary_search<T, O: Into<i64>>(array: &Vec<T>, target: O, eval: fn(&T) -> O) -> usize {
 
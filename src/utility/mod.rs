@@ -2,6 +2,9 @@
 pub mod permission_handler;
 pub use permission_handler::PermissionHandler;
 
+pub mod permission_level;
+pub use permission_level::PermissionLevel;
+
 #[cfg(feature = "tickets")]
 pub mod ticket_handler;
 #[cfg(feature = "tickets")]
@@ -17,17 +20,35 @@ pub mod traits;
 pub use traits::{Singleton, ToMessage, ToList, Triggerable, MatchType, match_triggerables};
 
 pub mod usage_builder;
-pub use usage_builder::UsageBuilder;
+pub use usage_builder::{UsageBuilder, Decoration};
+
+pub mod arguments;
+pub use arguments::{ArgumentType, Argument, Arguments, ArgumentError};
+
+pub mod tokenizer;
+pub use tokenizer::{Token, tokenize, quote};
 
 pub mod mixed;
-pub use mixed::{BoxedFuture, Result, RegexManager, string_distance, parse_time, binary_search};
+pub use mixed::{BoxedFuture, Result, RegexManager, string_distance, parse_time, format_duration, binary_search};
 
 pub mod resolver;
-pub use resolver::{Resolver, is_trial};
+pub use resolver::Resolver;
+
+pub mod discord;
+pub use discord::{Discord, LiveDiscord};
+
+#[cfg(any(test, feature = "simulate"))]
+pub mod fake_guild;
+#[cfg(any(test, feature = "simulate"))]
+pub use fake_guild::FakeGuild;
 
 pub mod logger;
 pub use logger::Logger;
 
+pub mod locale;
+pub use locale::{Locale, Text, Catalog};
+pub use crate::text;
+
 pub mod log_builder;
 pub use log_builder::LogBuilder;
 
@@ -37,7 +58,7 @@ pub mod auto_moder;
 pub use auto_moder::AutoModerator;
 
 pub mod interaction_helper;
-pub use interaction_helper::InteractionHelper;
+pub use interaction_helper::{InteractionHelper, NavigationPage};
 
 pub mod threads;
 pub use threads::{periodic_checks, spawn};
@@ -24,7 +24,7 @@ impl<'a> PermissionHandler<'_> {
 
     async fn update_permissions(&self, overwrites: Vec<PermissionOverwrite>) {
         for overwrite in overwrites {
-            let _ = self.channel.create_permission(self.resolver, overwrite).await;
+            let _ = self.resolver.create_permission(self.channel.id, overwrite).await;
         }
     }
 
@@ -108,7 +108,7 @@ impl<'a> PermissionHandler<'_> {
 
     pub async fn remove_overwrites(&self, target: UserId) {
         let permission = PermissionOverwriteType::Member(target);
-        let _ = self.channel.delete_permission(self.resolver, permission).await;
+        let _ = self.resolver.delete_permission(self.channel.id, permission).await;
     }
 
 }
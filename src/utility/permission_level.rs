@@ -0,0 +1,61 @@
+use strum_macros::EnumIter;
+
+use std::fmt;
+use std::str::FromStr;
+
+
+// Who may use what, from everyone up to administrators. Each level includes
+// the ones below it, e.g. moderators may use everything trials may use. The
+// roles of each level are configured with the `role_<level>` keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+pub enum PermissionLevel {
+    Everyone,
+    Member,
+    Trusted,
+    Trial,
+    Mod,
+    HeadMod,
+    Admin,
+}
+
+impl PermissionLevel {
+
+    // the configuration key of the roles of a level, everyone needs no role
+    pub fn key(&self) -> Option<String> {
+        match self {
+            PermissionLevel::Everyone => None,
+            level => Some(format!("role_{}", level)),
+        }
+    }
+}
+
+impl FromStr for PermissionLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> std::result::Result<PermissionLevel, String> {
+        match level.to_lowercase().as_str() {
+            "everyone" => Ok(PermissionLevel::Everyone),
+            "member"   => Ok(PermissionLevel::Member),
+            "trusted"  => Ok(PermissionLevel::Trusted),
+            "trial"    => Ok(PermissionLevel::Trial),
+            "mod"      => Ok(PermissionLevel::Mod),
+            "headmod"  => Ok(PermissionLevel::HeadMod),
+            "admin"    => Ok(PermissionLevel::Admin),
+            _ => Err(format!("'{}' is not a permission level", level)),
+        }
+    }
+}
+
+impl fmt::Display for PermissionLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PermissionLevel::Everyone => write!(f, "everyone"),
+            PermissionLevel::Member   => write!(f, "member"),
+            PermissionLevel::Trusted  => write!(f, "trusted"),
+            PermissionLevel::Trial    => write!(f, "trial"),
+            PermissionLevel::Mod      => write!(f, "mod"),
+            PermissionLevel::HeadMod  => write!(f, "headmod"),
+            PermissionLevel::Admin    => write!(f, "admin"),
+        }
+    }
+}
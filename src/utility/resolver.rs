@@ -1,18 +1,21 @@
 
 use serenity::model::prelude::*;
 use serenity::prelude::*;
-use serenity::all::{CacheHttp, Cache, Http};
-use cached::proc_macro::cached;
-use cached::SizedCache;
+use serenity::all::{CacheHttp, Cache, Http, ShardMessenger};
+use serenity::builder::CreateChannel;
+use strum::IntoEnumIterator;
 
 use std::sync::Arc;
 
 use crate::utility::*;
+use crate::databases::BotConfig;
 
 
-#[derive(Debug, Clone)]
+// Resolves and changes what belongs to a guild. All reads and writes go
+// through the `Discord` implementation the resolver was created with.
+#[derive(Clone)]
 pub struct Resolver {
-    ctx: Context,
+    discord: Arc<dyn Discord>,
     guild_id: Option<GuildId>
 }
 
@@ -21,7 +24,7 @@ impl CacheHttp for Resolver {
         self.http()
     }
     fn cache(&self) -> Option<&Arc<Cache>> {
-        Some(self.cache())
+        self.cache()
     }
 }
 
@@ -33,154 +36,72 @@ impl AsRef<Http> for Resolver {
 
 impl Resolver {
 
-    pub const ATTEMPTS: usize = 3;
-
     pub fn new(ctx: Context, guild_id: Option<GuildId>) -> Self {
-        Resolver { ctx: ctx, guild_id: guild_id }
+        Resolver::with_discord(Arc::new(LiveDiscord::new(ctx)), guild_id)
     }
 
-    pub fn ctx(&self) -> &Context {
-        &self.ctx
+    pub fn with_discord(discord: Arc<dyn Discord>, guild_id: Option<GuildId>) -> Self {
+        Resolver { discord, guild_id }
     }
 
-    pub fn http(&self) -> &Arc<Http> {
-        &self.ctx.http
+    pub fn guild_id(&self) -> Option<GuildId> {
+        self.guild_id
     }
 
-    pub fn cache(&self) -> &Arc<Cache> {
-        &self.ctx.cache
+    pub fn discord(&self) -> &dyn Discord {
+        self.discord.as_ref()
     }
 
-    pub async fn attempt<T, F>(&self, f: impl Fn() -> F) -> Result<T>
-    where F: std::future::Future<Output = std::result::Result<T, serenity::Error>>
-    {
-        for _ in 0..Self::ATTEMPTS {
-            match f().await {
-                Ok(value) => return Ok(value),
-                Err(_) => continue
-            }
-        }
-        Err("Attempt limit reached".into())
+    pub fn http(&self) -> &Arc<Http> {
+        self.discord.http()
     }
 
-    pub async fn resolve_guild(&self, guild_id: Option<GuildId>) -> Option<Guild> {
-
-        let guild_id = match guild_id {
-            None => self.guild_id,
-            guild @ _ => guild
-        };
+    pub fn cache(&self) -> Option<&Arc<Cache>> {
+        self.discord.cache()
+    }
 
-        if let Some(guild_id) = guild_id {
+    // the shard of the connected client, e.g. to await interactions
+    pub fn shard(&self) -> Option<&ShardMessenger> {
+        self.discord.shard()
+    }
 
-            // attempt to get guild from cache
-            if let Some(guild) = guild_id.to_guild_cached(&self.ctx.cache) {
-                return Some(guild.clone());
-            }
+    fn require_guild(&self) -> Result<GuildId> {
+        self.guild_id.ok_or("Not in a guild".to_string())
+    }
 
-        }
-        None
+    pub async fn resolve_guild(&self, guild_id: Option<GuildId>) -> Option<Guild> {
+        let guild_id = guild_id.or(self.guild_id)?;
+        self.discord.guild(guild_id).await.ok()
     }
 
     pub async fn resolve_user(&self, user_id: UserId) -> Option<User> {
-
-        // first, attempt to get user from cache
-        if let Some(user) = user_id.to_user_cached(&self.ctx.cache) {
-            return Some(user.clone());
-        }
-
-        // if cache fails, attempt to get user over discord api
-        let user = self.attempt(|| async { self.ctx.http.get_user(user_id).await }).await;
-        if let Ok(user) = user {
-            return Some(user);
-        }
-
-        None
+        self.discord.user(user_id).await.ok()
     }
 
     pub async fn resolve_member(&self, user: &User) -> Option<Member> {
-
-        if let Some(guild_id) = self.guild_id {
-
-            // first, attempt to get member from cache
-            if let Some(guild) = self.ctx.cache.guild(guild_id) {
-                let member = guild.members.get(&user.id);
-                if let Some(member) = member {
-                    return Some(member.clone());
-                }
-            }
-
-            // if cache fails, attempt to get member over discord api
-            let member = self.attempt(|| async { guild_id.member(&self, user.id).await }).await;
-            if let Ok(member) = member {
-                return Some(member);
-            }
-
-        }
-        None
+        let guild_id = self.guild_id?;
+        self.discord.member(guild_id, user.id).await.ok()
     }
 
     pub async fn resolve_role(&self, role_name: impl ToList<&str>) -> Option<Vec<Role>> {
-
-        if let Some(guild_id) = self.guild_id {
-
-            // first, attempt to get roles from cache
-            if let Some(guild) = self.ctx.cache.guild(guild_id) {
-                let values: Vec<_> = role_name.to_list()
-                    .into_iter()
-                    .flat_map(|name| {
-                        guild.roles.values().find(|role| role.name == name)
-                    })
-                    .cloned()
-                    .collect();
-                if values.len() == role_name.to_list().len() {
-                    return Some(values);
-                }
-            }
-
-            // if cache fails, attempt to get roles over discord api
-            let roles = self.attempt(|| async { guild_id.roles(&self).await }).await;
-            if let Ok(guild_roles) = roles {
-                let values: Vec<_> = role_name.to_list()
-                    .into_iter()
-                    .flat_map(|name| {
-                        guild_roles.values().find(|role| role.name == name)
-                    })
-                    .cloned()
-                    .collect();
-                if values.len() == role_name.to_list().len() {
-                    return Some(values);
-                }
-            }
-
+        let guild_id = self.guild_id?;
+        let guild_roles = self.discord.roles(guild_id).await.ok()?;
+        let values: Vec<_> = role_name.to_list()
+            .into_iter()
+            .flat_map(|name| {
+                guild_roles.iter().find(|role| role.name == name)
+            })
+            .cloned()
+            .collect();
+        match values.len() == role_name.to_list().len() {
+            true  => Some(values),
+            false => None,
         }
-        None
     }
 
     pub async fn guild_channels(&self) -> Option<Vec<GuildChannel>> {
-
-        if let Some(guild_id) = self.guild_id {
-
-            // first, attempt to get channels from cache
-            if let Some(guild) = self.ctx.cache.guild(guild_id) {
-                let channels = guild.channels
-                    .values()
-                    .cloned()
-                    .collect();
-                return Some(channels);
-            }
-
-            // if cache fails, attempt to get channels over discord api
-            let channels = self.attempt(|| async { guild_id.channels(&self).await }).await;
-            if let Ok(channels) = channels {
-                let values = channels
-                    .values()
-                    .cloned()
-                    .collect();
-                return Some(values);
-            }
-
-        }
-        None
+        let guild_id = self.guild_id?;
+        self.discord.channels(guild_id).await.ok()
     }
 
     pub async fn resolve_guild_channel(&self, channel_id: ChannelId) -> Option<GuildChannel> {
@@ -207,16 +128,61 @@ impl Resolver {
     }
 
     pub async fn resolve_message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
-        let message = self.attempt(|| async { self.ctx.http.get_message(channel_id, message_id).await }).await;
-        match message {
-            Ok(message) => Some(message),
-            Err(_) => match self.ctx.cache.message(channel_id, message_id) {
-                Some(message) => Some(message.clone()),
-                None => None
-            }
-        }
+        self.discord.message(channel_id, message_id).await.ok()
+    }
+
+    // ---- Changes ---- //
+
+    pub async fn send_message(&self, channel_id: ChannelId, message: impl ToMessage) -> Result<Message> {
+        self.discord.send_message(channel_id, message.to_message()).await
+    }
+
+    pub async fn direct_message(&self, user_id: UserId, message: impl ToMessage) -> Result<Message> {
+        self.discord.direct_message(user_id, message.to_message()).await
+    }
+
+    pub async fn delete_message(&self, channel_id: ChannelId, message_id: MessageId) -> Result<()> {
+        self.discord.delete_message(channel_id, message_id).await
+    }
+
+    pub async fn delete_messages(&self, channel_id: ChannelId, message_ids: Vec<MessageId>) -> Result<()> {
+        self.discord.delete_messages(channel_id, message_ids).await
+    }
+
+    pub async fn add_role(&self, user_id: UserId, role_id: RoleId) -> Result<()> {
+        self.discord.add_role(self.require_guild()?, user_id, role_id).await
+    }
+
+    pub async fn remove_role(&self, user_id: UserId, role_id: RoleId) -> Result<()> {
+        self.discord.remove_role(self.require_guild()?, user_id, role_id).await
+    }
+
+    pub async fn create_channel(&self, channel: CreateChannel<'_>) -> Result<GuildChannel> {
+        self.discord.create_channel(self.require_guild()?, channel).await
+    }
+
+    pub async fn delete_channel(&self, channel_id: ChannelId) -> Result<()> {
+        self.discord.delete_channel(channel_id).await
     }
 
+    pub async fn create_permission(&self, channel_id: ChannelId, overwrite: PermissionOverwrite) -> Result<()> {
+        self.discord.create_permission(channel_id, overwrite).await
+    }
+
+    pub async fn delete_permission(&self, channel_id: ChannelId, kind: PermissionOverwriteType) -> Result<()> {
+        self.discord.delete_permission(channel_id, kind).await
+    }
+
+    pub async fn ban(&self, user_id: UserId, reason: &str) -> Result<()> {
+        self.discord.ban(self.require_guild()?, user_id, reason).await
+    }
+
+    pub async fn unban(&self, user_id: UserId) -> Result<()> {
+        self.discord.unban(self.require_guild()?, user_id).await
+    }
+
+    // ---- Users ---- //
+
     pub fn resolve_name(&self, user: &User) -> String {
         user.global_name.clone().unwrap_or(user.name.clone())
     }
@@ -229,39 +195,36 @@ impl Resolver {
         false
     }
 
-    pub async fn is_admin(&self, user: &User) -> bool {
-        let role_id = self.resolve_role("Administrator")
-            .await.unwrap()[0].id;
-        self.has_role(user, role_id).await
-    }
-
-    pub async fn is_headmod(&self, user: &User) -> bool {
-        let role_ids: Vec<_> = self.resolve_role(vec!["Administrator", "Head Moderator"])
-            .await.unwrap().iter().map(|role| role.id).collect();
-        self.has_role(user, role_ids).await
-    }
+    // The highest level a user has through the roles configured for each
+    // level. Users that are not members of the guild have no level.
+    pub async fn level(&self, user: &User) -> PermissionLevel {
+        let Some(member) = self.resolve_member(user).await else {
+            return PermissionLevel::Everyone;
+        };
+        if let Ok(config) = BotConfig::get(self).await {
+            return PermissionLevel::iter()
+                .rev()
+                .find(|level| config.roles(*level).iter().any(|role| member.roles.contains(role)))
+                .unwrap_or(PermissionLevel::Everyone);
+        }
 
-    pub async fn is_mod(&self, user: &User) -> bool {
-        let role_ids: Vec<_> = self.resolve_role(vec!["Administrator", "Head Moderator", "Moderator"])
-            .await.unwrap().iter().map(|role| role.id).collect();
-        self.has_role(user, role_ids).await
+        // An invalid configuration can only be repaired with commands, so
+        // the roles are read on their own and the owner is an administrator.
+        if self.resolve_guild(None).await.is_some_and(|guild| guild.owner_id == user.id) {
+            return PermissionLevel::Admin;
+        }
+        for level in PermissionLevel::iter().rev() {
+            let roles = BotConfig::level_roles(self.into(), level).await;
+            if roles.iter().any(|role| member.roles.contains(role)) {
+                return level;
+            }
+        }
+        PermissionLevel::Everyone
     }
 
-    pub async fn is_trial(&self, user: &User) -> bool {
-        let role_ids: Vec<_> = self.resolve_role(vec!["Administrator", "Head Moderator", "Moderator", "Trial Moderator"])
-            .await.unwrap().iter().map(|role| role.id).collect();
-        self.has_role(user, role_ids).await
+    pub async fn has_level(&self, user: &User, level: PermissionLevel) -> bool {
+        level == PermissionLevel::Everyone || self.level(user).await >= level
     }
 
 }
 
-
-#[cached(
-    ty = "SizedCache<UserId, bool>",
-    create = "{ SizedCache::with_size(100) }",
-    convert = r#"{ user.id }"#
-)]
-pub async fn is_trial(resolver: &Resolver, user: &User) -> bool {
-    resolver.is_trial(user).await
-}
-
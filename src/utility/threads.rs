@@ -14,6 +14,7 @@ use std::thread;
 use std::str::FromStr;
 
 use crate::databases::*;
+use crate::databases::Scope;
 use crate::utility::*;
 
 
@@ -28,17 +29,23 @@ pub async fn spawn(thread: BoxedFuture<'static, ()>) {
 pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
     Box::pin(async move {
         let mut db = DB::Config;
+        let mut scope = Scope::Global;
         Logger::info_long("Connected to database", db.to_string().as_str());
         loop {
-            let database = ConfigDB::get_instance();
+            let database = ConfigDB::get_instance().scope(scope);
             let input = Logger::input("Enter a command");
             let words = input.split_whitespace().collect::<Vec<&str>>();
 
             match words[0] {
+                "exit" => break,
                 "ls" => {
-                    let mut keys = database.get_keys().await;
-                    keys.sort();
-                    Logger::info_long("Keys", &keys.join(", "));
+                    match database.get_keys().await {
+                        Ok(mut keys) => {
+                            keys.sort();
+                            Logger::info_long("Keys", &keys.join(", "));
+                        }
+                        Err(err) => Logger::err(&err.to_string())
+                    }
                 }
                 "get" => {
                     match words.len() {
@@ -50,7 +57,7 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                             let value = database.get(key).await;
                             match value {
                                 Ok(value) => Logger::info_long(&format!("Value of {}", key), &value.to_string()),
-                                Err(err) => Logger::err(err.as_str())
+                                Err(err) => Logger::err(&err.to_string())
                             }
                         }
                         _ => {
@@ -62,7 +69,7 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                                             let values: Vec<_> = values.iter().map(|entry| entry.to_string()).collect();
                                             Logger::info_long(&format!("Values of {}", words[2]), &values.join(", "))
                                         }
-                                        Err(err) => Logger::err(err.as_str())
+                                        Err(err) => Logger::err(&err.to_string())
                                     }
                                 },
                                 _ => {
@@ -72,7 +79,7 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                                             let values: Vec<_> = values.iter().map(|entry| entry.to_string()).collect();
                                             Logger::info_long(&format!("Values of {}", &words[1..].join(", ")), &values.join(", "))
                                         }
-                                        Err(err) => Logger::err(err.as_str())
+                                        Err(err) => Logger::err(&err.to_string())
                                     }
                                 }
                             }
@@ -84,16 +91,13 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                         1..=2 => {
                             Logger::warn("Too few parameters");
                         }
-                        3 => {
-                            let key = words[1];
-                            let value = words[2];
-                            database.set(key, value).await;
-                            Logger::info_long(&format!("Set value for {}", key), value);
-                        }
                         _ => {
-                            let _key = words[1];
-                            let _values = &words[2..];
-                            Logger::warn("Currently not implemented!");
+                            let key = words[1];
+                            let values = words[2..].iter().map(|value| value.to_string()).collect();
+                            match config::change(scope, key, values, "console").await {
+                                Ok(change) => Logger::info_long(&format!("Set value for {}", key), &change.new_value),
+                                Err(err) => Logger::err(&err.to_string())
+                            }
                         }
                     }
                 }
@@ -101,8 +105,10 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                     match words.len() {
                         2 => {
                             let key = words[1];
-                            database.delete(key).await;
-                            Logger::info_long("Removed key", key);
+                            match config::change(scope, key, Vec::new(), "console").await {
+                                Ok(_) => Logger::info_long("Removed key", key),
+                                Err(err) => Logger::err(&err.to_string())
+                            }
                         }
                         _ => {
                             Logger::warn("Too many parameters");
@@ -114,16 +120,22 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                         1..=2 => {
                             Logger::warn("Too few parameters")
                         }
-                        3 => {
-                            let key = words[1];
-                            let value = words[2];
-                            database.append(key, value).await;
-                            Logger::info_long(&format!("Appended value to {}", key), value);
-                        }
                         _ => {
-                            let _key = words[1];
-                            let _values = &words[2..];
-                            Logger::warn("Currently not implemented!");
+                            let key = words[1];
+                            let values = match config::values(scope, key).await {
+                                Ok(values) => values,
+                                Err(err) => {
+                                    Logger::err(&err.to_string());
+                                    continue;
+                                }
+                            };
+                            let values = values.into_iter()
+                                .chain(words[2..].iter().map(|value| value.to_string()))
+                                .collect();
+                            match config::change(scope, key, values, "console").await {
+                                Ok(_) => Logger::info_long(&format!("Appended value to {}", key), &words[2..].join(" ")),
+                                Err(err) => Logger::err(&err.to_string())
+                            }
                         }
                     }
                 }
@@ -147,6 +159,83 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
                         }
                     }
                 }
+                "guild" => {
+                    match words.len() {
+                        2 => {
+                            // `global` or the ID of the guild whose rows are edited
+                            scope = Scope::from_id(words[1]);
+                            Logger::info_long("Switched to guild", scope.to_string().as_str());
+                        }
+                        1 => {
+                            Logger::warn("Too few parameters");
+                        }
+                        _ => {
+                            Logger::warn("Too many parameters");
+                        }
+                    }
+                }
+                "export" => {
+                    match words.len() {
+                        1 => {
+                            Logger::warn("Too few parameters");
+                        }
+                        2 | 3 => {
+                            let path = words[1];
+                            match words.get(2).copied().unwrap_or("json") {
+                                "json" => match archive::export_json(None).await {
+                                    Ok(content) => match std::fs::write(path, content) {
+                                        Ok(_) => Logger::info_long("Exported all databases to", path),
+                                        Err(err) => Logger::err(&err.to_string())
+                                    }
+                                    Err(err) => Logger::err(&err.to_string())
+                                },
+                                "csv" => match archive::export_csv(None).await {
+                                    Ok(files) => {
+                                        let written = std::fs::create_dir_all(path).and_then(|_| files.iter()
+                                            .try_for_each(|(name, content)| std::fs::write(format!("{}/{}", path, name), content)));
+                                        match written {
+                                            Ok(_) => Logger::info_long("Exported all databases to", path),
+                                            Err(err) => Logger::err(&err.to_string())
+                                        }
+                                    }
+                                    Err(err) => Logger::err(&err.to_string())
+                                },
+                                _ => Logger::warn("Invalid format, expected json or csv")
+                            }
+                        }
+                        _ => {
+                            Logger::warn("Too many parameters");
+                        }
+                    }
+                }
+                "import" => {
+                    match words.len() {
+                        2 => {
+                            let path = std::path::Path::new(words[1]);
+                            // a directory holds the csv files of an export
+                            let imported = match path.is_dir() {
+                                true => match read_csv_files(path) {
+                                    Ok(files) => archive::import_csv(None, files).await,
+                                    Err(err) => Err(DBError::Io(err.to_string()))
+                                },
+                                false => match std::fs::read_to_string(path) {
+                                    Ok(content) => archive::import_json(None, &content).await,
+                                    Err(err) => Err(DBError::Io(err.to_string()))
+                                }
+                            };
+                            match imported {
+                                Ok(count) => Logger::info_long("Imported rows", &count.to_string()),
+                                Err(err) => Logger::err(&err.to_string())
+                            }
+                        }
+                        1 => {
+                            Logger::warn("Too few parameters");
+                        }
+                        _ => {
+                            Logger::warn("Too many parameters");
+                        }
+                    }
+                }
                 _ => {
                     Logger::err("Invalid command");
                 }
@@ -155,142 +244,186 @@ pub fn database_interface<'a>() -> BoxedFuture<'a, ()> {
     })
 }
 
-pub fn periodic_checks<'a>(resolver: Resolver) -> BoxedFuture<'a, ()> {
+#[cfg(feature = "db_interface")]
+fn read_csv_files(directory: &std::path::Path) -> std::io::Result<Vec<(String, String)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "csv") {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            files.push((name, std::fs::read_to_string(&path)?));
+        }
+    }
+    Ok(files)
+}
+
+pub fn periodic_checks<'a>(ctx: Context) -> BoxedFuture<'a, ()> {
     Box::pin(async move {
-        let resolver = &resolver;
         let allowed_mentions = &CreateAllowedMentions::new();
 
         #[cfg(feature = "message_logs")]
         let mut last_message_logs_cleanup = Utc::now().timestamp();
 
-        loop {
+        // records are pruned once on startup, then every hour
+        let mut last_pruning = 0;
 
-            // check for scheduled messages
-            let users = ScheduleDB::get_instance().get_keys().await;
-            let now = chrono::Utc::now().timestamp();
+        loop {
 
-            // remove all pending webhooks
-            if let Some(guild) = resolver.resolve_guild(None).await {
-                let webhooks = guild.webhooks(resolver).await;
-                if let Ok(webhooks) = webhooks {
-                    for webhook in webhooks {
-                        let _ = webhook.delete(resolver).await;
+            // the cleanup of the message logs is due in all guilds at once
+            #[cfg(feature = "message_logs")]
+            let clean_message_logs = last_message_logs_cleanup + 60 * 60 < chrono::Utc::now().timestamp();
+
+            for guild_id in ctx.cache.guilds() {
+                let resolver = &Resolver::new(ctx.clone(), Some(guild_id));
+
+                // check for scheduled messages
+                let users = ScheduleDB::get_instance().scope(resolver).get_keys().await
+                    .unwrap_or_else(|err| {
+                        Logger::err_long("Failed to get scheduled messages", &err.to_string());
+                        Vec::new()
+                    });
+                let now = chrono::Utc::now().timestamp();
+
+                // remove all pending webhooks
+                if let Some(guild) = resolver.resolve_guild(None).await {
+                    let webhooks = guild.webhooks(resolver).await;
+                    if let Ok(webhooks) = webhooks {
+                        for webhook in webhooks {
+                            let _ = webhook.delete(resolver).await;
+                        }
                     }
                 }
-            }
-
-            // for all users that have scheduled messages
-            futures::stream::iter(users)
-                .map(|user| UserId::from(user.parse::<u64>().unwrap()))
-                .for_each_concurrent(None, |user| {
-                    async move {
-
-                        // if user could be resolved
-                        if let Some(user) = resolver.resolve_user(user).await {
-
-                            // get scheduled messages
-                            let scheduled_messages = ScheduleDB::get_instance()
-                                .get_all(&user.id.to_string()).await;
-
-                            if let Ok(scheduled_messages) = scheduled_messages {
-
-                                // for all scheduled messages
-                                for scheduled_message in scheduled_messages.into_iter() {
-
-                                    // check if message is expired
-                                    if scheduled_message.is_expired(now) {
-
-                                        // delete scheduled message from database
-                                        ScheduleDB::get_instance().delete_by_id(scheduled_message.id).await;
-
-                                        // create webhook
-                                        let channel_id = ChannelId::from_str(&scheduled_message.channel_id).unwrap();
-                                        let hook = channel_id.create_webhook(resolver,
-                                            CreateWebhook::new(resolver.resolve_name(&user))
-                                                .avatar(&CreateAttachment::url(resolver, &user.face()).await.unwrap())
-                                        ).await;
 
-                                        // if hook could be created
-                                        if let Ok(hook) = hook {
-
-                                            // create embed
-                                            let execute = ExecuteWebhook::new()
-                                                .content(scheduled_message.message)
-                                                .allowed_mentions(allowed_mentions.clone());
-                                            let _ = hook.execute(resolver, false, execute).await;
+                // for all users that have scheduled messages
+                futures::stream::iter(users)
+                    .map(|user| UserId::from(user.parse::<u64>().unwrap()))
+                    .for_each_concurrent(None, |user| {
+                        async move {
+
+                            // if user could be resolved
+                            if let Some(user) = resolver.resolve_user(user).await {
+
+                                // get scheduled messages
+                                let scheduled_messages = ScheduleDB::get_instance().scope(resolver)
+                                    .get_all(&user.id.to_string()).await;
+
+                                if let Ok(scheduled_messages) = scheduled_messages {
+
+                                    // for all scheduled messages
+                                    for scheduled_message in scheduled_messages.into_iter() {
+
+                                        // check if message is expired
+                                        if scheduled_message.is_expired(now) {
+
+                                            // delete scheduled message from database
+                                            // (skip it on failure, so it is not sent twice)
+                                            if let Err(err) = ScheduleDB::get_instance().scope(resolver).delete_by_id(scheduled_message.id).await {
+                                                Logger::err_long("Failed to delete scheduled message", &err.to_string());
+                                                continue;
+                                            }
+
+                                            // create webhook
+                                            let channel_id = ChannelId::from_str(&scheduled_message.channel_id).unwrap();
+                                            let hook = channel_id.create_webhook(resolver,
+                                                CreateWebhook::new(resolver.resolve_name(&user))
+                                                    .avatar(&CreateAttachment::url(resolver, &user.face()).await.unwrap())
+                                            ).await;
+
+                                            // if hook could be created
+                                            if let Ok(hook) = hook {
+
+                                                // create embed
+                                                let execute = ExecuteWebhook::new()
+                                                    .content(scheduled_message.message)
+                                                    .allowed_mentions(allowed_mentions.clone());
+                                                let _ = hook.execute(resolver, false, execute).await;
+                                            }
                                         }
                                     }
                                 }
                             }
-                        }
 
-                        // discard all pending schedules
-                        else {
-                            ScheduleDB::get_instance().delete(&user.to_string()).await;
+                            // discard all pending schedules
+                            else {
+                                if let Err(err) = ScheduleDB::get_instance().scope(resolver).delete(&user.to_string()).await {
+                                    Logger::err_long("Failed to discard scheduled messages", &err.to_string());
+                                }
+                            }
                         }
-                    }
-                }).await;
-
+                    }).await;
 
-            // check for reminders
-            let users = RemindersDB::get_instance().get_keys().await;
-            let now = chrono::Utc::now().timestamp();
 
-            // for all users that have reminders
-            futures::stream::iter(users)
-                .map(|user| UserId::from(user.parse::<u64>().unwrap()))
-                .for_each_concurrent(None, |user| {
-                    async move {
+                // check for reminders
+                let users = RemindersDB::get_instance().scope(resolver).get_keys().await
+                    .unwrap_or_else(|err| {
+                        Logger::err_long("Failed to get reminders", &err.to_string());
+                        Vec::new()
+                    });
+                let now = chrono::Utc::now().timestamp();
 
-                        // get reminders messages
-                        let reminders = RemindersDB::get_instance().get_all(&user.to_string()).await;
+                // for all users that have reminders
+                futures::stream::iter(users)
+                    .map(|user| UserId::from(user.parse::<u64>().unwrap()))
+                    .for_each_concurrent(None, |user| {
+                        async move {
 
-                        if let Ok(reminders) = reminders {
+                            // get reminders messages
+                            let reminders = RemindersDB::get_instance().scope(resolver).get_all(&user.to_string()).await;
 
-                            // for all reminders
-                            for reminder in reminders.into_iter() {
+                            if let Ok(reminders) = reminders {
 
-                                // check if reminder is expired
-                                if reminder.is_expired(now) {
+                                // for all reminders
+                                for reminder in reminders.into_iter() {
 
-                                    // delete reminder from database
-                                    RemindersDB::get_instance().delete_by_id(reminder.id).await;
+                                    // check if reminder is expired
+                                    if reminder.is_expired(now) {
 
-                                    // create embed
-                                    let embed = MessageManager::create_embed(|embed| {
-                                        embed
-                                            .title("Reminder")
-                                            .description(reminder.message)
-                                            .color(0x00FF00)
-                                    }).await;
-                                    let embed = CreateMessage::new()
-                                        .content(format!("<@{}>", user.to_string()))
-                                        .embed(embed);
+                                        // delete reminder from database
+                                        // (skip it on failure, so it is not sent twice)
+                                        if let Err(err) = RemindersDB::get_instance().scope(resolver).delete_by_id(reminder.id).await {
+                                            Logger::err_long("Failed to delete reminder", &err.to_string());
+                                            continue;
+                                        }
 
-                                    let channel = ChannelId::from_str(&reminder.channel_id).unwrap();
-                                    let _ = channel.send_message(resolver, embed).await;
+                                        // create embed
+                                        let title = Text::new("remind.reminder-title")
+                                            .localize(Locale::of_user(resolver, user).await);
+                                        let embed = MessageManager::create_embed(|embed| {
+                                            embed
+                                                .title(title)
+                                                .description(reminder.message)
+                                                .color(0x00FF00)
+                                        }).await;
+                                        let embed = CreateMessage::new()
+                                            .content(format!("<@{}>", user))
+                                            .embed(embed);
+
+                                        let channel = ChannelId::from_str(&reminder.channel_id).unwrap();
+                                        let _ = channel.send_message(resolver, embed).await;
+                                    }
                                 }
                             }
                         }
-                    }
-                }).await;
-
-            // clean message logs
-            #[cfg(feature = "message_logs")]
-            {
+                    }).await;
 
                 // clean message logs every hour
-                if last_message_logs_cleanup + 60 * 60 < chrono::Utc::now().timestamp() {
+                #[cfg(feature = "message_logs")]
+                if clean_message_logs {
 
                     // get message logs channel
-                    let channel_id: ChannelId = ConfigDB::get_instance().get("channel_messagelogs").await.unwrap().into();
-                    let channel = resolver.resolve_guild_channel(channel_id).await.unwrap();
+                    let channel = match BotConfig::get(resolver).await {
+                        Ok(config) => resolver.resolve_guild_channel(config.channel_messagelogs).await,
+                        Err(err) => {
+                            Logger::err_long("Failed to get message logs channel", &err.to_string());
+                            None
+                        }
+                    };
 
                     // initialize search parameters
                     let one_week_ago = Utc::now().timestamp() - 60 * 60 * 24 * 7;
                     let get_oldest = GetMessages::new().after(MessageId::from(1)).limit(100);
 
-                    loop {
+                    while let Some(channel) = &channel {
 
                         // get oldest messages
                         let oldest_messages = channel.messages(resolver, get_oldest.clone()).await;
@@ -361,15 +494,30 @@ pub fn periodic_checks<'a>(resolver: Resolver) -> BoxedFuture<'a, ()> {
                         }
                     }
 
-                    last_message_logs_cleanup = Utc::now().timestamp();
                 }
             }
 
+            #[cfg(feature = "message_logs")]
+            if clean_message_logs {
+                last_message_logs_cleanup = Utc::now().timestamp();
+            }
+
+            // prune records that exceeded their retention
+            if last_pruning + 60 * 60 < chrono::Utc::now().timestamp() {
+                match retention::prune().await {
+                    Ok(0) => {},
+                    Ok(count) => Logger::info_long("Pruned expired records", &count.to_string()),
+                    Err(err) => Logger::err_long("Failed to prune expired records", &err.to_string()),
+                }
+                last_pruning = Utc::now().timestamp();
+            }
+
             // remind staff if last message in ticket is by a member
             // and longer than 10 minutes ago
             #[cfg(feature = "tickets")]
             {
 
+                let resolver = &Resolver::new(ctx.clone(), None);
                 let tickets = TicketHandler::get_instance().tickets.read().unwrap().clone();
                 let _ = futures::stream::iter(tickets.values())
                     .for_each_concurrent(None, |ticket| async {
@@ -403,9 +551,12 @@ pub fn hook_ticket_selector<'a>(resolver: Resolver, channel: GuildChannel) -> Bo
 
         // listen for reactions
         let resolver = &resolver;
+        let Some(shard) = resolver.shard() else {
+            return;
+        };
         let mut last_reaction = (None, chrono::Utc::now().timestamp());
         let mut reactions = channel
-            .await_reaction(&resolver.ctx().shard)
+            .await_reaction(shard)
             .stream();
 
         while let Some(reaction) = reactions.next().await {
@@ -2,7 +2,7 @@
 use serenity::model::permissions::Permissions;
 use serenity::model::channel::GuildChannel;
 use serenity::model::id::{ChannelId, RoleId, UserId};
-use serenity::builder::{CreateChannel, GetMessages};
+use serenity::builder::CreateChannel;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 use futures::stream::StreamExt;
@@ -109,8 +109,10 @@ impl TicketHandler {
         if let Some(channels) = channels {
 
             // get the ticket category
-            let ticket_category = ConfigDB::get_instance()
-                .get("category_tickets").await.unwrap().to_string();
+            let ticket_category = match BotConfig::get(resolver).await {
+                Ok(config) => config.category_tickets.to_string(),
+                Err(err) => return Logger::err_long("Failed to get ticket category", &err.to_string()),
+            };
 
             // recover all tickets
             let parse = futures::stream::iter(channels.iter()
@@ -140,8 +142,10 @@ impl TicketHandler {
         Logger::info("Hooking ticket selector");
 
         // get channel
-        let channel_id: ChannelId = ConfigDB::get_instance()
-            .get("channel_tickets").await.unwrap().into();
+        let channel_id: ChannelId = match BotConfig::get(resolver).await {
+            Ok(config) => config.channel_tickets,
+            Err(err) => return Logger::err_long("Failed to get ticket channel", &err.to_string()),
+        };
         let channel = resolver.resolve_guild_channel(channel_id).await.unwrap();
 
         spawn(hook_ticket_selector(resolver.clone(), channel)).await;
@@ -162,39 +166,31 @@ impl TicketHandler {
         #[cfg(feature = "debug")]
         Logger::info_long("Start", logstr);
 
-        // resolve guild
-        let guild = resolver.resolve_guild(None).await;
-        if let Some(guild) = guild {
+        // tickets only exist in guilds
+        if resolver.guild_id().is_some() {
 
             // get the ticket category
-            let ticket_category = ConfigDB::get_instance()
-                .get("category_tickets").await.unwrap().to_string();
-            let ticket_category = ticket_category.parse::<u64>().unwrap();
+            let ticket_category = BotConfig::get(resolver).await
+                .map_err(|err| err.to_string())?
+                .category_tickets;
 
             // create new channel
             let builder = CreateChannel::new(resolver.resolve_name(target))
-                .category(ChannelId::from(ticket_category))
+                .category(ticket_category)
                 .topic(ticket_type);
-            let channel = guild.create_channel(resolver, builder).await;
+            let channel = resolver.create_channel(builder).await;
 
             if let Ok(channel) = channel {
 
                 // figure out allowed roles
-                let mut allowed_roles = vec!["Administrator", "Head Moderator", "Moderator", "Trial Moderator"];
-                match ticket_type {
-                    TicketType::StaffReport => {
-                        let _ = allowed_roles.pop().unwrap();
-                        let _ = allowed_roles.pop().unwrap();
-                    },
-                    TicketType::UserReport => {
-                        let _ = allowed_roles.pop().unwrap();
-                    },
-                    _ => {},
+                let level = match ticket_type {
+                    TicketType::StaffReport => PermissionLevel::HeadMod,
+                    TicketType::UserReport => PermissionLevel::Mod,
+                    _ => PermissionLevel::Trial,
                 };
-
-                let allowed_roles = resolver.resolve_role(allowed_roles).await.unwrap().iter()
-                    .map(|role| role.id)
-                    .collect::<Vec<RoleId>>();
+                let allowed_roles = BotConfig::get(resolver).await
+                    .map_err(|err| err.to_string())?
+                    .roles_from(level);
 
                 let pings = format!("<@{}>", target.id);
                 let pings = format!("{pings} {}",
@@ -203,17 +199,17 @@ impl TicketHandler {
                         _ => allowed_roles.iter().map(|role| format!("<@&{}>", role)).collect::<Vec<String>>().join(" "),
                     });
 
-                let support_response = "Support will be with you shortly. It should not take longer than 10 minutes.";
-                let discuss_response = "If you **do not** respond within **2 hours**, this ticket will be closed and **appropriate action** will be taken.";
-
+                // the introduction is written for the member the ticket is for
                 let introduction_message = match ticket_type {
-                    TicketType::Muted       => format!("A staff member created this **muted ticket** with you to discuss your warnings. {}", discuss_response),
-                    TicketType::Discussion  => format!("A staff member created this **discussion ticket** with you to discuss a situation you were involved in. {}", discuss_response),
-                    TicketType::StaffReport => format!("{} Please provide the ID of the staff member you are reporting as well as any photo evidence or channel links relevant to this report.", support_response),
-                    TicketType::UserReport  => format!("{} Please provide the ID of the user you are reporting as well as any photo evidence or channel links relevant to this report.", support_response),
-                    TicketType::BugReport   => format!("{} Please provide photo evidence or channel links of the bug you are reporting.", support_response),
-                    TicketType::Question    => format!("{} Ask any server-related questions and a staff member will be able to help you out.", support_response),
+                    TicketType::Muted       => "ticket.introduction-muted",
+                    TicketType::Discussion  => "ticket.introduction-discussion",
+                    TicketType::StaffReport => "ticket.introduction-staff-report",
+                    TicketType::UserReport  => "ticket.introduction-user-report",
+                    TicketType::BugReport   => "ticket.introduction-bug-report",
+                    TicketType::Question    => "ticket.introduction-question",
                 };
+                let introduction_message = Text::new(introduction_message)
+                    .localize(Locale::of_user(resolver, target.id).await);
                 let embed = MessageManager::create_embed(|embed| {
                     embed.description(introduction_message)
                 }).await;
@@ -237,8 +233,8 @@ impl TicketHandler {
                 ticket.allow_participants().await;
 
                 // ping members and staff after they have been allowed
-                let _ = channel.send_message(resolver, pings.to_message()).await;
-                let _ = channel.send_message(resolver, embed.to_message()).await;
+                let _ = resolver.send_message(channel.id, pings).await;
+                let _ = resolver.send_message(channel.id, embed).await;
 
                 // add ticket to the ticket handler
                 self.tickets.write().expect("Could not get tickets")
@@ -265,7 +261,9 @@ impl TicketHandler {
             Logger::info_long("Start", logstr);
 
             ticket.deny_all().await;
-            let _ = ticket.channel.delete(&ticket.resolver).await;
+            let _ = ticket.resolver.delete_channel(ticket.channel.id).await;
+            self.tickets.write().expect("Could not get tickets")
+                .remove(&channel.to_string());
 
             #[cfg(feature = "debug")]
             Logger::info_long("End", logstr);
@@ -292,7 +290,7 @@ impl Ticket {
     }
 
     async fn append_user(resolver: &Resolver, user: &User, members: &mut HashSet<UserId>, staff: &mut HashSet<UserId>) {
-        match is_trial(resolver, user).await {
+        match resolver.has_level(user, PermissionLevel::Trial).await {
             true  => staff.insert(user.id),
             false => members.insert(user.id),
         };
@@ -309,13 +307,15 @@ impl Ticket {
         if let Some(last_message) = last_message {
 
             // get all messages in the channel
-            let builder = GetMessages::new().before(last_message).limit(255);
-            let messages = channel.messages(&resolver, builder).await;
+            let messages = resolver.discord()
+                .messages(channel.id, last_message, 255).await;
 
             if let Ok(messages) = &messages {
 
-                let bot_id = &ConfigDB::get_instance()
-                    .get("bot_id").await.unwrap().to_string();
+                let bot_id = &BotConfig::get(resolver).await
+                    .map_err(|err| err.to_string())?
+                    .bot_id
+                    .to_string();
                 let regex = Arc::new(RegexManager::get_id_regex());
 
                 // get all present staff and members in the ticket
@@ -343,20 +343,19 @@ impl Ticket {
                             continue;
                         }
 
-                        // parse embeds
+                        // parse embeds, in any language the guild had
                         if let Some(description) = &message.embeds[0].description {
-                            let splits = &description.split_whitespace().collect::<Vec<&str>>();
-                            let user_id = regex.find(splits.last().unwrap())
-                                .map(|hit| UserId::from(hit.as_str().parse::<u64>().unwrap()))
-                                .unwrap_or(UserId::from(1));
-                            let user = &resolver.resolve_user(user_id).await;
-                            if let Some(user) = user {
-                                match splits[0] {
-                                    "Added" | "Removed" | "Claimed" | "Unclaimed" =>
-                                        Ticket::append_user(resolver, user, &mut present_members, &mut present_staff).await,
-                                    _ => {}
-                                };
-                            };
+                            let mention = ["ticket.added", "ticket.removed", "ticket.claimed", "ticket.unclaimed"].iter()
+                                .filter_map(|id| Catalog::get_instance().arguments(id, description))
+                                .find_map(|mut arguments| arguments.remove("user").or(arguments.remove("staff")));
+                            let user_id = mention.as_deref()
+                                .and_then(|mention| regex.find(mention))
+                                .map(|hit| UserId::from(hit.as_str().parse::<u64>().unwrap()));
+                            if let Some(user_id) = user_id {
+                                if let Some(user) = &resolver.resolve_user(user_id).await {
+                                    Ticket::append_user(resolver, user, &mut present_members, &mut present_staff).await;
+                                }
+                            }
                         }
                     }
                 }
@@ -372,11 +371,9 @@ impl Ticket {
                 }
 
                 // get all allowed staff roles for the ticket
-                let mut allowed_roles = resolver.resolve_role(
-                    vec!["Administrator", "Head Moderator", "Moderator", "Trial Moderator"])
-                        .await.unwrap().iter()
-                        .map(|role| role.id)
-                        .collect::<Vec<RoleId>>();
+                let mut allowed_roles = BotConfig::get(resolver).await
+                    .map_err(|err| err.to_string())?
+                    .roles_from(PermissionLevel::Trial);
 
                 // get mentioned roles
                 let role_mentions = first_message.get_mentioned_roles().await;
@@ -502,8 +499,10 @@ impl Ticket {
         #[cfg(feature = "debug")]
         Logger::info_long("Start", logstr);
 
-        let token = ConfigDB::get_instance()
-            .get("token").await.unwrap().to_string();
+        let token = match ConfigDB::get_instance().global().get("token").await {
+            Ok(token) => token.to_string(),
+            Err(err) => return Logger::err_long("Failed to transcribe ticket", &err.to_string()),
+        };
 
         if cfg!(target_os = "linux") {
             let process = Command::new("python3")
@@ -531,18 +530,19 @@ impl Ticket {
 
     pub async fn ping_staff(&self) {
 
-        // create reminder
-        let reminder = MessageManager::create_embed(|embed| {
-            embed
-                .title("Ticket Reminder")
-                .description(format!("Members in <#{}> have been left on read for at least **10 minutes**.", self.channel.id))
-        }).await;
-
-        // ping all staff
+        // ping all staff, each in their own language
         let _ = futures::stream::iter(self.present_staff.lock().await.iter())
             .for_each_concurrent(None, |staff| async {
-                let user = self.resolver.resolve_user(*staff).await.unwrap();
-                let _ = user.dm(self.resolver.clone(), reminder.to_message()).await;
+                let locale = Locale::of_user(&self.resolver, *staff).await;
+                let title = Text::new("ticket.reminder-title").localize(locale);
+                let description = text!("ticket.reminder", channel = format!("<#{}>", self.channel.id))
+                    .localize(locale);
+                let reminder = MessageManager::create_embed(|embed| {
+                    embed
+                        .title(title)
+                        .description(description)
+                }).await;
+                let _ = self.resolver.direct_message(*staff, reminder).await;
             }).await;
         *self.pinged_staff.lock().await = true;
     }
@@ -0,0 +1,205 @@
+
+// A word of a command. Double quotes group several words into one token and
+// a backslash takes the following quote, dash or backslash as it is, both are
+// removed from the text of the token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub text: String,
+    // the name of a flag like `-title` or `--title`, without its dashes
+    pub flag: Option<String>,
+    // the value of a flag that is written as `-key=value`
+    pub value: Option<String>,
+}
+
+impl Token {
+
+    pub fn plain(text: impl Into<String>) -> Token {
+        Token { text: text.into(), flag: None, value: None }
+    }
+
+    // Tokens that start with a quote or an escaped dash are never flags,
+    // neither are negative numbers like `-1`.
+    fn new(text: String, literal: bool) -> Token {
+        if literal {
+            return Token::plain(text);
+        }
+        let name = text.strip_prefix("--")
+            .or_else(|| text.strip_prefix('-'))
+            .filter(|name| name.starts_with(|c: char| c.is_alphabetic()));
+        let Some(name) = name else {
+            return Token::plain(text);
+        };
+        let (flag, value) = match name.split_once('=') {
+            Some((flag, value)) => (flag.to_lowercase(), Some(value.to_string())),
+            None => (name.to_lowercase(), None),
+        };
+        Token { flag: Some(flag), value, text }
+    }
+
+    pub fn is_flag(&self) -> bool {
+        self.flag.is_some()
+    }
+}
+
+pub fn tokenize(content: &str) -> Vec<Token> {
+
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut started = false;
+    let mut literal = false;
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('"' | '-' | '\\')) => {
+                literal |= !started;
+                started = true;
+                text.push(chars.next().unwrap());
+            }
+            '"' => {
+                literal |= !started;
+                started = true;
+                quoted = !quoted;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    tokens.push(Token::new(std::mem::take(&mut text), literal));
+                }
+                started = false;
+                literal = false;
+            }
+            c => {
+                started = true;
+                text.push(c);
+            }
+        }
+    }
+
+    // a quote that is never closed reaches up to the end of the message
+    if started {
+        tokens.push(Token::new(text, literal));
+    }
+    tokens
+}
+
+// Writes a text so that it is read back as a single token.
+pub fn quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && !text.starts_with('-')
+        && !text.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\');
+    match plain {
+        true  => text.to_string(),
+        false => format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::commands::*;
+    use crate::utility::{Arguments, ArgumentError};
+
+    fn texts(content: &str) -> Vec<String> {
+        tokenize(content).into_iter().map(|token| token.text).collect()
+    }
+
+    fn parse(command: impl Command, content: &str) -> Result<Arguments, ArgumentError> {
+        command.define_usage().parse(&tokenize(content)[1..])
+    }
+
+    const USER: &str = "<@123456789012345678>";
+
+    #[test]
+    fn quotes_group_words() {
+        assert_eq!(texts(r#"?notes "Edate rules" rest"#), vec!["?notes", "Edate rules", "rest"]);
+        assert_eq!(texts(r#"?say "unterminated quote"#), vec!["?say", "unterminated quote"]);
+        assert_eq!(texts(r#"?say "" empty"#), vec!["?say", "", "empty"]);
+        assert_eq!(texts(r#"?say "a \"quote\"""#), vec!["?say", r#"a "quote""#]);
+    }
+
+    #[test]
+    fn flags() {
+        let tokens = tokenize("?cmd -title --long -key=value -KEY=\"two words\" -1 - --");
+        let flags = tokens.iter().map(|token| token.flag.clone()).collect::<Vec<_>>();
+        assert_eq!(flags, vec![None, Some("title".into()), Some("long".into()), Some("key".into()),
+                               Some("key".into()), None, None, None]);
+        assert_eq!(tokens[3].value.as_deref(), Some("value"));
+        assert_eq!(tokens[4].value.as_deref(), Some("two words"));
+        assert_eq!(tokens[2].value, None);
+    }
+
+    #[test]
+    fn escapes() {
+        let tokens = tokenize(r#"?cmd \-monthly "-quoted" \\ ¯\_(ツ)_/¯"#);
+        assert!(tokens.iter().all(|token| !token.is_flag()));
+        assert_eq!(tokens[1].text, "-monthly");
+        assert_eq!(tokens[2].text, "-quoted");
+        assert_eq!(tokens[3].text, r"\");
+        assert_eq!(tokens[4].text, r"¯\_(ツ)_/¯");
+    }
+
+    #[test]
+    fn quote_round_trip() {
+        for text in ["plain", "two words", "-dash", r#"a "quote""#, r"back\slash", ""] {
+            let tokens = tokenize(&quote(text));
+            assert_eq!(tokens, vec![Token::plain(text)], "{}", text);
+        }
+    }
+
+    #[test]
+    fn poll_usage() {
+        let arguments = parse(PollCommand, r#"?poll -title "What is best?" -opts +Self-care +Sleep"#).unwrap();
+        assert_eq!(arguments.text("title"), Some("What is best?"));
+        assert_eq!(arguments.text("opts"), Some("+Self-care +Sleep"));
+
+        let arguments = parse(PollCommand, "?poll --title=Colors -opts +Red -opts +Blue").unwrap();
+        assert_eq!(arguments.text("title"), Some("Colors"));
+        assert_eq!(arguments.text("opts"), Some("+Red +Blue"));
+
+        let error = parse(PollCommand, "?poll -title Colors").unwrap_err();
+        assert_eq!(error.position, 2);
+        assert!(parse(PollCommand, "?poll -title -opts +Red").is_err());
+    }
+
+    #[test]
+    fn flag_usage() {
+        let arguments = parse(FlagCommand, &format!("?flag {} spamming -1 links -monthly", USER)).unwrap();
+        assert_eq!(arguments.user("user").map(|id| id.get()), Some(123456789012345678));
+        assert_eq!(arguments.text("reason"), Some("spamming -1 links"));
+        assert!(arguments.has("monthly"));
+
+        let arguments = parse(FlagCommand, &format!(r"?flag {} said \-monthly", USER)).unwrap();
+        assert_eq!(arguments.text("reason"), Some("said -monthly"));
+        assert!(!arguments.has("monthly"));
+
+        assert!(parse(FlagCommand, &format!("?flag {} -monthly=yes", USER)).is_err());
+        assert!(parse(FlagCommand, "?flag being bad").is_err());
+    }
+
+    #[test]
+    fn review_usage() {
+        let arguments = parse(ReviewCommand, r#"?review -approve "great job" overall"#).unwrap();
+        assert!(arguments.has("approve"));
+        assert_eq!(arguments.text("notes"), Some("great job overall"));
+
+        let arguments = parse(ReviewCommand, "?review --deny").unwrap();
+        assert!(arguments.has("deny"));
+        assert_eq!(arguments.text("notes"), None);
+
+        assert!(parse(ReviewCommand, "?review looks good").is_err());
+    }
+
+    #[test]
+    fn notes_usage() {
+        let arguments = parse(NotesCommand, r#"?notes "Edate rules""#).unwrap();
+        assert_eq!(arguments.text("label"), Some("Edate rules"));
+
+        let arguments = parse(NotesCommand, "?notes -list").unwrap();
+        assert!(arguments.has("list"));
+
+        let arguments = parse(NotesCommand, r"?notes \-list").unwrap();
+        assert_eq!(arguments.text("label"), Some("-list"));
+    }
+}
@@ -14,6 +14,7 @@ use crate::utility::auto_moder::AutoModerator;
 use crate::utility::ticket_handler::TicketHandler;
 use crate::utility::message_manager::MessageManager;
 use crate::utility::mixed::{string_distance, BoxedFuture, Result};
+use crate::text;
 use crate::databases::*;
 
 
@@ -275,7 +276,7 @@ pub async fn match_triggerables<'a>
         }).collect::<Vec<_>>();
 
     if buttons.is_empty() {
-        message.reply_failure("No match found").await;
+        message.reply_failure("reply.no-match").await;
         return Err("No match found".to_string());
     }
 
@@ -283,9 +284,10 @@ pub async fn match_triggerables<'a>
     let message_content = message.words.join(" ");
     let hit = message_content.to_lowercase().find(compare).unwrap();
     let highlight = " ".repeat(hit) + &"^".repeat(compare.len());
+    let title = message.text(text!("reply.did-you-mean", user = message.resolve_name())).await;
     let embed = MessageManager::create_embed(|embed| {
         embed
-            .title(format!("{}, did you mean ...", message.resolve_name()))
+            .title(title)
             .description(&format!("`{message_content}`\n`{}`", highlight))
     }).await;
 
@@ -1,28 +1,101 @@
 
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, ResolvedOption, ResolvedValue};
 use nonempty::NonEmpty;
 
+use std::collections::{HashMap, HashSet};
+
 use crate::commands::command::CommandType;
 use crate::utility::*;
 
 
+// What a decorator asks for when the message of a command lacks it. The
+// parameters it stands for may therefore be left out of the message.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Decoration {
+    User,
+    Number,
+}
+
+#[derive(PartialEq)]
 enum ParameterType {
     Constant,
     Required,
     Optional,
 }
 
+// Parameters starting with a dash are named, they may be written anywhere
+// and their value is written as `-key=value` or reaches up to the next named
+// parameter. All others are positional, typed ones take the first word of
+// their type and the others take the words left in the order they are written.
 struct Parameter {
     name: String,
+    label: String,
     param_type: ParameterType,
+    arg_type: ArgumentType,
+}
+
+impl Parameter {
+
+    fn new(name: &str, param_type: ParameterType, arg_type: ArgumentType) -> Parameter {
+        let (name, label) = match name.starts_with('-') {
+            true  => name.split_once(' ').unwrap_or((name, &name[1..])),
+            false => (name, name),
+        };
+        Parameter { name: name.to_string(), label: label.to_string(), param_type, arg_type }
+    }
+
+    fn is_named(&self) -> bool {
+        self.name.starts_with('-')
+    }
+
+    fn is_literal(&self) -> bool {
+        self.param_type == ParameterType::Constant
+    }
+
+    fn key(&self) -> String {
+        UsageBuilder::option_name(&self.name)
+    }
+
+    fn display(&self) -> String {
+        let text = match self.arg_type {
+            _ if !self.is_named() => self.name.clone(),
+            ArgumentType::Flag => self.name.clone(),
+            _ => format!("{} ({})", self.name, self.label),
+        };
+        match self.param_type {
+            ParameterType::Constant => text,
+            ParameterType::Required if self.is_named() => text,
+            ParameterType::Required => format!("({})", text),
+            ParameterType::Optional => format!("[{}]", text),
+        }
+    }
 }
 
 type Usage = Vec<Parameter>;
 
+// An option of a slash command. When the command is run, the value of the
+// option is written into the message in place of the parameter it stands for.
+// Discord rejects commands and subcommands with more options.
+const MAX_OPTIONS: usize = 25;
+
+struct SlashOption {
+    name: String,
+    description: String,
+    kind: CommandOptionType,
+    required: bool,
+    // written in front of the value, e.g. `-title `
+    prefix: String,
+    choices: Vec<String>,
+}
+
 pub struct UsageBuilder {
     pub command_type: CommandType,
     pub triggers: NonEmpty<String>,
+    // who may use the command unless the guild configured another level
+    pub level: Option<PermissionLevel>,
     usage: Vec<Usage>,
     example: Option<String>,
+    decorations: Vec<Decoration>,
 }
 
 impl UsageBuilder {
@@ -31,11 +104,18 @@ impl UsageBuilder {
         UsageBuilder {
             command_type: command_type,
             triggers: triggers,
+            level: None,
             usage: Vec::new(),
             example: None,
+            decorations: Vec::new(),
         }
     }
 
+    pub fn level(mut self, level: PermissionLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
     pub fn new_usage(mut self) -> Self {
         self.usage.push(Vec::new());
         self
@@ -51,40 +131,25 @@ impl UsageBuilder {
         self
     }
 
+    // Words that have to be written as they are, like `export`, and flags
+    // like `-list`. Flags that require content take the words following them.
     pub fn add_constant<'a>(mut self, parameter: impl ToList<&'a str>, require_content: bool) -> Self {
         for name in parameter.to_list().into_iter() {
-            self = self.add_parameter(Parameter {
-                name: name.to_string(),
-                param_type: ParameterType::Constant,
+            self = self.add_parameter(match (name.starts_with('-'), require_content) {
+                (true, true)  => Parameter::new(name, ParameterType::Required, ArgumentType::Text),
+                (true, false) => Parameter::new(name, ParameterType::Required, ArgumentType::Flag),
+                (false, _)    => Parameter::new(name, ParameterType::Constant, ArgumentType::Flag),
             });
-            if require_content {
-                self = self.add_parameter(Parameter {
-                    name: name[1..].to_string(),
-                    param_type: ParameterType::Required,
-                });
-            }
         }
         self
     }
 
-    pub fn add_required<'a>(mut self, parameter: impl ToList<&'a str>) -> Self {
-        for name in parameter.to_list().into_iter() {
-            self = self.add_parameter(Parameter {
-                name: name.to_string(),
-                param_type: ParameterType::Required,
-            });
-        }
-        self
+    pub fn add_required(self, name: &str, arg_type: ArgumentType) -> Self {
+        self.add_parameter(Parameter::new(name, ParameterType::Required, arg_type))
     }
 
-    pub fn add_optional<'a>(mut self, parameter: impl ToList<&'a str>) -> Self {
-        for name in parameter.to_list().iter() {
-            self = self.add_parameter(Parameter {
-                name: name.to_string(),
-                param_type: ParameterType::Optional,
-            });
-        }
-        self
+    pub fn add_optional(self, name: &str, arg_type: ArgumentType) -> Self {
+        self.add_parameter(Parameter::new(name, ParameterType::Optional, arg_type))
     }
 
     pub fn example(mut self, example: &str) -> Self {
@@ -92,6 +157,11 @@ impl UsageBuilder {
         self
     }
 
+    pub fn decorate(mut self, decoration: Decoration) -> Self {
+        self.decorations.push(decoration);
+        self
+    }
+
     fn build_usage(&self, usage: Option<&Usage>, prefix: &str, trigger: &str) -> String {
 
         let mut usage_string = format!("{}{}", prefix, trigger);
@@ -100,12 +170,7 @@ impl UsageBuilder {
             Some(usage) => {
                 usage.into_iter()
                     .for_each(|param| {
-                        usage_string.push_str(
-                            &match param.param_type {
-                                ParameterType::Constant => format!(" {}", param.name.to_string()),
-                                ParameterType::Required => format!(" ({})", param.name.to_string()),
-                                ParameterType::Optional => format!(" [{}]", param.name.to_string())
-                            })
+                        usage_string.push_str(&format!(" {}", param.display()))
                     })
             },
             None => {},
@@ -114,14 +179,20 @@ impl UsageBuilder {
         usage_string
     }
 
-    pub fn build(&self, prefix: &String) -> String {
+    // the first usage in a single line, e.g. `?warn (user) [reason]`
+    pub fn summary(&self, prefix: &str) -> String {
+        self.build_usage(self.usage.first(), prefix, &self.triggers.head)
+    }
+
+    pub fn build(&self, prefix: &String, locale: Locale) -> String {
 
         let mut usage_strings = Vec::new();
         let trigger = &self.triggers.head;
         let aliases = &self.triggers.tail;
 
         usage_strings.push(
-            format!("**Usage:**\n`{}`",
+            format!("{}\n`{}`",
+                Text::new("usage.usage").localize(locale),
                 match self.usage.is_empty() {
                     true  => self.build_usage(None, prefix, trigger),
                     false => self.usage.iter()
@@ -133,7 +204,8 @@ impl UsageBuilder {
         if !aliases.is_empty() {
             usage_strings.push(
                 format!(
-                    "\n**Aliases**:\n`{}`",
+                    "\n{}\n`{}`",
+                    Text::new("usage.aliases").localize(locale),
                     aliases.join("`, `")));
 
         }
@@ -141,7 +213,8 @@ impl UsageBuilder {
         if let Some(example) = &self.example {
             usage_strings.push(
                 format!(
-                    "\n**Example Usage**:\n`{}{} {}`",
+                    "\n{}\n`{}{} {}`",
+                    Text::new("usage.example").localize(locale),
                     prefix,
                     trigger,
                     example));
@@ -150,4 +223,520 @@ impl UsageBuilder {
         usage_strings.join("\n")
     }
 
+    // ---- Slash Commands ---- //
+
+    // Discord only accepts lowercase names made of letters, digits, - and _
+    fn option_name(name: &str) -> String {
+        let name = name.split(" (").next().unwrap_or(name);
+        let name = name.split(" ..").next().unwrap_or(name);
+        name.trim_start_matches(['-', '+'])
+            .to_lowercase()
+            .chars()
+            .map(|c| match c.is_alphanumeric() || c == '-' || c == '_' {
+                true  => c,
+                false => '-',
+            })
+            .take(32)
+            .collect()
+    }
+
+    fn description(text: &str) -> String {
+        match text.chars().count() > 100 {
+            true  => format!("{}...", text.chars().take(97).collect::<String>()),
+            false => text.to_string(),
+        }
+    }
+
+    // Subcommands are used if every usage starts with its own constant,
+    // like `config get` and `config set`.
+    fn has_subcommands(&self) -> bool {
+        !self.usage.is_empty() && self.usage.iter().all(|usage| usage.first()
+            .is_some_and(|param| param.param_type == ParameterType::Constant && !param.name.starts_with('-')))
+    }
+
+    // The options of the given usages in the order their parameters are
+    // written in. An option is only required if every usage requires it.
+    // Fails if there are too many options or different parameters end up
+    // with the same option name.
+    fn slash_options(&self, usages: &[&[Parameter]]) -> Result<Vec<SlashOption>> {
+
+        let mut options = Vec::<SlashOption>::new();
+        let mut occurrences = Vec::<Vec<String>>::new();
+        // the parameter behind each option, all modes share one option
+        let mut sources = HashMap::<String, &str>::new();
+
+        for usage in usages {
+            let mut names = Vec::new();
+            for param in usage.iter() {
+                let option = match param.arg_type {
+
+                    // constants without a dash select a mode, like `open (user) m`
+                    _ if param.is_literal() => SlashOption {
+                        name: "mode".to_string(),
+                        description: "Mode".to_string(),
+                        kind: CommandOptionType::String,
+                        required: true,
+                        prefix: String::new(),
+                        choices: vec![param.name.clone()],
+                    },
+
+                    // flags can be switched on
+                    ArgumentType::Flag => SlashOption {
+                        name: param.key(),
+                        description: Self::description(&param.name),
+                        kind: CommandOptionType::Boolean,
+                        required: param.param_type == ParameterType::Required,
+                        prefix: param.name.clone(),
+                        choices: Vec::new(),
+                    },
+
+                    arg_type => SlashOption {
+                        name: param.key(),
+                        description: Self::description(&match arg_type {
+                            ArgumentType::Duration => format!("{}, e.g. 1h30m", param.label),
+                            _ => param.label.clone(),
+                        }),
+                        kind: match arg_type {
+                            ArgumentType::User    => CommandOptionType::User,
+                            ArgumentType::Role    => CommandOptionType::Role,
+                            ArgumentType::Channel => CommandOptionType::Channel,
+                            ArgumentType::Integer => CommandOptionType::Integer,
+                            _ => CommandOptionType::String,
+                        },
+                        required: param.param_type == ParameterType::Required,
+                        prefix: match param.is_named() {
+                            true  => format!("{} ", param.name),
+                            false => String::new(),
+                        },
+                        choices: Vec::new(),
+                    },
+                };
+                let source = match param.is_literal() {
+                    true  => "a mode",
+                    false => param.name.as_str(),
+                };
+                match sources.insert(option.name.clone(), source) {
+                    Some(other) if other != source => return Err(format!(
+                        "`{}` and `{}` are both the option `{}`", other, source, option.name)),
+                    _ => {}
+                }
+                names.push(option.name.clone());
+                match options.iter_mut().find(|existing| existing.name == option.name) {
+                    Some(existing) => {
+                        existing.required &= option.required;
+                        existing.choices.extend(option.choices);
+                    }
+                    None => options.push(option),
+                }
+            }
+            occurrences.push(names);
+        }
+
+        for option in options.iter_mut() {
+            option.required &= occurrences.iter().all(|names| names.contains(&option.name));
+        }
+        match options.len() > MAX_OPTIONS {
+            true  => Err(format!("{} options, at most {} are allowed", options.len(), MAX_OPTIONS)),
+            false => Ok(options),
+        }
+    }
+
+    fn create_options(options: Vec<SlashOption>) -> Vec<CreateCommandOption> {
+
+        // Discord expects the required options first
+        let (required, optional): (Vec<SlashOption>, Vec<SlashOption>) = options.into_iter()
+            .partition(|option| option.required);
+        required.into_iter()
+            .chain(optional)
+            .map(|option| {
+                let mut created = CreateCommandOption::new(option.kind, option.name, option.description)
+                    .required(option.required);
+                for choice in option.choices {
+                    created = created.add_string_choice(choice.clone(), choice);
+                }
+                created
+            })
+            .collect()
+    }
+
+    // The slash command that stands for the command, named after its first
+    // trigger. Fails if Discord would reject the command.
+    pub fn application_command(&self) -> Result<CreateCommand> {
+
+        let trigger = &self.triggers.head;
+        let command = CreateCommand::new(trigger)
+            .description(Self::description(&self.build_usage(self.usage.first(), "/", trigger)));
+
+        match self.has_subcommands() {
+            true => {
+                if self.usage.len() > MAX_OPTIONS {
+                    return Err(format!("{} subcommands, at most {} are allowed", self.usage.len(), MAX_OPTIONS));
+                }
+                let mut names = HashSet::new();
+                let mut subcommands = Vec::new();
+                for usage in self.usage.iter() {
+                    let name = Self::option_name(&usage[0].name);
+                    if !names.insert(name.clone()) {
+                        return Err(format!("`{}` is not the only subcommand `{}`", usage[0].name, name));
+                    }
+                    let description = Self::description(&self.build_usage(Some(usage), "/", trigger));
+                    let subcommand = Self::create_options(self.slash_options(&[&usage[1..]])?).into_iter()
+                        .fold(CreateCommandOption::new(CommandOptionType::SubCommand, name, description),
+                            |subcommand, option| subcommand.add_sub_option(option));
+                    subcommands.push(subcommand);
+                }
+                Ok(command.set_options(subcommands))
+            }
+            false => {
+                let usages = self.usage.iter().map(|usage| usage.as_slice()).collect::<Vec<&[Parameter]>>();
+                Ok(command.set_options(Self::create_options(self.slash_options(&usages)?)))
+            }
+        }
+    }
+
+    // Writes the options a slash command was run with as the message the
+    // text command would have been run with.
+    pub fn application_content(&self, prefix: &str, options: &[ResolvedOption]) -> String {
+
+        let mut words = vec![format!("{}{}", prefix, self.triggers.head)];
+        let (usages, options) = match (self.has_subcommands(), options.first()) {
+            (true, Some(ResolvedOption { name, value: ResolvedValue::SubCommand(options), .. })) => {
+                let Some(usage) = self.usage.iter().find(|usage| Self::option_name(&usage[0].name) == *name) else {
+                    return words.join(" ");
+                };
+                words.push(usage[0].name.clone());
+                (vec![&usage[1..]], options.as_slice())
+            }
+            _ => (self.usage.iter().map(|usage| usage.as_slice()).collect(), options),
+        };
+
+        // commands with invalid options are never registered
+        for slash_option in self.slash_options(&usages).unwrap_or_default() {
+            let Some(option) = options.iter().find(|option| option.name == slash_option.name) else {
+                continue;
+            };
+            let value = match &option.value {
+                ResolvedValue::Boolean(true) => String::new(),
+                ResolvedValue::Boolean(false) => continue,
+                ResolvedValue::User(user, _) => format!("<@{}>", user.id),
+                ResolvedValue::Role(role) => format!("<@&{}>", role.id),
+                ResolvedValue::Channel(channel) => format!("<#{}>", channel.id),
+                ResolvedValue::Integer(number) => number.to_string(),
+                ResolvedValue::String(text) => quote(text),
+                _ => continue,
+            };
+            words.push(format!("{}{}", slash_option.prefix, value));
+        }
+        words.join(" ")
+    }
+
+    // ---- Parsing ---- //
+
+    // Parses the words following the trigger of a command into the arguments
+    // of the first usage they fit. If they fit none, the error of the usage
+    // that got the most arguments before failing is returned.
+    pub fn parse(&self, tokens: &[Token]) -> std::result::Result<Arguments, ArgumentError> {
+
+        let mut closest: Option<(usize, ArgumentError)> = None;
+        let usages = match self.usage.is_empty() {
+            true  => vec![&[] as &[Parameter]],
+            false => self.usage.iter().map(|usage| usage.as_slice()).collect(),
+        };
+        for usage in usages {
+            let mut arguments = Arguments::default();
+            match self.parse_usage(usage, tokens, &mut arguments) {
+                Ok(()) => return Ok(arguments),
+                Err(error) => if closest.as_ref().is_none_or(|(progress, _)| arguments.count() > *progress) {
+                    closest = Some((arguments.count(), error));
+                },
+            }
+        }
+        Err(closest.unwrap().1)
+    }
+
+    // Parameters a decorator asks for if they are missing.
+    fn is_decorated(&self, param: &Parameter) -> bool {
+        match param.arg_type {
+            ArgumentType::User    => self.decorations.contains(&Decoration::User),
+            ArgumentType::Integer => self.decorations.contains(&Decoration::Number),
+            _ => false,
+        }
+    }
+
+    fn parse_usage(&self, usage: &[Parameter], tokens: &[Token], arguments: &mut Arguments) -> std::result::Result<(), ArgumentError> {
+
+        let mut consumed = vec![false; tokens.len()];
+        let named = |token: &Token| usage.iter()
+            .find(|param| param.is_named() && token.flag.as_ref() == Some(&param.key()));
+
+        // named parameters, their values are written as `-key=value` or reach
+        // up to the next one, the values of repeated text parameters are joined
+        let mut i = 0;
+        while i < tokens.len() {
+            let Some(param) = named(&tokens[i]) else {
+                i += 1;
+                continue;
+            };
+            let flag = i;
+            consumed[i] = true;
+            i += 1;
+            if param.arg_type == ArgumentType::Flag {
+                if tokens[flag].value.is_some() {
+                    return Err(ArgumentError::new(flag, text!("argument.no-value", parameter = &param.name)));
+                }
+                arguments.insert(param.key(), Argument::Flag);
+                continue;
+            }
+            let (start, values) = match &tokens[flag].value {
+                Some(value) => (flag, vec![value.as_str()]),
+                None => {
+                    let start = i;
+                    while i < tokens.len() && named(&tokens[i]).is_none() {
+                        consumed[i] = true;
+                        i += 1;
+                    }
+                    (start, tokens[start..i].iter().map(|token| token.text.as_str()).collect())
+                }
+            };
+            let argument = match (values.as_slice(), param.arg_type) {
+                ([], _) => return Err(ArgumentError::new(start, text!("argument.missing", parameter = &param.label))),
+                (values, ArgumentType::Text) => Argument::Text(match arguments.text(&param.key()) {
+                    Some(previous) => format!("{} {}", previous, values.join(" ")),
+                    None => values.join(" "),
+                }),
+                ([value], arg_type) => arg_type.parse(value)
+                    .ok_or_else(|| ArgumentError::new(start, arg_type.expectation()))?,
+                (_, _) => return Err(ArgumentError::new(start + 1, "argument.unexpected-word")),
+            };
+            arguments.insert(param.key(), argument);
+        }
+        if let Some(param) = usage.iter().find(|param| param.is_named()
+            && param.param_type == ParameterType::Required && !arguments.has(&param.key())) {
+            return Err(ArgumentError::new(tokens.len(), text!("argument.missing", parameter = param.display())));
+        }
+
+        // flags that are not part of the usage, text starting with a dash
+        // has to be quoted or escaped
+        if let Some(i) = (0..tokens.len()).find(|i| !consumed[*i] && tokens[*i].is_flag()) {
+            return Err(ArgumentError::new(i, "argument.unknown-flag"));
+        }
+
+        // typed parameters take the first word of their type
+        for param in usage.iter().filter(|param| !param.is_named() && !param.is_literal()
+            && param.arg_type != ArgumentType::Text) {
+            let found = (0..tokens.len())
+                .filter(|i| !consumed[*i])
+                .find_map(|i| param.arg_type.parse(&tokens[i].text).map(|argument| (i, argument)));
+            match found {
+                Some((i, argument)) => {
+                    consumed[i] = true;
+                    arguments.insert(param.key(), argument);
+                }
+                None if param.param_type == ParameterType::Optional || self.is_decorated(param) => {}
+                None => {
+                    let position = (0..tokens.len()).find(|i| !consumed[*i]).unwrap_or(tokens.len());
+                    let reason = match position < tokens.len() {
+                        true  => Text::new(param.arg_type.expectation()),
+                        false => text!("argument.missing", parameter = param.display()),
+                    };
+                    return Err(ArgumentError::new(position, reason));
+                }
+            }
+        }
+
+        // constants and text take the remaining words in order, the last
+        // text parameter takes all words that are left
+        let remaining = (0..tokens.len()).filter(|i| !consumed[*i]).collect::<Vec<usize>>();
+        let ordered = usage.iter()
+            .filter(|param| param.is_literal() || (!param.is_named() && param.arg_type == ArgumentType::Text))
+            .collect::<Vec<&Parameter>>();
+        let mut next = 0;
+        for (n, param) in ordered.iter().enumerate() {
+            if param.is_literal() {
+                match remaining.get(next) {
+                    Some(&i) if tokens[i].text.eq_ignore_ascii_case(&param.name) => {
+                        arguments.insert(param.key(), Argument::Flag);
+                        next += 1;
+                    }
+                    Some(&i) => return Err(ArgumentError::new(i, text!("argument.expected", parameter = &param.name))),
+                    None => return Err(ArgumentError::new(tokens.len(), text!("argument.missing", parameter = &param.name))),
+                }
+                continue;
+            }
+            let count = match n + 1 == ordered.len() {
+                true  => remaining.len() - next,
+                false => (remaining.len() - next).min(1),
+            };
+            if count == 0 {
+                if param.param_type == ParameterType::Required {
+                    return Err(ArgumentError::new(tokens.len(), text!("argument.missing", parameter = param.display())));
+                }
+                continue;
+            }
+            let text = remaining[next..next + count].iter()
+                .map(|i| tokens[*i].text.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+            arguments.insert(param.key(), Argument::Text(text));
+            next += count;
+        }
+
+        match remaining.get(next) {
+            Some(&i) => Err(ArgumentError::new(i, "argument.unexpected-word")),
+            None => Ok(()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use serenity::all::CommandData;
+    use serde_json::{json, Value};
+    use nonempty::nonempty;
+
+    use super::*;
+    use crate::commands::*;
+    use crate::commands::command::Command;
+
+    const USER: &str = "123456789012345678";
+
+    // the name, type and whether it is required of every option
+    fn options(command: &Value) -> Vec<(String, u64, bool)> {
+        command["options"].as_array().cloned().unwrap_or_default().iter()
+            .map(|option| (
+                option["name"].as_str().unwrap().to_string(),
+                option["type"].as_u64().unwrap(),
+                option["required"].as_bool().unwrap_or(false)))
+            .collect()
+    }
+
+    fn option(name: &str, kind: CommandOptionType, required: bool) -> (String, u64, bool) {
+        (name.to_string(), u8::from(kind) as u64, required)
+    }
+
+    fn slash_command(usage: &UsageBuilder) -> Value {
+        serde_json::to_value(usage.application_command().unwrap()).unwrap()
+    }
+
+    // the message a slash command run with the given options stands for
+    fn content(usage: &UsageBuilder, options: Value) -> String {
+        let data: CommandData = serde_json::from_value(json!({
+            "id": "1",
+            "name": usage.triggers.head,
+            "type": 1,
+            "options": options,
+            "resolved": { "users": { USER: { "id": USER, "username": "member" } } },
+        })).unwrap();
+        usage.application_content("?", &data.options())
+    }
+
+    fn parse(usage: &UsageBuilder, content: &str) -> Arguments {
+        usage.parse(&tokenize(content)[1..]).unwrap()
+    }
+
+    #[test]
+    fn options_are_only_required_in_every_usage() {
+        let command = slash_command(&UnmuteCommand.define_usage());
+        assert_eq!(command["name"], "unmute");
+        assert_eq!(options(&command), vec![
+            option("user", CommandOptionType::User, true),
+            option("reason", CommandOptionType::String, false),
+            option("flag", CommandOptionType::Boolean, false),
+            option("monthly", CommandOptionType::Boolean, false),
+        ]);
+
+        // named parameters keep their label as the description
+        let command = slash_command(&PollCommand.define_usage());
+        assert_eq!(options(&command), vec![
+            option("title", CommandOptionType::String, true),
+            option("opts", CommandOptionType::String, true),
+        ]);
+        assert_eq!(command["options"][0]["description"], "title");
+    }
+
+    #[test]
+    fn constants_become_modes_or_subcommands() {
+        let usage = UsageBuilder::new(CommandType::Moderation, nonempty!["open".to_string()])
+            .add_required("user", ArgumentType::User)
+            .add_constant("m", false)
+            .new_usage()
+            .add_required("user", ArgumentType::User)
+            .add_constant("t", false);
+        let command = slash_command(&usage);
+        assert_eq!(options(&command), vec![
+            option("user", CommandOptionType::User, true),
+            option("mode", CommandOptionType::String, true),
+        ]);
+        let choices = command["options"][1]["choices"].as_array().unwrap().iter()
+            .map(|choice| choice["value"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(choices, vec!["m", "t"]);
+
+        let command = slash_command(&BackupCommand.define_usage());
+        assert_eq!(options(&command), vec![
+            option("export", CommandOptionType::SubCommand, false),
+            option("import", CommandOptionType::SubCommand, false),
+        ]);
+        assert_eq!(options(&command["options"][0]), vec![option("csv", CommandOptionType::Boolean, false)]);
+    }
+
+    #[test]
+    fn commands_discord_would_reject_fail() {
+        let usage = UsageBuilder::new(CommandType::Moderation, nonempty!["clash".to_string()])
+            .add_required("user", ArgumentType::User)
+            .add_optional("-user", ArgumentType::Text);
+        assert!(usage.application_command().is_err());
+
+        let usage = (0..26).fold(UsageBuilder::new(CommandType::Moderation, nonempty!["many".to_string()]),
+            |usage, index| usage.add_optional(&format!("-option{}", index), ArgumentType::Text));
+        assert!(usage.application_command().is_err());
+
+        // parameters of the same name in several usages are one option
+        assert!(UnmuteCommand.define_usage().application_command().is_ok());
+    }
+
+    #[test]
+    fn slash_commands_are_run_as_messages() {
+        let usage = UnmuteCommand.define_usage();
+        let written = content(&usage, json!([
+            { "name": "user", "type": 6, "value": USER },
+            { "name": "reason", "type": 3, "value": "said -monthly twice" },
+            { "name": "flag", "type": 5, "value": true },
+            { "name": "monthly", "type": 5, "value": false },
+        ]));
+        assert_eq!(written, format!(r#"?unmute <@{}> "said -monthly twice" -flag"#, USER));
+        let arguments = parse(&usage, &written);
+        assert_eq!(arguments.user("user").map(|id| id.to_string()), Some(USER.to_string()));
+        assert!(arguments.has("flag"));
+        assert!(!arguments.has("monthly"));
+        assert_eq!(arguments.text("reason"), Some("said -monthly twice"));
+
+        let usage = PollCommand.define_usage();
+        let written = content(&usage, json!([
+            { "name": "title", "type": 3, "value": "Best color?" },
+            { "name": "opts", "type": 3, "value": "+Red +Blue" },
+        ]));
+        let arguments = parse(&usage, &written);
+        assert_eq!(arguments.text("title"), Some("Best color?"));
+        assert_eq!(arguments.text("opts"), Some("+Red +Blue"));
+    }
+
+    #[test]
+    fn subcommands_are_run_as_messages() {
+        let usage = ConfigCommand.define_usage();
+        let written = content(&usage, json!([
+            { "name": "set", "type": 1, "options": [
+                { "name": "key", "type": 3, "value": "channel_modlogs" },
+                { "name": "value", "type": 3, "value": "1100000000000000000" },
+            ] },
+        ]));
+        assert_eq!(written, "?config set channel_modlogs 1100000000000000000");
+        let arguments = parse(&usage, &written);
+        assert_eq!(arguments.text("key"), Some("channel_modlogs"));
+
+        // unknown subcommands only write the trigger
+        assert_eq!(content(&usage, json!([{ "name": "unknown", "type": 1, "options": [] }])), "?config");
+    }
 }